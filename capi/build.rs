@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+/// Generates the C header of the library in `$OUT_DIR/aries.h`.
+///
+/// The header distributed in `include/aries.h` is a copy of it, that is checked to be up to date by the tests.
+fn main() {
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("Invalid cbindgen configuration");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate the C header")
+        .write_to_file(out_dir.join("aries.h"));
+}
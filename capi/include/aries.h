@@ -0,0 +1,170 @@
+#ifndef ARIES_H
+#define ARIES_H
+
+/* Generated with cbindgen from the `aries_capi` crate, do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Value returned by a call to `solve` when a solution was found.
+#define ARIES_SAT 10
+
+// Value returned by a call to `solve` when the problem was proven unsatisfiable.
+#define ARIES_UNSAT 20
+
+// Value returned by a call to `solve` when the search was interrupted.
+#define ARIES_UNKNOWN 0
+
+// Status returned by a function that completed successfully.
+#define ARIES_OK 0
+
+// Status returned by a function called with invalid arguments (e.g. an unknown variable).
+#define ARIES_ERROR -1
+
+// An opaque solver handle.
+typedef struct AriesSolver AriesSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the name and version of the solver.
+const char *aries_version(void);
+
+// Creates a new solver, to be released with `aries_free`.
+struct AriesSolver *aries_new(void);
+
+// Releases a solver.
+//
+// # Safety
+//
+// The solver must have been created by `aries_new` and not released before.
+void aries_free(struct AriesSolver *solver);
+
+// Creates a new integer variable with the domain `[lb, ub]` and returns its identifier
+// (or `ARIES_ERROR` if the domain is empty).
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int32_t aries_new_int_var(struct AriesSolver *s, int32_t lb, int32_t ub);
+
+// Posts the difference constraint `b - a <= ub`.
+// Returns `ARIES_OK`, or `ARIES_ERROR` if a variable is unknown.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int aries_add_difference(struct AriesSolver *s, int32_t a, int32_t b, int32_t ub);
+
+// Posts the linear constraint `sum_i coefs[i] * vars[i] <= ub`, where `vars` and `coefs` have `n` elements.
+// Returns `ARIES_OK`, or `ARIES_ERROR` if a variable is unknown.
+//
+// # Safety
+//
+// The solver must be a valid handle, and `vars` and `coefs` must point to arrays of at least `n` elements.
+int aries_add_linear(struct AriesSolver *s,
+                     size_t n,
+                     const int32_t *vars,
+                     const int32_t *coefs,
+                     int32_t ub);
+
+// Posts a clause over DIMACS literals of the IPASIR interface, where `lits` has `n` elements.
+// Returns `ARIES_OK`, or `ARIES_ERROR` if a literal is `0`.
+//
+// # Safety
+//
+// The solver must be a valid handle and `lits` must point to an array of at least `n` elements.
+int aries_add_clause(struct AriesSolver *s, size_t n, const int32_t *lits);
+
+// Solves the problem under the current assumptions.
+// Returns `ARIES_SAT` (10), `ARIES_UNSAT` (20), `ARIES_UNKNOWN` (0) if the search was interrupted
+// or `ARIES_ERROR` for a null handle.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int aries_solve(struct AriesSolver *s);
+
+// Writes in `value` the value of an integer variable in the solution found by the last call to solve.
+// Returns `ARIES_OK`, or `ARIES_ERROR` if the variable is unknown or the last call to solve did not return `ARIES_SAT`.
+//
+// # Safety
+//
+// The solver must be a valid handle and `value` must be a valid pointer.
+int aries_int_value(struct AriesSolver *s,
+                    int32_t var,
+                    int32_t *value);
+
+// Returns the name and version of the solver.
+const char *ipasir_signature(void);
+
+// Creates a new solver, to be released with `ipasir_release`.
+void *ipasir_init(void);
+
+// Releases a solver.
+//
+// # Safety
+//
+// The solver must have been created by `ipasir_init` and not released before.
+void ipasir_release(void *solver);
+
+// Adds a literal to the clause currently being built, or finalizes the clause if `lit_or_zero` is zero.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+void ipasir_add(void *s,
+                int lit_or_zero);
+
+// Adds an assumption for the next call to `ipasir_solve`.
+// Assumptions are cleared after each call to `ipasir_solve`.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+void ipasir_assume(void *s, int lit);
+
+// Solves the formula under the current assumptions.
+// Returns 10 if satisfiable, 20 if unsatisfiable and 0 if the search was interrupted.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int ipasir_solve(void *s);
+
+// Returns the value of a literal in the solution found by the last call to `ipasir_solve`:
+// `lit` if it is true, `-lit` if it is false and `0` if its value is irrelevant.
+// Returns `0` if the last call to `ipasir_solve` did not return 10.
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int ipasir_val(void *s, int lit);
+
+// Returns 1 if the assumption `lit` was used to prove the unsatisfiability in the last call to `ipasir_solve`,
+// and 0 otherwise (including when the last call to `ipasir_solve` did not return 20).
+//
+// # Safety
+//
+// The solver must be a valid handle.
+int ipasir_failed(void *s,
+                  int lit);
+
+// Sets a callback that is polled during search, and that should return a non-zero value to stop the search.
+// A null callback removes the current one.
+//
+// Note that the callback is invoked from a separate thread while the solver is running.
+//
+// # Safety
+//
+// The solver must be a valid handle, and `data` must remain valid as long as the callback is set.
+void ipasir_set_terminate(void *s,
+                          void *data,
+                          int (*terminate)(void *data));
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ARIES_H */
@@ -0,0 +1,148 @@
+//! Implementation of the IPASIR interface for incremental SAT solving.
+//!
+//! All functions take as first parameter a solver handle created by `ipasir_init`.
+//! Variables are created on demand, when they first appear in a clause or an assumption.
+//!
+//! Calls that are invalid in the IPASIR specification (e.g. assuming the literal `0` or querying the value of a literal
+//! after an unsatisfiable call) are ignored, and the functions returning a value return `0`.
+
+use crate::{solver, AriesSolver};
+use aries::model::lang::expr::or;
+use std::ffi::{c_char, c_int, c_void};
+
+/// Returns the name and version of the solver.
+#[no_mangle]
+pub extern "C" fn ipasir_signature() -> *const c_char {
+    crate::aries_version()
+}
+
+/// Creates a new solver, to be released with `ipasir_release`.
+#[no_mangle]
+pub extern "C" fn ipasir_init() -> *mut c_void {
+    crate::aries_new() as *mut c_void
+}
+
+/// Releases a solver.
+///
+/// # Safety
+///
+/// The solver must have been created by `ipasir_init` and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_release(solver: *mut c_void) {
+    crate::aries_free(solver as *mut AriesSolver)
+}
+
+/// Adds a literal to the clause currently being built, or finalizes the clause if `lit_or_zero` is zero.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_add(s: *mut c_void, lit_or_zero: c_int) {
+    let Some(s) = solver(s) else {
+        return;
+    };
+    s.reset();
+    match s.lit(lit_or_zero) {
+        Some(lit) => s.clause.push(lit),
+        None => {
+            let clause = std::mem::take(&mut s.clause);
+            s.solver.enforce(or(clause), []);
+        }
+    }
+}
+
+/// Adds an assumption for the next call to `ipasir_solve`.
+/// Assumptions are cleared after each call to `ipasir_solve`.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_assume(s: *mut c_void, lit: c_int) {
+    let Some(s) = solver(s) else {
+        return;
+    };
+    s.reset();
+    if let Some(lit) = s.lit(lit) {
+        s.assumptions.push(lit);
+    }
+}
+
+/// Solves the formula under the current assumptions.
+/// Returns 10 if satisfiable, 20 if unsatisfiable and 0 if the search was interrupted.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_solve(s: *mut c_void) -> c_int {
+    match solver(s) {
+        Some(s) => s.solve(),
+        None => 0,
+    }
+}
+
+/// Returns the value of a literal in the solution found by the last call to `ipasir_solve`:
+/// `lit` if it is true, `-lit` if it is false and `0` if its value is irrelevant.
+/// Returns `0` if the last call to `ipasir_solve` did not return 10.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_val(s: *mut c_void, lit: c_int) -> c_int {
+    let Some(s) = solver(s) else {
+        return 0;
+    };
+    let Some(l) = s.existing_lit(lit) else {
+        return 0; // variable that does not appear in the formula
+    };
+    let Some(solution) = s.solution() else {
+        return 0;
+    };
+    if solution.entails(l) {
+        lit
+    } else if solution.entails(!l) {
+        -lit
+    } else {
+        0
+    }
+}
+
+/// Returns 1 if the assumption `lit` was used to prove the unsatisfiability in the last call to `ipasir_solve`,
+/// and 0 otherwise (including when the last call to `ipasir_solve` did not return 20).
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_failed(s: *mut c_void, lit: c_int) -> c_int {
+    let Some(s) = solver(s) else {
+        return 0;
+    };
+    let l = s.existing_lit(lit);
+    match &s.result {
+        Some(Err(core)) => l.is_some_and(|l| core.literals().contains(&l)) as c_int,
+        _ => 0,
+    }
+}
+
+/// Sets a callback that is polled during search, and that should return a non-zero value to stop the search.
+/// A null callback removes the current one.
+///
+/// Note that the callback is invoked from a separate thread while the solver is running.
+///
+/// # Safety
+///
+/// The solver must be a valid handle, and `data` must remain valid as long as the callback is set.
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_terminate(
+    s: *mut c_void,
+    data: *mut c_void,
+    terminate: Option<extern "C" fn(data: *mut c_void) -> c_int>,
+) {
+    if let Some(s) = solver(s) {
+        s.terminate = terminate.map(|callback| (data, callback));
+    }
+}
@@ -0,0 +1,348 @@
+//! C API of the aries solver.
+//!
+//! The library exposes an opaque solver handle that can be used through two sets of functions:
+//!
+//!  - the [IPASIR](https://github.com/biotomas/ipasir) interface (`ipasir_*` functions) for incremental SAT solving,
+//!    where boolean variables are identified by positive integers and literals by non-zero integers as in DIMACS,
+//!  - the `aries_*` functions, to create integer variables and post difference and linear constraints on them.
+//!
+//! Both interfaces can be mixed on the same solver: the clauses of the IPASIR interface are solved together with the
+//! integer constraints, and `aries_solve` is equivalent to `ipasir_solve`.
+//!
+//! Functions never panic on invalid inputs (e.g. an unknown variable or a null handle), which would abort the host
+//! process. Instead, the `aries_*` functions report them with the `ARIES_ERROR` status and the IPASIR functions
+//! ignore them.
+//!
+//! The C header `include/aries.h` is generated from this crate by `cbindgen` (see the README of the crate).
+
+use aries::backtrack::Backtrack;
+use aries::core::{Lit, VarRef};
+use aries::model::extensions::SavedAssignment;
+use aries::model::lang::expr::{leq, or};
+use aries::model::lang::linear::LinearSum;
+use aries::model::lang::{BVar, IVar};
+use aries::solver::parallel::signals::{InputSignal, InputStream};
+use aries::solver::{Exit, Solver, UnsatCore};
+use std::ffi::{c_char, c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+mod ipasir;
+
+pub use ipasir::*;
+
+/// Value returned by a call to `solve` when a solution was found.
+pub const ARIES_SAT: c_int = 10;
+/// Value returned by a call to `solve` when the problem was proven unsatisfiable.
+pub const ARIES_UNSAT: c_int = 20;
+/// Value returned by a call to `solve` when the search was interrupted.
+pub const ARIES_UNKNOWN: c_int = 0;
+/// Status returned by a function that completed successfully.
+pub const ARIES_OK: c_int = 0;
+/// Status returned by a function called with invalid arguments (e.g. an unknown variable).
+pub const ARIES_ERROR: c_int = -1;
+
+/// Callback polled during search that should return a non-zero value to stop the search.
+type TerminateCallback = extern "C" fn(data: *mut c_void) -> c_int;
+
+/// An opaque solver handle.
+pub struct AriesSolver {
+    solver: Solver<String>,
+    /// Boolean variables of the IPASIR interface, the variable `i` being at index `i - 1`
+    bool_vars: Vec<BVar>,
+    int_vars: Vec<IVar>,
+    /// Clause currently being built with `ipasir_add`
+    clause: Vec<Lit>,
+    /// Assumptions for the next call to solve
+    assumptions: Vec<Lit>,
+    /// Result of the last call to solve
+    result: Option<Result<Arc<SavedAssignment>, UnsatCore>>,
+    terminate: Option<(*mut c_void, TerminateCallback)>,
+}
+
+impl AriesSolver {
+    fn new() -> Self {
+        AriesSolver {
+            solver: Solver::new(Default::default()),
+            bool_vars: Vec::new(),
+            int_vars: Vec::new(),
+            clause: Vec::new(),
+            assumptions: Vec::new(),
+            result: None,
+            terminate: None,
+        }
+    }
+
+    /// Returns the solver literal corresponding to a DIMACS literal, creating the variable if needed.
+    /// Returns `None` for the invalid literal `0`.
+    fn lit(&mut self, dimacs: i32) -> Option<Lit> {
+        if dimacs == 0 {
+            return None;
+        }
+        let var = dimacs.unsigned_abs() as usize;
+        while self.bool_vars.len() < var {
+            let label = format!("b{}", self.bool_vars.len() + 1);
+            let v = self.solver.model.new_bvar(label);
+            self.bool_vars.push(v);
+        }
+        let lit = self.bool_vars[var - 1].true_lit();
+        Some(if dimacs > 0 { lit } else { !lit })
+    }
+
+    /// Returns the solver literal corresponding to a DIMACS literal, if its variable was already created.
+    fn existing_lit(&self, dimacs: i32) -> Option<Lit> {
+        let var = dimacs.unsigned_abs() as usize;
+        let lit = self.bool_vars.get(var.checked_sub(1)?)?.true_lit();
+        Some(if dimacs > 0 { lit } else { !lit })
+    }
+
+    fn int_var(&self, id: i32) -> Option<IVar> {
+        usize::try_from(id).ok().and_then(|i| self.int_vars.get(i)).copied()
+    }
+
+    /// Brings back the solver to the root level after a call to solve, so that new constraints can be added.
+    fn reset(&mut self) {
+        if self.result.take().is_some() {
+            self.solver.reset();
+        }
+    }
+
+    fn solve(&mut self) -> c_int {
+        self.reset();
+        let assumptions = std::mem::take(&mut self.assumptions);
+        let watcher = self
+            .terminate
+            .map(|(data, callback)| Watcher::start(SendPtr(data), callback, self.solver.input_stream()));
+        let result = self.solver.solve_with_assumptions(assumptions);
+        if let Some(watcher) = watcher {
+            watcher.stop();
+            // the callback may have requested an interruption after the end of the search
+            self.solver.clear_interruptions();
+        }
+        match result {
+            Ok(result) => {
+                let status = if result.is_ok() { ARIES_SAT } else { ARIES_UNSAT };
+                self.result = Some(result);
+                status
+            }
+            Err(Exit::Interrupted) => {
+                self.solver.reset();
+                ARIES_UNKNOWN
+            }
+            Err(Exit::MovedBySymmetries(_)) => unreachable!("symmetry breaking is not enabled through the C API"),
+        }
+    }
+
+    /// Returns the solution found by the last call to solve, if it returned SAT.
+    fn solution(&self) -> Option<&SavedAssignment> {
+        match &self.result {
+            Some(Ok(solution)) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+/// A raw pointer that is passed to the thread polling the termination callback.
+struct SendPtr(*mut c_void);
+unsafe impl Send for SendPtr {}
+
+/// A thread that polls the termination callback during search and interrupts the solver when requested.
+struct Watcher {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl Watcher {
+    fn start(data: SendPtr, callback: TerminateCallback, input: InputStream) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let data = data;
+            while !stopped.load(Ordering::Acquire) {
+                if callback(data.0) != 0 {
+                    let _ = input.sender.send(InputSignal::Interrupt);
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        });
+        Watcher { stop, handle }
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::Release);
+        let _ = self.handle.join();
+    }
+}
+
+/// Returns a reference to the solver behind a handle, or `None` if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or have been created by `aries_new` (or `ipasir_init`) and not released.
+unsafe fn solver<'a>(handle: *mut c_void) -> Option<&'a mut AriesSolver> {
+    (handle as *mut AriesSolver).as_mut()
+}
+
+/// Returns the name and version of the solver.
+#[no_mangle]
+pub extern "C" fn aries_version() -> *const c_char {
+    concat!("aries-", env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Creates a new solver, to be released with `aries_free`.
+#[no_mangle]
+pub extern "C" fn aries_new() -> *mut AriesSolver {
+    Box::into_raw(Box::new(AriesSolver::new()))
+}
+
+/// Releases a solver.
+///
+/// # Safety
+///
+/// The solver must have been created by `aries_new` and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn aries_free(solver: *mut AriesSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Creates a new integer variable with the domain `[lb, ub]` and returns its identifier
+/// (or `ARIES_ERROR` if the domain is empty).
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn aries_new_int_var(s: *mut AriesSolver, lb: i32, ub: i32) -> i32 {
+    let Some(s) = solver(s as *mut c_void) else {
+        return ARIES_ERROR;
+    };
+    if lb > ub {
+        return ARIES_ERROR;
+    }
+    s.reset();
+    let label = format!("i{}", s.int_vars.len());
+    let var = s.solver.model.new_ivar(lb, ub, label);
+    s.int_vars.push(var);
+    (s.int_vars.len() - 1) as i32
+}
+
+/// Posts the difference constraint `b - a <= ub`.
+/// Returns `ARIES_OK`, or `ARIES_ERROR` if a variable is unknown.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn aries_add_difference(s: *mut AriesSolver, a: i32, b: i32, ub: i32) -> c_int {
+    let Some(s) = solver(s as *mut c_void) else {
+        return ARIES_ERROR;
+    };
+    let (Some(a), Some(b)) = (s.int_var(a), s.int_var(b)) else {
+        return ARIES_ERROR;
+    };
+    s.reset();
+    s.solver.enforce(leq(b, a + ub), []);
+    ARIES_OK
+}
+
+/// Posts the linear constraint `sum_i coefs[i] * vars[i] <= ub`, where `vars` and `coefs` have `n` elements.
+/// Returns `ARIES_OK`, or `ARIES_ERROR` if a variable is unknown.
+///
+/// # Safety
+///
+/// The solver must be a valid handle, and `vars` and `coefs` must point to arrays of at least `n` elements.
+#[no_mangle]
+pub unsafe extern "C" fn aries_add_linear(
+    s: *mut AriesSolver,
+    n: usize,
+    vars: *const i32,
+    coefs: *const i32,
+    ub: i32,
+) -> c_int {
+    let Some(s) = solver(s as *mut c_void) else {
+        return ARIES_ERROR;
+    };
+    let (vars, coefs) = if n == 0 {
+        (&[][..], &[][..])
+    } else {
+        (
+            std::slice::from_raw_parts(vars, n),
+            std::slice::from_raw_parts(coefs, n),
+        )
+    };
+    let mut sum = LinearSum::zero();
+    for (&v, &c) in vars.iter().zip(coefs) {
+        let Some(v) = s.int_var(v) else {
+            return ARIES_ERROR;
+        };
+        sum += v * c;
+    }
+    s.reset();
+    s.solver.enforce(sum.leq(ub), []);
+    ARIES_OK
+}
+
+/// Posts a clause over DIMACS literals of the IPASIR interface, where `lits` has `n` elements.
+/// Returns `ARIES_OK`, or `ARIES_ERROR` if a literal is `0`.
+///
+/// # Safety
+///
+/// The solver must be a valid handle and `lits` must point to an array of at least `n` elements.
+#[no_mangle]
+pub unsafe extern "C" fn aries_add_clause(s: *mut AriesSolver, n: usize, lits: *const i32) -> c_int {
+    let Some(s) = solver(s as *mut c_void) else {
+        return ARIES_ERROR;
+    };
+    let lits = if n == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(lits, n)
+    };
+    if lits.contains(&0) {
+        return ARIES_ERROR;
+    }
+    s.reset();
+    let clause: Vec<Lit> = lits.iter().filter_map(|&l| s.lit(l)).collect();
+    s.solver.enforce(or(clause), []);
+    ARIES_OK
+}
+
+/// Solves the problem under the current assumptions.
+/// Returns `ARIES_SAT` (10), `ARIES_UNSAT` (20), `ARIES_UNKNOWN` (0) if the search was interrupted
+/// or `ARIES_ERROR` for a null handle.
+///
+/// # Safety
+///
+/// The solver must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn aries_solve(s: *mut AriesSolver) -> c_int {
+    match solver(s as *mut c_void) {
+        Some(s) => s.solve(),
+        None => ARIES_ERROR,
+    }
+}
+
+/// Writes in `value` the value of an integer variable in the solution found by the last call to solve.
+/// Returns `ARIES_OK`, or `ARIES_ERROR` if the variable is unknown or the last call to solve did not return `ARIES_SAT`.
+///
+/// # Safety
+///
+/// The solver must be a valid handle and `value` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aries_int_value(s: *mut AriesSolver, var: i32, value: *mut i32) -> c_int {
+    let Some(s) = solver(s as *mut c_void) else {
+        return ARIES_ERROR;
+    };
+    match (s.int_var(var), s.solution(), value.as_mut()) {
+        (Some(var), Some(solution), Some(value)) => {
+            *value = solution.lb(VarRef::from(var));
+            ARIES_OK
+        }
+        _ => ARIES_ERROR,
+    }
+}
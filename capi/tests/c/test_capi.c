@@ -0,0 +1,120 @@
+/* Test of the C API, compiled and run by `tests/c_program.rs`. */
+#include <stdio.h>
+#include <stdlib.h>
+#include "aries.h"
+
+#define CHECK(cond)                                                                                                    \
+    do {                                                                                                               \
+        if (!(cond)) {                                                                                                 \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond);                                    \
+            exit(1);                                                                                                   \
+        }                                                                                                              \
+    } while (0)
+
+static int always_terminate(void *data) {
+    int *calls = (int *)data;
+    *calls += 1;
+    return 1;
+}
+
+static void test_ipasir(void) {
+    void *s = ipasir_init();
+    CHECK(ipasir_signature() != NULL);
+
+    /* (1 or 2) and (-1 or 2) and (-2 or 3) */
+    ipasir_add(s, 1), ipasir_add(s, 2), ipasir_add(s, 0);
+    ipasir_add(s, -1), ipasir_add(s, 2), ipasir_add(s, 0);
+    ipasir_add(s, -2), ipasir_add(s, 3), ipasir_add(s, 0);
+    CHECK(ipasir_solve(s) == 10);
+    CHECK(ipasir_val(s, 2) == 2);
+    CHECK(ipasir_val(s, -3) == 3);
+
+    /* assuming -3 makes the formula unsatisfiable, independently of 1 */
+    ipasir_assume(s, 1);
+    ipasir_assume(s, -3);
+    CHECK(ipasir_solve(s) == 20);
+    CHECK(ipasir_failed(s, -3) == 1);
+    CHECK(ipasir_failed(s, 1) == 0);
+
+    /* assumptions are cleared after each call */
+    CHECK(ipasir_solve(s) == 10);
+
+    /* incremental addition of clauses */
+    ipasir_add(s, -3), ipasir_add(s, 0);
+    CHECK(ipasir_solve(s) == 20);
+
+    ipasir_release(s);
+}
+
+static void test_terminate(void) {
+    void *s = ipasir_init();
+    int calls = 0;
+    /* pigeonhole problem with 9 pigeons and 8 holes, too hard to be solved before the first call to the callback */
+    const int pigeons = 9, holes = 8;
+    for (int p = 0; p < pigeons; p++) {
+        for (int h = 0; h < holes; h++) {
+            ipasir_add(s, 1 + p * holes + h);
+        }
+        ipasir_add(s, 0);
+    }
+    for (int h = 0; h < holes; h++) {
+        for (int p1 = 0; p1 < pigeons; p1++) {
+            for (int p2 = p1 + 1; p2 < pigeons; p2++) {
+                ipasir_add(s, -(1 + p1 * holes + h));
+                ipasir_add(s, -(1 + p2 * holes + h));
+                ipasir_add(s, 0);
+            }
+        }
+    }
+    ipasir_set_terminate(s, &calls, always_terminate);
+    CHECK(ipasir_solve(s) == 0);
+    CHECK(calls >= 1);
+    ipasir_release(s);
+}
+
+static void test_integer_constraints(void) {
+    AriesSolver *s = aries_new();
+    int32_t x = aries_new_int_var(s, 0, 10);
+    int32_t y = aries_new_int_var(s, 0, 10);
+    int32_t z = aries_new_int_var(s, 0, 10);
+
+    /* y - x <= -3  (x >= y + 3) */
+    aries_add_difference(s, x, y, -3);
+    /* x + y + z >= 12  <=>  -x - y - z <= -12 */
+    int32_t vars[] = {x, y, z};
+    int32_t coefs[] = {-1, -1, -1};
+    aries_add_linear(s, 3, vars, coefs, -12);
+    /* z <= 2 */
+    int32_t one[] = {1};
+    aries_add_linear(s, 1, &z, one, 2);
+    CHECK(aries_solve(s) == ARIES_SAT);
+
+    int32_t vx, vy, vz;
+    CHECK(aries_int_value(s, x, &vx) == ARIES_OK);
+    CHECK(aries_int_value(s, y, &vy) == ARIES_OK);
+    CHECK(aries_int_value(s, z, &vz) == ARIES_OK);
+    CHECK(vx >= vy + 3);
+    CHECK(vx + vy + vz >= 12);
+    CHECK(vz <= 2);
+
+    /* invalid calls are reported instead of aborting */
+    int32_t unknown = 42;
+    CHECK(aries_int_value(s, unknown, &vx) == ARIES_ERROR);
+    CHECK(aries_add_difference(s, x, unknown, 0) == ARIES_ERROR);
+    CHECK(aries_add_linear(s, 1, &unknown, one, 0) == ARIES_ERROR);
+    CHECK(aries_new_int_var(s, 1, 0) == ARIES_ERROR);
+
+    /* x <= 5 makes it unsatisfiable: x + y <= 5 + 2 */
+    CHECK(aries_add_linear(s, 1, &x, one, 5) == ARIES_OK);
+    CHECK(aries_solve(s) == ARIES_UNSAT);
+    CHECK(aries_int_value(s, x, &vx) == ARIES_ERROR);
+    aries_free(s);
+}
+
+int main(void) {
+    test_ipasir();
+    test_terminate();
+    test_integer_constraints();
+    printf("All C API tests passed\n");
+    return 0;
+}
@@ -0,0 +1,60 @@
+//! Compiles and runs the C program `tests/c/test_capi.c` against the shared library of the crate.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory in which cargo places the shared library of the crate (next to the test executable).
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().to_path_buf()
+}
+
+/// Header generated by the build script.
+const GENERATED_HEADER: &str = concat!(env!("OUT_DIR"), "/aries.h");
+
+#[test]
+fn test_header_is_up_to_date() {
+    let header = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/aries.h");
+    let generated = std::fs::read_to_string(GENERATED_HEADER).unwrap();
+    let distributed = std::fs::read_to_string(&header).unwrap_or_default();
+    assert!(
+        generated == distributed,
+        "The header {} is outdated, update it with:\n  cp {GENERATED_HEADER} {}",
+        header.display(),
+        header.display()
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_c_program() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = library_dir();
+    let exe = lib_dir.join("test_capi");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let compilation = Command::new(&compiler)
+        .arg(crate_dir.join("tests/c/test_capi.c"))
+        .arg("-I")
+        .arg(Path::new(GENERATED_HEADER).parent().unwrap())
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-laries_capi")
+        .arg("-o")
+        .arg(&exe)
+        .status();
+    match compilation {
+        Ok(status) => assert!(status.success(), "Compilation of the C program failed"),
+        Err(e) => {
+            eprintln!("Skipping the C API test, could not run the C compiler `{compiler}`: {e}");
+            return;
+        }
+    }
+    let output = Command::new(&exe)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .env("DYLD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    println!("{}", String::from_utf8_lossy(&output.stdout));
+    eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success());
+}
@@ -0,0 +1,86 @@
+#!/usr/bin/python3
+
+# Script that should be run from the root of the repository.
+# It compares learnt clause database policies of the SAT solver on the example instances
+# (plain CNF files in examples/sat/instances/{sat,unsat} and, if available, the instances of the
+# examples/sat/instances/{test-sat.zip, test-unsat.zip} archives).
+#
+# For each policy, it reports the number of solved instances and the average number of conflicts per second.
+#
+# Usage: ci/sat_clause_db.py [TIMEOUT_SECONDS]
+#
+# The output of a run with a 20s timeout is recorded in ci/sat_clause_db.results.txt.
+
+import json
+import os
+import subprocess
+import sys
+import tempfile
+import time
+import zipfile
+
+os.system("cargo build --profile ci --bin aries-sat")
+solver = "target/ci/aries-sat"
+
+timeout = int(sys.argv[1]) if len(sys.argv) > 1 else 30
+
+# clause database parameters of each policy, as given in the `clauses` table of a portfolio worker
+policies = {
+    # single tier: clauses with LBD <= 4 are kept, half of the others are removed on each reduction
+    "single-tier": "{ locked-lbd-level = 4, tier2-lbd-level = 4 }",
+    # core (LBD <= 4), tier-2 (LBD <= 6) and local tiers
+    "three-tiers": "{ locked-lbd-level = 4, tier2-lbd-level = 6 }",
+}
+
+
+def instances():
+    """Yields tuples `(source, instance)` to be passed to the solver."""
+    for directory in ["examples/sat/instances/sat", "examples/sat/instances/unsat"]:
+        for instance in sorted(os.listdir(directory)):
+            yield directory, instance
+    for archive in ["examples/sat/instances/test-sat.zip", "examples/sat/instances/test-unsat.zip"]:
+        if zipfile.is_zipfile(archive):
+            for instance in zipfile.ZipFile(archive).namelist():
+                yield archive, instance
+
+
+def run(portfolio, source, instance):
+    """Returns the status and the global statistics of the solver on the instance, or None if the solver failed."""
+    with tempfile.NamedTemporaryFile(suffix=".json") as stats_file:
+        cmd = [solver, "--timeout", str(timeout), "--portfolio", portfolio, "--stats-json", stats_file.name,
+               "--source", source, instance]
+        solver_run = subprocess.run(cmd, stdout=subprocess.DEVNULL, stderr=subprocess.DEVNULL)
+        if solver_run.returncode != 0:
+            return None
+        stats = json.load(stats_file)
+    return stats["status"], stats["workers"][0]
+
+
+print(f"{'policy':<14}{'instance':<40}{'status':<16}{'conflicts':>12}{'time (s)':>10}{'conflicts/s':>14}")
+summary = {}
+for name, clauses in policies.items():
+    with tempfile.NamedTemporaryFile("w", suffix=".toml", delete=False) as portfolio:
+        portfolio.write(f'[[worker]]\nbrancher = {{ type = "custom", name = "" }}\nclauses = {clauses}\n')
+    solved = 0
+    conflicts_per_sec = []
+    for source, instance in instances():
+        label = f"{os.path.basename(source)}/{instance}"
+        start = time.time()
+        result = run(portfolio.name, source, instance)
+        runtime = time.time() - start
+        if result is None:
+            print(f"{name:<14}{label:<40}{'ERROR':<16}")
+            continue
+        status, stats = result
+        rate = stats["conflicts"] / stats["solve_time"] if stats["solve_time"] > 0 else 0
+        conflicts_per_sec.append(rate)
+        if status != "TIMEOUT":
+            solved += 1
+        print(f"{name:<14}{label:<40}{status:<16}{stats['conflicts']:>12}{runtime:>10.2f}{rate:>14.0f}")
+    os.remove(portfolio.name)
+    summary[name] = (solved, len(conflicts_per_sec), sum(conflicts_per_sec) / len(conflicts_per_sec))
+
+print()
+print(f"{'policy':<14}{'solved':>10}{'avg conflicts/s':>18}")
+for name, (solved, total, rate) in summary.items():
+    print(f"{name:<14}{f'{solved}/{total}':>10}{rate:>18.0f}")
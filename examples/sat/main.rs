@@ -3,6 +3,7 @@
 use anyhow::*;
 use aries::core::Lit;
 use aries::model::lang::expr::or;
+use aries::solver::parallel::portfolio::Portfolio;
 use aries::solver::parallel::{ParSolver, SolverResult};
 use aries::solver::search::combinators::{RoundRobin, WithGeomRestart};
 use aries::solver::search::conflicts::{ConflictBasedBrancher, Params};
@@ -30,6 +31,13 @@ struct Opt {
     timeout: Option<u64>,
     #[structopt(long, short, default_value = "")]
     search: String,
+    /// Path to a TOML file describing the configuration of each parallel worker.
+    /// If provided, the `--search` option is ignored.
+    #[structopt(long)]
+    portfolio: Option<PathBuf>,
+    /// If provided, the statistics of all workers will be written as JSON to this file upon termination.
+    #[structopt(long)]
+    stats_json: Option<PathBuf>,
 }
 
 enum Source {
@@ -104,72 +112,92 @@ fn solve_multi_threads(model: Model, opt: &Opt, deadline: Option<Instant>) -> Re
     let choices: Vec<_> = model.state.variables().map(|v| Lit::geq(v, 1)).collect();
     let solver = Box::new(Solver::new(model));
 
-    let search_params: Vec<_> = opt.search.split(',').collect();
-    let num_threads = search_params.len();
+    let mut par_solver = if let Some(portfolio) = &opt.portfolio {
+        // custom strategies of the portfolio use the same syntax as the `--search` option
+        let portfolio = Portfolio::from_file(portfolio)?;
+        portfolio.build_solver(solver, |conf, solver| configure_search(conf, &choices, solver))?
+    } else {
+        let search_params: Vec<_> = opt.search.split(',').collect();
+        let num_threads = search_params.len();
+        ParSolver::try_new(solver, num_threads, |id, solver| {
+            configure_search(search_params[id], &choices, solver)
+        })?
+    };
+
+    let result = par_solver.solve(deadline);
+    let status = match &result {
+        SolverResult::Sol(_) => "SATISFIED",
+        SolverResult::Unsat => "UNSATISFIABLE",
+        SolverResult::Timeout(_) => "TIMEOUT",
+    };
+    println!("> {status}");
+    par_solver.print_stats();
+    if let Some(stats_file) = &opt.stats_json {
+        let stats = serde_json::json!({
+            "status": status,
+            "workers": par_solver.stats_reports(),
+        });
+        let file = File::create(stats_file).with_context(|| format!("Could not create {}", stats_file.display()))?;
+        serde_json::to_writer_pretty(file, &stats)?;
+    }
 
+    match result {
+        SolverResult::Sol(_) if opt.expected_satisfiability == Some(false) => {
+            eprintln!("Error: expected UNSAT but got SAT");
+            std::process::exit(1);
+        }
+        SolverResult::Unsat if opt.expected_satisfiability == Some(true) => {
+            eprintln!("Error: expected SAT but got UNSAT");
+            std::process::exit(1);
+        }
+        SolverResult::Timeout(_) if opt.expected_satisfiability.is_some() => {
+            eprintln!("Error: could not conclude on SAT or UNSAT within the allocated time");
+            std::process::exit(1);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Sets up the search of a solver that alternates between a stable and a focused mode.
+/// The configuration is of the form `STABLE_PARAMS/FOCUSED_PARAMS`, where the parameters of each mode
+/// are a `:`-separated list of options of the conflict-based brancher.
+fn configure_search(conf: &str, choices: &[Lit], solver: &mut Solver<String>) -> Result<()> {
     let conflict_params = |conf: &str| {
         let mut params = Params::default();
         for opt in conf.split(':') {
             let handled = params.configure(opt);
             if !handled {
-                panic!("UNSUPPORTED OPTION: {opt}")
+                bail!("UNSUPPORTED OPTION: {opt}")
             }
         }
-        params
+        Ok(params)
     };
 
-    let mut par_solver = ParSolver::new(solver, num_threads, |id, solver| {
-        let search_params: Vec<_> = search_params[id].split('/').collect();
-        let stable_params = if !search_params.is_empty() {
-            search_params[0]
-        } else {
-            "+lrb:+p+l:-neg"
-        };
-        let focused_params = if search_params.len() > 1 {
-            search_params[1]
-        } else {
-            "+lrb:+p:+neg"
-        };
-        let choices = choices.clone();
-
-        let stable_params = conflict_params(stable_params);
-        let stable_brancher = Box::new(ConflictBasedBrancher::with(choices.clone(), stable_params));
-        let stable_brancher = WithGeomRestart::new(5000, 1.2, stable_brancher).clone_to_box();
-
-        let focused_params = conflict_params(focused_params);
-        let focused_brancher = Box::new(ConflictBasedBrancher::with(choices, focused_params));
-        let focused_brancher = WithGeomRestart::new(400, 1.0, focused_brancher).clone_to_box();
+    let search_params: Vec<_> = conf.split('/').collect();
+    let stable_params = if !search_params.is_empty() {
+        search_params[0]
+    } else {
+        "+lrb:+p+l:-neg"
+    };
+    let focused_params = if search_params.len() > 1 {
+        search_params[1]
+    } else {
+        "+lrb:+p:+neg"
+    };
 
-        let round_robin = RoundRobin::new(10_000, 1.1, vec![stable_brancher, focused_brancher]);
+    let stable_params = conflict_params(stable_params)?;
+    let stable_brancher = Box::new(ConflictBasedBrancher::with(choices.to_vec(), stable_params));
+    let stable_brancher = WithGeomRestart::new(5000, 1.2, stable_brancher).clone_to_box();
 
-        solver.set_brancher(round_robin);
-    });
+    let focused_params = conflict_params(focused_params)?;
+    let focused_brancher = Box::new(ConflictBasedBrancher::with(choices.to_vec(), focused_params));
+    let focused_brancher = WithGeomRestart::new(400, 1.0, focused_brancher).clone_to_box();
 
-    match par_solver.solve(deadline) {
-        SolverResult::Sol(_sol) => {
-            println!("> SATISFIED");
-            if opt.expected_satisfiability == Some(false) {
-                eprintln!("Error: expected UNSAT but got SAT");
-                std::process::exit(1);
-            }
-        }
-        SolverResult::Unsat => {
-            println!("> UNSATISFIABLE");
-            if opt.expected_satisfiability == Some(true) {
-                eprintln!("Error: expected SAT but got UNSAT");
-                std::process::exit(1);
-            }
-        }
-        SolverResult::Timeout(_) => {
-            println!("> TIMEOUT");
-            if opt.expected_satisfiability.is_some() {
-                eprintln!("Error: could not conclude on SAT or UNSAT within the allocated time");
-                std::process::exit(1);
-            }
-        }
-    }
-    par_solver.print_stats();
+    let round_robin = RoundRobin::new(10_000, 1.1, vec![stable_brancher, focused_brancher]);
 
+    solver.set_brancher(round_robin);
     Ok(())
 }
 
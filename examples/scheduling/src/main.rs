@@ -7,8 +7,11 @@ use crate::search::{SearchStrategy, Solver, Var};
 use anyhow::*;
 use aries::model::extensions::AssignmentExt;
 use aries::model::lang::IVar;
+use aries::solver::parallel::portfolio::Portfolio;
 use aries::solver::parallel::{Solution, SolverResult};
 use std::fmt::Write;
+use std::fs::File;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use walkdir::WalkDir;
@@ -39,6 +42,14 @@ pub struct Opt {
     /// Number of threads to allocate to search
     #[structopt(long, default_value = "1")]
     num_threads: u32,
+    /// Path to a TOML file describing the configuration of each parallel worker.
+    /// If provided, the `--search` and `--num-threads` options are ignored.
+    #[structopt(long)]
+    portfolio: Option<PathBuf>,
+    /// If provided, the statistics of all workers will be written as JSON to this file upon termination.
+    /// When solving all instances of a directory, the file holds the statistics of the last instance.
+    #[structopt(long)]
+    stats_json: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -53,23 +64,24 @@ fn main() -> Result<()> {
     // read command line arguments
     let opt = Opt::from_args();
 
+    let portfolio = opt.portfolio.as_ref().map(Portfolio::from_file).transpose()?;
+
     let file = &opt.file;
     if std::fs::metadata(file)?.is_file() {
-        solve(opt.kind, &opt.file, &opt);
-        Ok(())
+        solve(opt.kind, &opt.file, &opt, portfolio.as_ref())
     } else {
         for entry in WalkDir::new(file).follow_links(true).into_iter().filter_map(|e| e.ok()) {
             let f_name = entry.file_name().to_string_lossy();
             if f_name.ends_with(".txt") {
                 println!("{f_name}");
-                solve(opt.kind, &entry.path().to_string_lossy(), &opt);
+                solve(opt.kind, &entry.path().to_string_lossy(), &opt, portfolio.as_ref())?;
             }
         }
         Ok(())
     }
 }
 
-fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
+fn solve(kind: ProblemKind, instance: &str, opt: &Opt, portfolio: Option<&Portfolio>) -> Result<()> {
     let deadline = opt.timeout.map(|dur| Instant::now() + Duration::from_secs(dur as u64));
     let start_time = std::time::Instant::now();
     let filecontent = std::fs::read_to_string(instance).expect("Cannot read file");
@@ -88,7 +100,11 @@ fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
     let makespan: IVar = IVar::new(model.shape.get_variable(&Var::Makespan).unwrap());
 
     let solver = Solver::new(model);
-    let mut solver = search::get_solver(solver, &opt.search, &encoding, opt.num_threads as usize);
+    let mut solver = if let Some(portfolio) = portfolio {
+        search::get_solver_from_portfolio(solver, portfolio)?
+    } else {
+        search::get_solver(solver, &opt.search, &encoding, opt.num_threads as usize)
+    };
 
     let result = solver.minimize_with(
         makespan,
@@ -96,6 +112,23 @@ fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
         deadline,
     );
 
+    if let Some(stats_file) = &opt.stats_json {
+        let (status, cost) = match &result {
+            SolverResult::Sol(solution) => ("OPTIMAL", Some(solution.var_domain(makespan).lb)),
+            SolverResult::Unsat => ("UNSATISFIABLE", None),
+            SolverResult::Timeout(sol) => ("TIMEOUT", sol.as_ref().map(|s| s.var_domain(makespan).lb)),
+        };
+        let stats = serde_json::json!({
+            "instance": instance,
+            "status": status,
+            "makespan": cost,
+            "runtime": start_time.elapsed().as_secs_f64(),
+            "workers": solver.stats_reports(),
+        });
+        let file = File::create(stats_file).with_context(|| format!("Could not create {}", stats_file.display()))?;
+        serde_json::to_writer_pretty(file, &stats)?;
+    }
+
     match result {
         SolverResult::Sol(solution) => {
             let optimum = solution.var_domain(makespan).lb;
@@ -133,6 +166,7 @@ fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
         }
     }
     println!("TOTAL RUNTIME: {:.6}", start_time.elapsed().as_secs_f64());
+    Ok(())
 }
 
 /// Write the solution to file if the file if the file is not None
@@ -143,8 +177,8 @@ fn export(solution: &Solution, pb: &Problem, encoding: &Encoding, file: Option<&
             // all tasks on this machine
             let mut tasks = Vec::new();
             for alt in encoding.alternatives_on_machine(m) {
-                if solution.entails(alt.presence) {
-                    let start_time = solution.var_domain(alt.start).lb;
+                if solution.entails(alt.presence()) {
+                    let start_time = solution.var_domain(alt.interval.start).lb;
                     tasks.push((alt.id, start_time));
                 }
             }
@@ -1,9 +1,10 @@
 use crate::search::{Model, Var};
 use aries::core::{Lit, VarRef};
-use aries::model::lang::expr::{alternative, eq, leq, or};
+use aries::model::lang::expr::{leq, or};
+use aries::model::lang::interval::{alternative, end_before_start, synchronize, IntervalPart};
 use aries::model::lang::linear::LinearSum;
 use aries::model::lang::max::{EqMax, EqMin};
-use aries::model::lang::{IAtom, IVar};
+use aries::model::lang::{IAtom, IVar, IntervalVar};
 use itertools::Itertools;
 use std::fmt::{Debug, Formatter};
 
@@ -39,6 +40,10 @@ impl Op {
     pub fn min_duration(&self) -> i32 {
         self.alternatives.iter().map(|a| a.duration).min().unwrap()
     }
+
+    pub fn max_duration(&self) -> i32 {
+        self.alternatives.iter().map(|a| a.duration).max().unwrap()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -96,10 +101,6 @@ impl Problem {
         self.ops().filter(move |op| op.job == job)
     }
 
-    pub fn operation(&self, job: u32, op_id: u32) -> &Op {
-        self.ops().find(move |op| op.job == job && op.op_id == op_id).unwrap()
-    }
-
     pub fn machines(&self) -> impl Iterator<Item = u32> {
         0..self.num_machines
     }
@@ -134,8 +135,7 @@ impl Problem {
 pub struct Operation {
     pub job: u32,
     pub op: u32,
-    start: IAtom,
-    end: IAtom,
+    interval: IntervalVar,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
@@ -164,17 +164,21 @@ pub struct OperationAlternative {
     pub id: OperationId,
     pub machine: u32,
     pub duration: i32,
-    pub start: IVar,
-    pub presence: Lit,
+    /// Interval of fixed duration, present iff the alternative is selected.
+    pub interval: IntervalVar,
 }
 
 impl OperationAlternative {
     pub fn start(&self) -> IAtom {
-        self.start.into()
+        self.interval.start()
     }
 
     pub fn end(&self) -> IAtom {
-        self.start + self.duration
+        self.interval.end()
+    }
+
+    pub fn presence(&self) -> Lit {
+        self.interval.presence
     }
 }
 
@@ -208,13 +212,15 @@ impl Encoding {
                 } else {
                     m.new_presence_variable(Lit::TRUE, Var::Presence(id)).true_lit()
                 };
-                let start = m.new_optional_ivar(0, upper_bound, presence, Var::Start(id));
+                let interval =
+                    m.new_optional_interval_var(0, upper_bound, alt.duration, alt.duration, presence, |_| {
+                        Var::Start(id)
+                    });
                 alternatives.push(OperationAlternative {
                     id,
                     machine: alt.machine,
                     duration: alt.duration,
-                    start,
-                    presence,
+                    interval,
                 });
             }
 
@@ -225,21 +231,23 @@ impl Encoding {
                 Operation {
                     job: job_id,
                     op: op_id,
-                    start: alt.start(),
-                    end: alt.end(),
+                    interval: alt.interval,
                 }
             } else {
-                // more that one alternative, create new variables for start/end
+                // more that one alternative, create a new interval whose duration is the one of the selected alternative
                 let id = OperationId {
                     job: job_id,
                     op: op_id,
                     alt: None,
                 };
+                let label = |part| match part {
+                    IntervalPart::Start => Var::Start(id),
+                    IntervalPart::End | IntervalPart::Duration => Var::Intermediate,
+                };
                 Operation {
                     job: job_id,
                     op: op_id,
-                    start: m.new_optional_ivar(0, upper_bound, Lit::TRUE, Var::Start(id)).into(),
-                    end: m.new_optional_ivar(0, upper_bound, Lit::TRUE, Var::Start(id)).into(),
+                    interval: m.new_interval_var(0, upper_bound, op.min_duration(), op.max_duration(), label),
                 }
             };
             operations.push(operation);
@@ -292,14 +300,12 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
 
     // enforce makespan after last alternative
     for oa in e.all_alternatives() {
-        m.enforce(leq(oa.end(), e.makespan), [oa.presence]);
+        m.enforce(leq(oa.end(), e.makespan), [oa.presence()]);
     }
 
-    // enforce makespan after last operation and minimal duration of operation (based on alternatives)
+    // enforce makespan after last operation (the bounds of its duration are enforced by its interval)
     for o in e.all_operations() {
-        m.enforce(leq(o.end, e.makespan), []);
-        let min_duration = pb.operation(o.job, o.op).min_duration();
-        m.enforce(leq(o.start + min_duration, o.end), []);
+        m.enforce(leq(o.interval.end(), e.makespan), []);
     }
 
     // make sure we have exactly one alternative per operation
@@ -307,20 +313,15 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
         for op in e.operations_ids(j) {
             let operation = e.operation(j, op);
 
+            let intervals = e.alternatives(j, op).map(|alt| alt.interval).collect_vec();
             if use_constraints {
-                let starts = e.alternatives(j, op).map(|alt| alt.start()).collect_vec();
-                m.enforce(alternative(operation.start, starts), []);
-                let ends = e.alternatives(j, op).map(|alt| alt.end()).collect_vec();
-                m.enforce(alternative(operation.end, ends), []);
+                alternative(&mut m, operation.interval, &intervals);
             } else {
                 // enforce that, if an alternative is present, it matches the operation
-                for alt in e.alternatives(j, op) {
-                    m.enforce(eq(operation.start, alt.start()), [alt.presence]);
-                    m.enforce(eq(operation.end, alt.end()), [alt.presence]);
-                }
+                synchronize(&mut m, operation.interval, &intervals);
 
                 // presence literals of all alternatives
-                let alts = e.alternatives(j, op).map(|a| a.presence).collect_vec();
+                let alts = e.alternatives(j, op).map(|a| a.presence()).collect_vec();
                 assert!(!alts.is_empty());
                 assert!(
                     alts.len() > 1 || alts[0] == Lit::TRUE,
@@ -345,18 +346,18 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
             for alt2 in &alts[i + 1..] {
                 // variable that is true if alt1 comes first and false otherwise.
                 // in any case, setting a value to it enforces that the two tasks do not overlap
-                let scope = m.get_conjunctive_scope(&[alt1.presence, alt2.presence]);
+                let scope = m.get_conjunctive_scope(&[alt1.presence(), alt2.presence()]);
                 let prec = m.new_optional_bvar(scope, Var::Prec(alt1.id, alt2.id));
 
-                m.bind(leq(alt1.end(), alt2.start), prec.true_lit());
-                m.bind(leq(alt2.end(), alt1.start), prec.false_lit());
+                m.bind(leq(alt1.end(), alt2.start()), prec.true_lit());
+                m.bind(leq(alt2.end(), alt1.start()), prec.false_lit());
             }
         }
 
         if use_constraints {
             // variable that is bound to the start of first task executing on the machine
             let start_first = m.new_ivar(0, upper_bound, Var::Intermediate);
-            let mut starts = alts.iter().map(|a| a.start).collect_vec();
+            let mut starts = alts.iter().map(|a| a.interval.start).collect_vec();
             starts.push(e.makespan); // add the makespan as a fallback in case there are no tasks on this machine
             m.enforce(EqMin::new(start_first, starts), []);
 
@@ -371,12 +372,12 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
             let mut dur_sum = LinearSum::zero();
             for alt in &alts {
                 // TODO: this is currently a workaound a missing API
-                if alt.presence.variable() != VarRef::ZERO {
-                    let i_prez = IVar::new(alt.presence.variable());
+                if alt.presence().variable() != VarRef::ZERO {
+                    let i_prez = IVar::new(alt.presence().variable());
                     // assumes that i_prez is a 0-1 variable where 1 indicates presence
                     dur_sum += i_prez * alt.duration;
                 } else {
-                    assert_eq!(alt.presence, Lit::TRUE);
+                    assert_eq!(alt.presence(), Lit::TRUE);
                     dur_sum += alt.duration;
                 }
             }
@@ -396,7 +397,7 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
 
                     let o1 = e.operation(j, op1);
                     let o2 = e.operation(j, op2);
-                    m.enforce(leq(o1.end, o2.start), [])
+                    end_before_start(&mut m, o1.interval, o2.interval, 0);
                 }
             }
         }
@@ -410,11 +411,11 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32, use_const
                             for alt2 in e.alternatives(j, op2) {
                                 // variable that is true if alt1 comes first and false otherwise.
                                 // in any case, setting a value to it enforces that the two tasks do not overlap
-                                let scope = m.get_conjunctive_scope(&[alt1.presence, alt2.presence]);
+                                let scope = m.get_conjunctive_scope(&[alt1.presence(), alt2.presence()]);
                                 let prec = m.new_optional_bvar(scope, Var::Prec(alt1.id, alt2.id));
 
-                                m.bind(leq(alt1.end(), alt2.start), prec.true_lit());
-                                m.bind(leq(alt2.end(), alt1.start), prec.false_lit());
+                                m.bind(leq(alt1.end(), alt2.start()), prec.true_lit());
+                                m.bind(leq(alt2.end(), alt1.start()), prec.false_lit());
                             }
                         }
                     }
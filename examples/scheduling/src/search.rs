@@ -5,6 +5,7 @@ use crate::search::greedy::EstBrancher;
 use crate::search::SearchStrategy::Custom;
 use aries::core::*;
 use aries::model::extensions::Shaped;
+use aries::solver::parallel::portfolio::Portfolio;
 use aries::solver::search::activity::Heuristic;
 use aries::solver::search::combinators::{CombinatorExt, RoundRobin, UntilFirstConflict};
 use aries::solver::search::conflicts::{ConflictBasedBrancher, ImpactMeasure};
@@ -76,13 +77,15 @@ enum Mode {
 struct Strat {
     mode: Mode,
     params: conflicts::Params,
+    /// If set, overrides the LBD level under which learnt clauses are never removed.
+    locked_lbd_level: Option<u32>,
 }
 
-/// Builds a solver for the given strategy.
-pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_threads: usize) -> ParSolver {
-    let mut base_solver = Box::new(base);
+impl FromStr for Strat {
+    type Err = String;
 
-    let load_conf = |conf: &str| -> Strat {
+    /// Parses a strategy from a `:`-separated list of options (e.g. `stable:+sol`)
+    fn from_str(conf: &str) -> Result<Self, Self::Err> {
         let mut mode = Mode::Stable;
         let mut params = conflicts::Params {
             heuristic: conflicts::Heuristic::LearningRate,
@@ -90,6 +93,7 @@ pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_th
             impact_measure: ImpactMeasure::LBD,
             ..Default::default()
         };
+        let mut locked_lbd_level = None;
         for opt in conf.split(':') {
             if params.configure(opt) {
                 // handled
@@ -99,42 +103,66 @@ pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_th
                 "stable" => mode = Mode::Stable,
                 "focused" => mode = Mode::Focused,
                 x if x.starts_with("+lbd") => {
-                    let lvl = x.strip_prefix("+lbd").unwrap().parse().unwrap();
-                    base_solver.reasoners.sat.clauses.params.locked_lbd_level = lvl;
+                    let lvl = x.strip_prefix("+lbd").unwrap();
+                    locked_lbd_level = Some(lvl.parse().map_err(|_| format!("Invalid LBD level: {lvl}"))?);
                 }
                 "" => {} // ignore
-                _ => panic!("Unsupported option: {opt}"),
+                _ => return Err(format!("Unsupported option: {opt}")),
             }
         }
-        Strat { mode, params }
-    };
+        Ok(Strat {
+            mode,
+            params,
+            locked_lbd_level,
+        })
+    }
+}
 
-    let conf = match strategy {
-        SearchStrategy::Default => "stable:+sol",
-        SearchStrategy::Custom(conf) => conf.as_str(),
-    };
-    let all_strats = conf.split('/').map(load_conf).collect_vec();
+impl Strat {
+    /// Creates a brancher for this strategy, that will only branch on the given literals.
+    fn brancher(&self, decision_lits: &[Lit]) -> Brancher<Var> {
+        let brancher: Brancher<Var> = Box::new(ConflictBasedBrancher::with(decision_lits.to_vec(), self.params));
+        let (restart_period, restart_update) = match self.mode {
+            Mode::Stable => (2000, 1.2), // stable: few restarts
+            Mode::Focused => (800, 1.0), // focused: always aggressive restarts
+        };
+        brancher.with_restarts(restart_period, restart_update)
+    }
+}
 
-    let decision_lits: Vec<Lit> = base_solver
+/// Returns the literals on which the search should branch: resource orderings and presence of alternatives.
+fn decision_literals(solver: &Solver) -> Vec<Lit> {
+    solver
         .model
         .state
         .variables()
-        .filter_map(|v| match base_solver.model.get_label(v) {
+        .filter_map(|v| match solver.model.get_label(v) {
             Some(&Var::Prec(_, _)) => Some(v.geq(1)),
             Some(&Var::Presence(_)) => Some(v.geq(1)),
             _ => None,
         })
-        .collect();
+        .collect()
+}
 
-    // creates a brancher for a given strategy
-    let build_brancher = |strat: Strat| {
-        let brancher: Brancher<Var> = Box::new(ConflictBasedBrancher::with(decision_lits.clone(), strat.params));
-        let (restart_period, restart_update) = match strat.mode {
-            Mode::Stable => (2000, 1.2), // stable: few restarts
-            Mode::Focused => (800, 1.0), // focused: always aggressive restarts
-        };
-        brancher.with_restarts(restart_period, restart_update)
+/// Builds a solver for the given strategy.
+pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_threads: usize) -> ParSolver {
+    let mut base_solver = Box::new(base);
+
+    let conf = match strategy {
+        SearchStrategy::Default => "stable:+sol",
+        SearchStrategy::Custom(conf) => conf.as_str(),
     };
+    let all_strats = conf
+        .split('/')
+        .map(|conf| Strat::from_str(conf).unwrap_or_else(|e| panic!("{e}")))
+        .collect_vec();
+    for strat in &all_strats {
+        if let Some(lvl) = strat.locked_lbd_level {
+            base_solver.reasoners.sat.clauses.params.locked_lbd_level = lvl;
+        }
+    }
+
+    let decision_lits = decision_literals(&base_solver);
 
     // build a brancher that alternates between the proposed strategies
     let round_robin = |mut branchers: Vec<Brancher<Var>>| {
@@ -161,7 +189,7 @@ pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_th
             .collect_vec();
 
         // conflict based search, possibly alternating between several strategies
-        let branchers = strats.into_iter().map(build_brancher).collect_vec();
+        let branchers = strats.iter().map(|strat| strat.brancher(&decision_lits)).collect_vec();
         let brancher = round_robin(branchers);
 
         // search strategy. For the first one simply add a greedy EST strategy to bootstrap the search
@@ -176,3 +204,21 @@ pub fn get_solver(base: Solver, strategy: &SearchStrategy, pb: &Encoding, num_th
         s.set_brancher_boxed(brancher)
     })
 }
+
+/// Builds a solver whose workers are described by the given portfolio.
+///
+/// A `custom` brancher of the portfolio is interpreted as a single strategy of the `--search` option (e.g. `focused:+sol`).
+pub fn get_solver_from_portfolio(base: Solver, portfolio: &Portfolio) -> anyhow::Result<ParSolver> {
+    let base_solver = Box::new(base);
+    let decision_lits = decision_literals(&base_solver);
+
+    portfolio.build_solver(base_solver, |conf, s| {
+        let strat = Strat::from_str(conf).map_err(anyhow::Error::msg)?;
+        if let Some(lvl) = strat.locked_lbd_level {
+            s.reasoners.sat.clauses.params.locked_lbd_level = lvl;
+        }
+        let brancher = strat.brancher(&decision_lits);
+        s.set_brancher_boxed(brancher.and_then(Lexical::with_min().clone_to_box()));
+        Ok(())
+    })
+}
@@ -0,0 +1,977 @@
+//! Execution of SMT-LIB 2 commands on top of the aries solver.
+//!
+//! Boolean terms are reified into literals, and arithmetic atoms are mapped onto the reasoners of the solver:
+//!
+//!  - atoms on a single variable (`(<= x 3)`) are bound literals,
+//!  - difference atoms (`(<= (- x y) 3)`) are handled by the STN,
+//!  - all other linear atoms are expressed on a slack variable that is constrained (by the CP reasoner)
+//!    to be equal to the linear sum, which allows reifying them as bound literals on the slack variable.
+//!
+//! Integer variables are given the bounded domain `[-ARIES_SMT_INT_BOUND, ARIES_SMT_INT_BOUND]`.
+//!
+//! Applications of uninterpreted functions (declared with `declare-fun`) are handled by the EUF reasoner:
+//! each distinct application `(f a b)` is given a fresh result variable `r`, constrained by `f(a, b) = r`.
+//!
+//! Assertions made in the base scope are directly enforced in the solver.
+//! Assertions made after a `push` (as well as the named assertions and the ones of `check-sat-assuming`)
+//! are instead passed as assumptions to the solver, which allows retracting them and extracting unsat cores.
+
+use crate::sexpr::SExpr;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use aries::backtrack::Backtrack;
+use aries::core::VarRef;
+use aries::core::{IntCst, Lit, INT_CST_MAX, INT_CST_MIN};
+use aries::model::extensions::{AssignmentExt, SavedAssignment};
+use aries::model::lang::euf::{Apply, FunId};
+use aries::model::lang::expr::{and, leq, or};
+use aries::model::lang::linear::LinearSum;
+use aries::model::lang::{IAtom, IVar};
+use aries::solver::Solver;
+use env_param::EnvParam;
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::sync::Arc;
+
+/// Bound on the absolute value of the integer variables.
+static INT_BOUND: EnvParam<IntCst> = EnvParam::new("ARIES_SMT_INT_BOUND", "1000000");
+
+/// Logics that are supported by the interpreter.
+const LOGICS: [&str; 7] = ["QF_IDL", "QF_LIA", "QF_BOOL", "QF_UF", "QF_UFIDL", "QF_UFLIA", "ALL"];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Sort {
+    Bool,
+    Int,
+}
+
+/// A linear sum `sum_i factor_i * var_i + cst`
+#[derive(Clone, Default, Debug)]
+struct Lin {
+    terms: BTreeMap<IVar, IntCst>,
+    cst: IntCst,
+}
+
+impl Lin {
+    fn constant(cst: IntCst) -> Lin {
+        Lin {
+            terms: Default::default(),
+            cst,
+        }
+    }
+
+    fn var(var: IVar) -> Lin {
+        Lin {
+            terms: [(var, 1)].into(),
+            cst: 0,
+        }
+    }
+
+    fn as_constant(&self) -> Option<IntCst> {
+        self.terms.is_empty().then_some(self.cst)
+    }
+
+    fn scaled(mut self, factor: IntCst) -> Result<Lin> {
+        for f in self.terms.values_mut() {
+            *f = f.checked_mul(factor).context("Integer overflow")?;
+        }
+        self.terms.retain(|_, f| *f != 0);
+        self.cst = self.cst.checked_mul(factor).context("Integer overflow")?;
+        Ok(self)
+    }
+
+    fn add(mut self, other: &Lin) -> Result<Lin> {
+        for (&v, &f) in &other.terms {
+            let factor = self.terms.entry(v).or_insert(0);
+            *factor = factor.checked_add(f).context("Integer overflow")?;
+        }
+        self.terms.retain(|_, f| *f != 0);
+        self.cst = self.cst.checked_add(other.cst).context("Integer overflow")?;
+        Ok(self)
+    }
+
+    fn sub(self, other: &Lin) -> Result<Lin> {
+        self.add(&other.clone().scaled(-1)?)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Term {
+    Bool(Lit),
+    Int(Lin),
+}
+
+impl Term {
+    fn sort(&self) -> Sort {
+        match self {
+            Term::Bool(_) => Sort::Bool,
+            Term::Int(_) => Sort::Int,
+        }
+    }
+}
+
+/// An uninterpreted function, with the sorts of its parameters and of its result.
+#[derive(Clone, Debug)]
+struct Function {
+    id: FunId,
+    params: Vec<Sort>,
+    result: Sort,
+}
+
+/// Symbols and assertions introduced between a `push` and the corresponding `pop`.
+#[derive(Default)]
+struct Frame {
+    declarations: Vec<(String, Sort, Term)>,
+    functions: Vec<(String, Function)>,
+    definitions: Vec<String>,
+    /// Assertions that must be passed as assumptions when checking satisfiability, with their name if any.
+    assertions: Vec<(Lit, Option<String>)>,
+}
+
+enum CheckResult {
+    Sat(Arc<SavedAssignment>),
+    Unsat(Vec<String>),
+    Unknown,
+}
+
+/// Interpreter of an SMT-LIB 2 script, writing the responses of the solver to `out`.
+pub struct Interpreter<W: Write> {
+    out: W,
+    solver: Solver<String>,
+    /// Declared and defined symbols, a later definition shadowing a previous one.
+    symbols: HashMap<String, Vec<Term>>,
+    /// Stack of frames, the first one being the base scope.
+    frames: Vec<Frame>,
+    /// Declared uninterpreted functions, a later declaration shadowing a previous one.
+    functions: HashMap<String, Vec<Function>>,
+    /// Number of functions declared so far, used to identify the next one.
+    num_functions: u32,
+    /// Result variable of each function application.
+    applications: HashMap<(FunId, Vec<VarRef>), VarRef>,
+    /// Slack variables for linear sums (without constant).
+    slacks: HashMap<Vec<(IVar, IntCst)>, IVar>,
+    last_result: Option<CheckResult>,
+    print_success: bool,
+    exited: bool,
+}
+
+impl<W: Write> Interpreter<W> {
+    pub fn new(out: W) -> Self {
+        Interpreter {
+            out,
+            solver: Solver::new(Default::default()),
+            symbols: Default::default(),
+            frames: vec![Frame::default()],
+            functions: Default::default(),
+            num_functions: 0,
+            applications: Default::default(),
+            slacks: Default::default(),
+            last_result: None,
+            print_success: false,
+            exited: false,
+        }
+    }
+
+    /// Returns true if an `exit` command was executed.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Executes all commands of the script, stopping at the first `exit`.
+    /// Errors in commands are reported on the output, as specified by SMT-LIB, and do not interrupt the script.
+    pub fn run_script(&mut self, commands: &[SExpr]) -> Result<()> {
+        for command in commands {
+            if self.exited {
+                break;
+            }
+            self.run(command)?;
+        }
+        Ok(())
+    }
+
+    /// Executes a single command. An error is only returned if the output could not be written.
+    pub fn run(&mut self, command: &SExpr) -> Result<()> {
+        match self.execute(command) {
+            Ok(Some(response)) => writeln!(self.out, "{response}")?,
+            Ok(None) if self.print_success => writeln!(self.out, "success")?,
+            Ok(None) => {}
+            Err(e) => writeln!(self.out, "(error \"{}\")", format!("{e:#}").replace('"', "\"\""))?,
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Executes a command, returning its response if it has any (beyond `success`).
+    fn execute(&mut self, command: &SExpr) -> Result<Option<String>> {
+        let [SExpr::Atom(name), args @ ..] = command.as_list().context("Expected a command")? else {
+            bail!("Expected a command")
+        };
+        match (name.as_str(), args) {
+            ("set-logic", [logic]) => {
+                let logic = atom(logic)?;
+                if !LOGICS.contains(&logic) {
+                    return Ok(Some("unsupported".to_string()));
+                }
+            }
+            ("set-option", [key, value, ..]) => {
+                if atom(key)? == ":print-success" {
+                    self.print_success = atom(value)? == "true";
+                }
+            }
+            ("set-info", _) => {}
+            ("echo", [s]) => return Ok(Some(atom(s)?.to_string())),
+            ("exit", []) => self.exited = true,
+            ("declare-const", [name, sort]) => self.declare(atom(name)?, sort)?,
+            ("declare-fun", [name, params, sort]) => {
+                let params = params.as_list().context("Expected a list of parameter sorts")?;
+                if params.is_empty() {
+                    self.declare(atom(name)?, sort)?
+                } else {
+                    self.declare_function(atom(name)?, params, sort)?
+                }
+            }
+            ("define-fun", [name, params, sort, body]) => {
+                ensure!(
+                    params.as_list().is_some_and(|l| l.is_empty()),
+                    "Functions with parameters are not supported"
+                );
+                let name = atom(name)?;
+                let term = self.term(body, &HashMap::new())?;
+                ensure!(term.sort() == parse_sort(sort)?, "Unexpected sort for {name}");
+                self.define(name, term);
+            }
+            ("assert", [t]) => self.assert(t)?,
+            ("check-sat", []) => return Ok(Some(self.check_sat(Vec::new())?)),
+            ("check-sat-assuming", [assumptions]) => {
+                let assumptions = assumptions
+                    .as_list()
+                    .context("Expected a list of assumptions")?
+                    .iter()
+                    .map(|a| Ok((self.bool_term(a, &HashMap::new())?, a.to_string())))
+                    .collect::<Result<Vec<_>>>()?;
+                return Ok(Some(self.check_sat(assumptions)?));
+            }
+            ("get-model", []) => return self.get_model().map(Some),
+            ("get-unsat-core", []) => match &self.last_result {
+                Some(CheckResult::Unsat(core)) => return Ok(Some(format!("({})", core.iter().join(" ")))),
+                _ => bail!("The last check-sat did not return unsat"),
+            },
+            ("push", n) => {
+                for _ in 0..num_levels(n)? {
+                    self.frames.push(Frame::default());
+                }
+                self.last_result = None;
+            }
+            ("pop", n) => {
+                let n = num_levels(n)?;
+                ensure!(n < self.frames.len(), "Cannot pop {n} levels");
+                for _ in 0..n {
+                    let frame = self.frames.pop().unwrap();
+                    for name in frame.declarations.iter().map(|d| &d.0).chain(&frame.definitions) {
+                        self.undefine(name);
+                    }
+                    for (name, _) in &frame.functions {
+                        let functions = self.functions.get_mut(name).unwrap();
+                        functions.pop();
+                        if functions.is_empty() {
+                            self.functions.remove(name);
+                        }
+                    }
+                }
+                self.last_result = None;
+            }
+            (name, _) => bail!("Unsupported command: {name}"),
+        }
+        Ok(None)
+    }
+
+    fn define(&mut self, name: &str, term: Term) {
+        self.symbols.entry(name.to_string()).or_default().push(term);
+        self.frames.last_mut().unwrap().definitions.push(name.to_string());
+    }
+
+    fn undefine(&mut self, name: &str) {
+        if let Some(terms) = self.symbols.get_mut(name) {
+            terms.pop();
+            if terms.is_empty() {
+                self.symbols.remove(name);
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, sort: &SExpr) -> Result<()> {
+        let sort = parse_sort(sort)?;
+        let term = match sort {
+            Sort::Bool => Term::Bool(self.solver.model.new_bvar(name).true_lit()),
+            Sort::Int => {
+                let bound = INT_BOUND.get();
+                Term::Int(Lin::var(self.solver.model.new_ivar(-bound, bound, name)))
+            }
+        };
+        self.symbols.entry(name.to_string()).or_default().push(term.clone());
+        self.frames
+            .last_mut()
+            .unwrap()
+            .declarations
+            .push((name.to_string(), sort, term));
+        Ok(())
+    }
+
+    fn declare_function(&mut self, name: &str, params: &[SExpr], sort: &SExpr) -> Result<()> {
+        let function = Function {
+            id: FunId::new(self.num_functions),
+            params: params.iter().map(parse_sort).collect::<Result<_>>()?,
+            result: parse_sort(sort)?,
+        };
+        self.num_functions += 1;
+        self.functions
+            .entry(name.to_string())
+            .or_default()
+            .push(function.clone());
+        self.frames
+            .last_mut()
+            .unwrap()
+            .functions
+            .push((name.to_string(), function));
+        Ok(())
+    }
+
+    fn assert(&mut self, t: &SExpr) -> Result<()> {
+        // named assertion: (! t :named name)
+        let (t, name) = match t.as_list() {
+            Some([SExpr::Atom(bang), t, SExpr::Atom(key), SExpr::Atom(name)]) if bang == "!" && key == ":named" => {
+                (t, Some(name.clone()))
+            }
+            _ => (t, None),
+        };
+        let lit = self.bool_term(t, &HashMap::new())?;
+        if self.frames.len() == 1 && name.is_none() {
+            self.solver.enforce(lit, []);
+        } else {
+            self.frames.last_mut().unwrap().assertions.push((lit, name));
+        }
+        self.last_result = None;
+        Ok(())
+    }
+
+    fn check_sat(&mut self, extra_assumptions: Vec<(Lit, String)>) -> Result<String> {
+        // all assumptions, with the name under which they may appear in an unsat core
+        let assumptions = self
+            .frames
+            .iter()
+            .flat_map(|f| f.assertions.iter().cloned())
+            .chain(extra_assumptions.into_iter().map(|(l, name)| (l, Some(name))))
+            .collect_vec();
+        let result = self.solver.solve_with_assumptions(assumptions.iter().map(|a| a.0));
+        self.solver.reset();
+        let (response, result) = match result {
+            Ok(Ok(assignment)) => ("sat", CheckResult::Sat(assignment)),
+            Ok(Err(core)) => {
+                let core = assumptions
+                    .into_iter()
+                    .filter(|(l, _)| core.literals().contains(l))
+                    .filter_map(|(_, name)| name)
+                    .unique()
+                    .collect();
+                ("unsat", CheckResult::Unsat(core))
+            }
+            Err(_) => ("unknown", CheckResult::Unknown),
+        };
+        self.last_result = Some(result);
+        Ok(response.to_string())
+    }
+
+    fn get_model(&self) -> Result<String> {
+        let Some(CheckResult::Sat(assignment)) = &self.last_result else {
+            bail!("The last check-sat did not return sat")
+        };
+        let mut model = String::from("(");
+        for (name, sort, term) in self.frames.iter().flat_map(|f| &f.declarations) {
+            let value = match term {
+                Term::Bool(l) => assignment.entails(*l).to_string(),
+                Term::Int(lin) => {
+                    let (&var, _) = lin.terms.first_key_value().unwrap();
+                    format_value(*sort, assignment.var_domain(var).lb)
+                }
+            };
+            let sort = sort_name(*sort);
+            model.push_str(&format!("\n  (define-fun {} () {sort} {value})", quote(name)));
+        }
+        for (name, function) in self.frames.iter().flat_map(|f| &f.functions) {
+            model.push_str(&format!("\n  {}", self.function_model(name, function, assignment)));
+        }
+        model.push_str("\n)");
+        Ok(model)
+    }
+
+    /// Definition of a function as a chain of `ite` over the arguments of its applications.
+    fn function_model(&self, name: &str, function: &Function, assignment: &SavedAssignment) -> String {
+        let value = |var: VarRef| assignment.var_domain(var).lb;
+        let params = (0..function.params.len()).map(|i| format!("x!{i}")).collect_vec();
+        let mut body = format_value(function.result, 0);
+        for ((_, args), &result) in self.applications.iter().filter(|((f, _), _)| *f == function.id) {
+            let conditions = params
+                .iter()
+                .zip(&function.params)
+                .zip(args)
+                .map(|((param, &sort), &arg)| format!("(= {param} {})", format_value(sort, value(arg))))
+                .join(" ");
+            let condition = if args.len() == 1 {
+                conditions
+            } else {
+                format!("(and {conditions})")
+            };
+            body = format!(
+                "(ite {condition} {} {body})",
+                format_value(function.result, value(result))
+            );
+        }
+        let params = params
+            .iter()
+            .zip(&function.params)
+            .map(|(param, &sort)| format!("({param} {})", sort_name(sort)))
+            .join(" ");
+        format!(
+            "(define-fun {} ({params}) {} {body})",
+            quote(name),
+            sort_name(function.result)
+        )
+    }
+
+    fn bool_term(&mut self, e: &SExpr, env: &HashMap<String, Term>) -> Result<Lit> {
+        match self.term(e, env)? {
+            Term::Bool(l) => Ok(l),
+            Term::Int(_) => bail!("Expected a boolean term: {e}"),
+        }
+    }
+
+    fn int_term(&mut self, e: &SExpr, env: &HashMap<String, Term>) -> Result<Lin> {
+        match self.term(e, env)? {
+            Term::Int(l) => Ok(l),
+            Term::Bool(_) => bail!("Expected an integer term: {e}"),
+        }
+    }
+
+    fn term(&mut self, e: &SExpr, env: &HashMap<String, Term>) -> Result<Term> {
+        let list = match e {
+            SExpr::Atom(a) => return self.atom_term(a, env),
+            SExpr::List(l) => l,
+        };
+        let [SExpr::Atom(f), args @ ..] = list.as_slice() else {
+            bail!("Unsupported term: {e}")
+        };
+        if let Some(function) = self.functions.get(f).and_then(|fs| fs.last()).cloned() {
+            return self
+                .application(&function, args, env)
+                .with_context(|| format!("In {e}"));
+        }
+        let bools = |s: &mut Self, args: &[SExpr]| args.iter().map(|a| s.bool_term(a, env)).collect::<Result<Vec<_>>>();
+        let ints = |s: &mut Self, args: &[SExpr]| args.iter().map(|a| s.int_term(a, env)).collect::<Result<Vec<_>>>();
+
+        let term = match (f.as_str(), args) {
+            ("let", [bindings, body]) => {
+                let mut inner = env.clone();
+                for binding in bindings.as_list().context("Expected a list of bindings")? {
+                    let [SExpr::Atom(var), value] = binding.as_list().context("Expected a binding")? else {
+                        bail!("Invalid binding: {binding}")
+                    };
+                    // bindings are parallel: all values are evaluated in the outer environment
+                    let value = self.term(value, env)?;
+                    inner.insert(var.clone(), value);
+                }
+                return self.term(body, &inner);
+            }
+            ("!", [t, ..]) => return self.term(t, env),
+            ("not", [t]) => Term::Bool(!self.bool_term(t, env)?),
+            ("and", args) => {
+                let conjuncts = bools(self, args)?;
+                Term::Bool(self.solver.reify(and(conjuncts)))
+            }
+            ("or", args) => {
+                let disjuncts = bools(self, args)?;
+                Term::Bool(self.solver.reify(or(disjuncts)))
+            }
+            ("=>", [_, _, ..]) => {
+                // right associative: a => (b => c)
+                let mut lits = bools(self, args)?;
+                let conclusion = lits.pop().unwrap();
+                let clause = lits.into_iter().map(|l| !l).chain([conclusion]).collect_vec();
+                Term::Bool(self.solver.reify(or(clause)))
+            }
+            ("xor", [_, _, ..]) => {
+                let lits = bools(self, args)?;
+                let mut acc = lits[0];
+                for &l in &lits[1..] {
+                    acc = self.bool_equal(acc, !l);
+                }
+                Term::Bool(acc)
+            }
+            ("ite", [c, t, e]) => {
+                let c = self.bool_term(c, env)?;
+                match (self.term(t, env)?, self.term(e, env)?) {
+                    (Term::Bool(t), Term::Bool(e)) => {
+                        let then = self.solver.reify(or([!c, t]));
+                        let otherwise = self.solver.reify(or([c, e]));
+                        Term::Bool(self.solver.reify(and([then, otherwise])))
+                    }
+                    (Term::Int(t), Term::Int(e)) => Term::Int(self.int_ite(c, t, e)?),
+                    _ => bail!("Branches of ite have different sorts: {e}"),
+                }
+            }
+            ("=", [_, _, ..]) | ("distinct", [_, _, ..]) => {
+                let terms = args.iter().map(|a| self.term(a, env)).collect::<Result<Vec<_>>>()?;
+                let pairs = if f == "=" {
+                    terms.iter().tuple_windows().collect_vec()
+                } else {
+                    terms.iter().tuple_combinations().collect_vec()
+                };
+                let mut lits = Vec::with_capacity(pairs.len());
+                for (a, b) in pairs {
+                    let equal = match (a, b) {
+                        (Term::Bool(a), Term::Bool(b)) => self.bool_equal(*a, *b),
+                        (Term::Int(a), Term::Int(b)) => self.int_equal(a, b)?,
+                        _ => bail!("Arguments of {f} have different sorts: {e}"),
+                    };
+                    lits.push(if f == "=" { equal } else { !equal });
+                }
+                Term::Bool(self.solver.reify(and(lits)))
+            }
+            ("<=" | "<" | ">=" | ">", [_, _, ..]) => {
+                let terms = ints(self, args)?;
+                let mut lits = Vec::with_capacity(terms.len() - 1);
+                for (a, b) in terms.iter().tuple_windows() {
+                    // express the comparison as `lhs - rhs + offset <= 0`
+                    let (lhs, rhs, offset) = match f.as_str() {
+                        "<=" => (a, b, 0),
+                        "<" => (a, b, 1),
+                        ">=" => (b, a, 0),
+                        _ => (b, a, 1),
+                    };
+                    let diff = lhs.clone().sub(rhs)?.add(&Lin::constant(offset))?;
+                    lits.push(self.leq_zero(diff)?);
+                }
+                Term::Bool(self.solver.reify(and(lits)))
+            }
+            ("+", [_, ..]) => {
+                let terms = ints(self, args)?;
+                Term::Int(terms.iter().try_fold(Lin::default(), |acc, t| acc.add(t))?)
+            }
+            ("-", [t]) => Term::Int(self.int_term(t, env)?.scaled(-1)?),
+            ("-", [first, rest @ ..]) => {
+                let first = self.int_term(first, env)?;
+                let rest = ints(self, rest)?;
+                Term::Int(rest.iter().try_fold(first, |acc, t| acc.sub(t))?)
+            }
+            ("*", [_, _, ..]) => {
+                let terms = ints(self, args)?;
+                let mut product = Lin::constant(1);
+                for t in terms {
+                    product = if let Some(c) = t.as_constant() {
+                        product.scaled(c)?
+                    } else if let Some(c) = product.as_constant() {
+                        t.scaled(c)?
+                    } else {
+                        bail!("Non-linear multiplication: {e}")
+                    };
+                }
+                Term::Int(product)
+            }
+            _ => bail!("Unsupported term: {e}"),
+        };
+        Ok(term)
+    }
+
+    fn atom_term(&mut self, a: &str, env: &HashMap<String, Term>) -> Result<Term> {
+        if let Some(t) = env.get(a) {
+            return Ok(t.clone());
+        }
+        if let Some(t) = self.symbols.get(a).and_then(|terms| terms.last()) {
+            return Ok(t.clone());
+        }
+        match a {
+            "true" => Ok(Term::Bool(Lit::TRUE)),
+            "false" => Ok(Term::Bool(Lit::FALSE)),
+            _ if a.chars().all(|c| c.is_ascii_digit()) => {
+                let value = a
+                    .parse::<IntCst>()
+                    .ok()
+                    .filter(|v| *v <= INT_CST_MAX)
+                    .ok_or_else(|| anyhow!("Integer constant out of range: {a}"))?;
+                Ok(Term::Int(Lin::constant(value)))
+            }
+            _ => bail!("Unknown symbol: {a}"),
+        }
+    }
+
+    /// Returns a term for the application of the function to the given arguments.
+    /// Applications with the same arguments share the same result.
+    fn application(&mut self, function: &Function, args: &[SExpr], env: &HashMap<String, Term>) -> Result<Term> {
+        ensure!(
+            args.len() == function.params.len(),
+            "Expected {} arguments but got {}",
+            function.params.len(),
+            args.len()
+        );
+        let mut vars = Vec::with_capacity(args.len());
+        for (arg, &sort) in args.iter().zip(&function.params) {
+            let arg = self.term(arg, env)?;
+            ensure!(arg.sort() == sort, "Unexpected sort for argument {}", vars.len() + 1);
+            vars.push(self.term_var(arg)?);
+        }
+        let key = (function.id, vars);
+        let result = match self.applications.get(&key) {
+            Some(&result) => result,
+            None => {
+                let result = match function.result {
+                    Sort::Bool => self.solver.model.new_bvar("_app").into(),
+                    Sort::Int => {
+                        let bound = INT_BOUND.get();
+                        self.solver.model.new_ivar(-bound, bound, "_app").into()
+                    }
+                };
+                self.solver
+                    .enforce(Apply::new(function.id, key.1.iter().copied(), result), []);
+                self.applications.insert(key, result);
+                result
+            }
+        };
+        Ok(match function.result {
+            Sort::Bool => Term::Bool(result.geq(1)),
+            Sort::Int => Term::Int(Lin::var(IVar::new(result))),
+        })
+    }
+
+    /// Returns a variable that is equal to the term, to be used as the argument of a function application.
+    /// A boolean term is represented by a `[0, 1]` variable that is 1 iff the term holds.
+    fn term_var(&mut self, t: Term) -> Result<VarRef> {
+        match t {
+            Term::Bool(l) => {
+                let var = l.variable();
+                if l == var.geq(1) && self.solver.model.state.bounds(var) == (0, 1) {
+                    Ok(var)
+                } else {
+                    let b = self.solver.model.new_bvar("_arg");
+                    let equal = self.bool_equal(b.true_lit(), l);
+                    self.solver.enforce(equal, []);
+                    Ok(b.into())
+                }
+            }
+            Term::Int(lin) => match lin.terms.iter().collect_vec().as_slice() {
+                [(&var, 1)] if lin.cst == 0 => Ok(var.into()),
+                _ => {
+                    let (lb, ub) = self.bounds(&lin);
+                    let var = self.solver.model.new_ivar(lb, ub, "_arg");
+                    let equal = self.int_equal(&Lin::var(var), &lin)?;
+                    self.solver.enforce(equal, []);
+                    Ok(var.into())
+                }
+            },
+        }
+    }
+
+    /// Returns a literal that is true iff `a <=> b`.
+    fn bool_equal(&mut self, a: Lit, b: Lit) -> Lit {
+        let ab = self.solver.reify(or([!a, b]));
+        let ba = self.solver.reify(or([!b, a]));
+        self.solver.reify(and([ab, ba]))
+    }
+
+    /// Returns a literal that is true iff `a = b`.
+    fn int_equal(&mut self, a: &Lin, b: &Lin) -> Result<Lit> {
+        let le = self.leq_zero(a.clone().sub(b)?)?;
+        let ge = self.leq_zero(b.clone().sub(a)?)?;
+        Ok(self.solver.reify(and([le, ge])))
+    }
+
+    /// Returns a new variable that is equal to `t` if `c` holds and to `e` otherwise.
+    fn int_ite(&mut self, c: Lit, t: Lin, e: Lin) -> Result<Lin> {
+        let (t_lb, t_ub) = self.bounds(&t);
+        let (e_lb, e_ub) = self.bounds(&e);
+        let var = self.solver.model.new_ivar(t_lb.min(e_lb), t_ub.max(e_ub), "_ite");
+        let res = Lin::var(var);
+        let then = self.int_equal(&res, &t)?;
+        let otherwise = self.int_equal(&res, &e)?;
+        self.solver.enforce(or([!c, then]), []);
+        self.solver.enforce(or([c, otherwise]), []);
+        Ok(res)
+    }
+
+    /// Bounds of a linear sum, clamped to the range of representable values.
+    fn bounds(&self, lin: &Lin) -> (IntCst, IntCst) {
+        let mut lb = lin.cst as i64;
+        let mut ub = lin.cst as i64;
+        for (&v, &f) in &lin.terms {
+            let (v_lb, v_ub) = self.solver.model.state.bounds(v.into());
+            let (a, b) = (f as i64 * v_lb as i64, f as i64 * v_ub as i64);
+            lb += a.min(b);
+            ub += a.max(b);
+        }
+        let clamp = |x: i64| x.clamp(INT_CST_MIN as i64, INT_CST_MAX as i64) as IntCst;
+        (clamp(lb), clamp(ub))
+    }
+
+    /// Returns a literal that is true iff `lin <= 0`.
+    fn leq_zero(&mut self, lin: Lin) -> Result<Lit> {
+        let k = lin.cst;
+        let terms = lin.terms.iter().map(|(&v, &f)| (v, f)).collect_vec();
+        let lit = match terms.as_slice() {
+            [] => {
+                if k <= 0 {
+                    Lit::TRUE
+                } else {
+                    Lit::FALSE
+                }
+            }
+            // f * x + k <= 0
+            [(x, f)] if *f > 0 => x.leq((-k).div_euclid(*f)),
+            [(x, f)] => x.geq(-((-k).div_euclid(-*f))),
+            // x - y + k <= 0, i.e., x + k <= y
+            [(x, 1), (y, -1)] | [(y, -1), (x, 1)] => self.solver.reify(leq(*x + k, *y)),
+            _ => {
+                let slack = self.slack(&terms)?;
+                slack.leq(-k)
+            }
+        };
+        Ok(lit)
+    }
+
+    /// Returns a variable constrained to be equal to the given linear sum.
+    fn slack(&mut self, terms: &[(IVar, IntCst)]) -> Result<IVar> {
+        if let Some(&slack) = self.slacks.get(terms) {
+            return Ok(slack);
+        }
+        let sum = Lin {
+            terms: terms.iter().copied().collect(),
+            cst: 0,
+        };
+        let (lb, ub) = self.bounds(&sum);
+        let slack = self
+            .solver
+            .model
+            .new_ivar(lb, ub, format!("_slack{}", self.slacks.len()));
+        // sum - slack = 0
+        let mut sum = LinearSum::zero();
+        for &(v, f) in terms {
+            sum += LinearSum::from(IAtom::from(v)) * f;
+        }
+        let sum = sum - IAtom::from(slack);
+        self.solver.enforce(sum.clone().leq(0), []);
+        self.solver.enforce(sum.geq(0), []);
+        self.slacks.insert(terms.to_vec(), slack);
+        Ok(slack)
+    }
+}
+
+fn atom(e: &SExpr) -> Result<&str> {
+    e.as_atom().with_context(|| format!("Expected an atom but got: {e}"))
+}
+
+fn parse_sort(e: &SExpr) -> Result<Sort> {
+    match atom(e)? {
+        "Bool" => Ok(Sort::Bool),
+        "Int" => Ok(Sort::Int),
+        s => bail!("Unsupported sort: {s}"),
+    }
+}
+
+fn sort_name(sort: Sort) -> &'static str {
+    match sort {
+        Sort::Bool => "Bool",
+        Sort::Int => "Int",
+    }
+}
+
+/// Formats the value of a variable of the given sort, as expected in a model.
+fn format_value(sort: Sort, value: IntCst) -> String {
+    match sort {
+        Sort::Bool => (value == 1).to_string(),
+        Sort::Int if value < 0 => format!("(- {})", -value),
+        Sort::Int => value.to_string(),
+    }
+}
+
+fn num_levels(args: &[SExpr]) -> Result<usize> {
+    match args {
+        [] => Ok(1),
+        [n] => atom(n)?.parse().context("Invalid number of levels"),
+        _ => bail!("Invalid arguments"),
+    }
+}
+
+/// Quotes a symbol if it is not a simple symbol.
+fn quote(name: &str) -> String {
+    let simple = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "~!@$%^&*_-+=<>.?/".contains(c));
+    if simple {
+        name.to_string()
+    } else {
+        format!("|{name}|")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sexpr::parse_all;
+
+    /// Runs the script and returns the non-empty lines of the output.
+    fn run(script: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        Interpreter::new(&mut out)
+            .run_script(&parse_all(script).unwrap())
+            .unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_difference_logic() {
+        let script = "
+            (set-logic QF_IDL)
+            (declare-fun a () Int)
+            (declare-fun b () Int)
+            (declare-fun c () Int)
+            (assert (< (- a b) 0))
+            (assert (<= (- b c) (- 3)))
+            (assert (or (>= a c) (= a 0)))
+            (assert (>= a 0))
+            (check-sat)
+            (get-model)
+            (assert (< c 3))
+            (check-sat)";
+        assert_eq!(
+            run(script),
+            [
+                "sat",
+                "(",
+                "(define-fun a () Int 0)",
+                "(define-fun b () Int 1)",
+                "(define-fun c () Int 4)",
+                ")",
+                "unsat"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linear_arithmetic() {
+        let script = "
+            (set-logic QF_LIA)
+            (declare-const x Int)
+            (declare-const y Int)
+            (declare-const p Bool)
+            (define-fun s () Int (+ x (* 2 y) (- 1)))
+            (assert (= s 10))
+            (assert (distinct x y))
+            (assert (let ((lo (- y 3))) (and (>= x lo) (>= y 0) (<= y 5))))
+            (assert (= p (> x 4)))
+            (assert (not p))
+            (check-sat)
+            (get-model)
+            (assert (= (ite p 1 x) 3))
+            (check-sat)
+            (get-model)
+            (assert (> (* x y) 1))";
+        let out = run(script);
+        assert_eq!(out[0], "sat");
+        // x + 2y = 11 with x in [y-3, 4] and y in [0, 5], x != y
+        assert!(
+            out.contains(&"(define-fun x () Int 1)".to_string())
+                || out.contains(&"(define-fun x () Int 3)".to_string())
+        );
+        assert_eq!(out[6], "sat");
+        assert_eq!(out[8], "(define-fun x () Int 3)");
+        assert_eq!(out[9], "(define-fun y () Int 4)");
+        assert!(out.last().unwrap().starts_with("(error \"Non-linear multiplication"));
+    }
+
+    #[test]
+    fn test_uninterpreted_functions() {
+        let script = "
+            (set-logic QF_UFLIA)
+            (declare-fun f (Int) Int)
+            (declare-fun p (Int Bool) Bool)
+            (declare-const x Int)
+            (declare-const y Int)
+            (assert (and (>= x 0) (<= x 1) (>= y 0) (<= y 1)))
+            (assert (and (>= (f x) 0) (<= (f x) 3) (>= (f y) 0) (<= (f y) 3)))
+            (assert (distinct (f x) (f y)))
+            (assert (p (+ x 1) true))
+            (check-sat)
+            (push 1)
+            (assert (= x y))
+            (check-sat)
+            (pop 1)
+            (assert (not (p 1 (= x x))))
+            (check-sat)
+            (get-model)
+            (assert (f x y))";
+        let out = run(script);
+        // congruence: x = y implies f(x) = f(y)
+        assert_eq!(out[..3], ["sat", "unsat", "sat"]);
+        // p(x + 1, true) and not p(1, true) force x = 1
+        assert_eq!(out[4], "(define-fun x () Int 1)");
+        assert_eq!(out[5], "(define-fun y () Int 0)");
+        assert!(out[6].starts_with("(define-fun f ((x!0 Int)) Int (ite (= x!0 "));
+        assert!(out[7].starts_with("(define-fun p ((x!0 Int) (x!1 Bool)) Bool (ite (and (= x!0 "));
+        assert!(out[9].starts_with("(error \"In (f x y): Expected 1 arguments but got 2"));
+    }
+
+    #[test]
+    fn test_incremental() {
+        let script = "
+            (set-option :print-success true)
+            (set-logic QF_BOOL)
+            (declare-fun a () Bool)
+            (declare-fun b () Bool)
+            (assert (xor a b))
+            (push 1)
+            (declare-fun c () Bool)
+            (assert (! (and a c) :named ac))
+            (assert (! b :named b))
+            (assert (! (=> a c) :named imp))
+            (check-sat)
+            (get-unsat-core)
+            (pop 1)
+            (assert c)
+            (check-sat)
+            (check-sat-assuming (a (not b)))
+            (check-sat-assuming (a b))
+            (get-unsat-core)
+            (get-model)
+            (exit)
+            (check-sat)";
+        assert_eq!(
+            run(script),
+            [
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "success",
+                "unsat",
+                "(ac b)",
+                "success",
+                "(error \"Unknown symbol: c\")",
+                "sat",
+                "sat",
+                "unsat",
+                "(a b)",
+                "(error \"The last check-sat did not return sat\")",
+                "success",
+            ]
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! An SMT-LIB 2 front-end for the aries solver, supporting the `QF_IDL`, `QF_LIA` (bounded) and `QF_BOOL` logics.
+
+pub mod interpreter;
+pub mod sexpr;
+
+pub use interpreter::Interpreter;
@@ -0,0 +1,31 @@
+use anyhow::{Context, Result};
+use aries_smt::sexpr::parse_all;
+use aries_smt::Interpreter;
+use std::io::Read;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "aries-smt",
+    about = "Solves SMT-LIB 2 scripts in QF_IDL, QF_LIA (bounded) and QF_BOOL"
+)]
+struct Opt {
+    /// SMT-LIB 2 script to execute. If absent, the script is read from the standard input.
+    file: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let script = match &opt.file {
+        Some(file) => std::fs::read_to_string(file).with_context(|| format!("Could not read {}", file.display()))?,
+        None => {
+            let mut script = String::new();
+            std::io::stdin().read_to_string(&mut script)?;
+            script
+        }
+    };
+    let commands = parse_all(&script)?;
+    let mut interpreter = Interpreter::new(std::io::stdout().lock());
+    interpreter.run_script(&commands)
+}
@@ -0,0 +1,155 @@
+//! A minimal reader for the S-expressions of SMT-LIB 2 scripts.
+//!
+//! Contrary to the PDDL parser, symbols are case-sensitive and quoted symbols (`|a b|`) are supported.
+
+use anyhow::{bail, Result};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SExpr {
+    /// A symbol, keyword, numeral or string literal (the latter keeping its surrounding quotes).
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(a) => Some(a.as_str()),
+            SExpr::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[SExpr]> {
+        match self {
+            SExpr::Atom(_) => None,
+            SExpr::List(l) => Some(l.as_slice()),
+        }
+    }
+}
+
+impl Display for SExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(a) => write!(f, "{a}"),
+            SExpr::List(l) => {
+                write!(f, "(")?;
+                for (i, e) in l.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{e}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Parses all top-level S-expressions of the input.
+pub fn parse_all(input: &str) -> Result<Vec<SExpr>> {
+    let mut chars = input.chars().peekable();
+    // stack of the lists currently being built
+    let mut stack: Vec<Vec<SExpr>> = Vec::new();
+    let mut parsed = Vec::new();
+
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            ';' => {
+                // comment until the end of the line
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '(' => {
+                stack.push(Vec::new());
+                continue;
+            }
+            ')' => {
+                let Some(list) = stack.pop() else {
+                    bail!("Unexpected closing parenthesis")
+                };
+                push(&mut stack, &mut parsed, SExpr::List(list));
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '"' => {
+                // string literal, where a double quote is escaped by another double quote
+                let mut s = String::from('"');
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            s.push_str("\"\"");
+                        }
+                        Some('"') => break,
+                        Some(c) => s.push(c),
+                        None => bail!("Unterminated string literal"),
+                    }
+                }
+                s.push('"');
+                s
+            }
+            '|' => {
+                // quoted symbol, the bars are not part of the symbol
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('|') => break,
+                        Some(c) => s.push(c),
+                        None => bail!("Unterminated quoted symbol"),
+                    }
+                }
+                s
+            }
+            c => {
+                let mut s = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == ';' || c == '"' || c == '|' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                s
+            }
+        };
+        push(&mut stack, &mut parsed, SExpr::Atom(atom));
+    }
+    if !stack.is_empty() {
+        bail!("Unexpected end of input: missing closing parenthesis")
+    }
+    Ok(parsed)
+}
+
+fn push(stack: &mut [Vec<SExpr>], parsed: &mut Vec<SExpr>, e: SExpr) {
+    match stack.last_mut() {
+        Some(list) => list.push(e),
+        None => parsed.push(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn formats_as(input: &str, output: &[&str]) {
+        let parsed = parse_all(input).unwrap();
+        let formatted: Vec<String> = parsed.iter().map(|e| e.to_string()).collect();
+        assert_eq!(formatted, output);
+    }
+
+    #[test]
+    fn test_parsing() {
+        formats_as("(assert (< x 2)) (check-sat)", &["(assert (< x 2))", "(check-sat)"]);
+        formats_as("(a ; comment ( \n B)", &["(a B)"]);
+        formats_as("(echo \"a \"\"b\"\" ; c\")", &["(echo \"a \"\"b\"\" ; c\")"]);
+        formats_as("(declare-const |a b| Int)", &["(declare-const a b Int)"]);
+        formats_as("(! (= x y) :named a1)", &["(! (= x y) :named a1)"]);
+        assert!(parse_all("(a (b)").is_err());
+        assert!(parse_all("a)").is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use anyhow::{bail, ensure, Context, Error};
 use aries::model::extensions::SavedAssignment;
+use aries::solver::parallel::portfolio::Portfolio;
+use aries::solver::stats::StatsReport;
 use aries_grpc_server::chronicles::problem_to_chronicles;
 use aries_grpc_server::serialize::{engine, serialize_plan};
 use aries_plan_validator::validate_upf;
 use aries_planners::solver;
-use aries_planners::solver::{Metric, SolverResult, Strat};
+use aries_planners::solver::{DepthStats, Metric, SolverResult, Strat};
 use aries_planning::chronicles::analysis::hierarchy::hierarchical_is_non_recursive;
 use aries_planning::chronicles::FiniteProblem;
 use async_trait::async_trait;
@@ -13,6 +15,7 @@ use env_param::EnvParam;
 use itertools::Itertools;
 use prost::Message;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
@@ -84,6 +87,11 @@ pub struct SolverConfiguration {
     /// Allowed values: forward | activity | activity-bool | activity-bool-light | causal
     #[clap(long = "strategy", short = 's')]
     strategies: Vec<Strat>,
+
+    /// Path to a TOML file describing the configuration of each parallel worker.
+    /// If provided, the `--strategy` options are ignored.
+    #[clap(long)]
+    portfolio: Option<PathBuf>,
 }
 
 impl Default for SolverConfiguration {
@@ -94,6 +102,7 @@ impl Default for SolverConfiguration {
             min_depth: 0,
             max_depth: u32::MAX,
             strategies: Vec::new(),
+            portfolio: None,
         }
     }
 }
@@ -113,6 +122,7 @@ impl SolverConfiguration {
                 "max_depth" | "max-depth" => {
                     self.max_depth = value.parse().context("Unreadable value for `max-depth`.)?")?
                 }
+                "portfolio" => self.portfolio = Some(PathBuf::from(value)),
                 _ => bail!("Unknown config key: {key}"),
             }
         }
@@ -192,22 +202,27 @@ fn solve_blocking(
             Err(err) => eprintln!("Error when serializing intermediate plan: {err}"),
         }
     };
+    let portfolio = conf.portfolio.as_ref().map(Portfolio::from_file).transpose()?;
+
     // run solver
+    let mut stats = Vec::new();
     let result = solver::solve(
         base_problem,
         min_depth,
         max_depth,
         &conf.strategies,
+        portfolio.as_ref(),
         metric,
         htn_mode,
         on_new_solution,
         deadline,
+        &mut stats,
     )?;
-    match result {
-        SolverResult::Sol((finite_problem, plan)) => {
+    let mut answer = match result {
+        SolverResult::Sol((finite_problem, plan, _)) => {
             println!(
                 "************* SOLUTION FOUND **************\n\n{}",
-                solver::format_plan(&finite_problem, &plan, htn_mode)?
+                solver::format_plan(&finite_problem, &plan, None, htn_mode)?
             );
             let status = if metric.is_some() && bounded {
                 up::plan_generation_result::Status::SolvedOptimally
@@ -215,28 +230,28 @@ fn solve_blocking(
                 up::plan_generation_result::Status::SolvedSatisficing
             };
             let plan = serialize_plan(&problem, &finite_problem, &plan)?;
-            Ok(up::PlanGenerationResult {
+            up::PlanGenerationResult {
                 status: status as i32,
                 plan: Some(plan),
                 metrics: Default::default(),
                 log_messages: vec![],
                 engine: Some(aries_grpc_server::serialize::engine()),
-            })
+            }
         }
         SolverResult::Unsat => {
             println!("************* NO PLAN **************");
-            Ok(up::PlanGenerationResult {
+            up::PlanGenerationResult {
                 status: up::plan_generation_result::Status::UnsolvableIncompletely as i32,
                 plan: None,
                 metrics: Default::default(),
                 log_messages: vec![],
                 engine: Some(engine()),
-            })
+            }
         }
         SolverResult::Timeout(opt_plan) => {
             println!("************* TIMEOUT **************");
-            let opt_plan = if let Some((finite_problem, plan)) = opt_plan {
-                println!("\n{}", solver::format_plan(&finite_problem, &plan, htn_mode)?);
+            let opt_plan = if let Some((finite_problem, plan, _)) = opt_plan {
+                println!("\n{}", solver::format_plan(&finite_problem, &plan, None, htn_mode)?);
                 Some(serialize_plan(&problem, &finite_problem, &plan)?)
             } else {
                 None
@@ -248,16 +263,19 @@ fn solve_blocking(
                 up::plan_generation_result::Status::SolvedSatisficing
             };
 
-            Ok(up::PlanGenerationResult {
+            up::PlanGenerationResult {
                 status: status as i32,
                 plan: opt_plan,
                 metrics: Default::default(),
                 log_messages: vec![],
                 engine: Some(engine()),
-            })
+            }
         }
-    }
+    };
+    add_solver_stats(&mut answer.metrics, &stats);
+    Ok(answer)
 }
+
 #[derive(Default)]
 pub struct UnifiedPlanningService {}
 
@@ -482,6 +500,23 @@ async fn main() -> Result<(), Error> {
     Ok(())
 }
 
+/// Adds the statistics of all solvers (accumulated over all depths and workers) to the metrics, with an `aries_` prefix.
+///
+/// Counters (e.g. decisions, conflicts or `SAT.unit_propagations`) are summed, while the sizes of the reasoners
+/// (e.g. `SAT.db_size`) hold their maximum value over all solvers.
+fn add_solver_stats(metrics: &mut HashMap<String, String>, stats: &[DepthStats]) {
+    let mut total = StatsReport::default();
+    for worker in stats.iter().flat_map(|depth| &depth.workers) {
+        total += worker;
+    }
+    if let Some(depth) = stats.last() {
+        metrics.insert("aries_depth".to_string(), depth.depth.to_string());
+    }
+    for (key, value) in total.flatten() {
+        metrics.insert(format!("aries_{key}"), value);
+    }
+}
+
 /// Adds a measure of the time spent in the engine in a the metrics
 fn add_engine_time(metrics: &mut HashMap<String, String>, start: &Instant) {
     metrics.insert(
@@ -627,6 +627,7 @@ impl<'a> ChronicleFactory<'a> {
             }
         };
         self.chronicle.effects.push(Effect {
+            presence: self.chronicle.presence,
             transition_start: span.start,
             transition_end: span.end,
             min_mutex_end: Vec::new(),
@@ -711,6 +712,7 @@ impl<'a> ChronicleFactory<'a> {
         let sv = StateVar { fluent, args };
 
         self.chronicle.effects.push(Effect {
+            presence: self.chronicle.presence,
             transition_start: self.context.origin(),
             transition_end: self.context.origin(),
             min_mutex_end: vec![],
@@ -841,6 +843,7 @@ impl<'a> ChronicleFactory<'a> {
         };
 
         let condition = Condition {
+            presence: self.chronicle.presence,
             start: span.start,
             end: span.end,
             state_var,
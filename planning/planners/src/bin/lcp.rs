@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
 use aries::core::state::Domains;
+use aries::reasoners::stn::theory::DispatchableStn;
+use aries::solver::parallel::portfolio::Portfolio;
 use aries::utils::input::Input;
 use aries_planners::solver::{format_plan, solve, SolverResult};
 use aries_planners::solver::{Metric, Strat};
@@ -49,6 +51,11 @@ pub struct Opt {
     #[structopt(long = "strategy", short = "s")]
     strategies: Vec<Strat>,
 
+    /// Path to a TOML file describing the configuration of each parallel worker.
+    /// If provided, the `--strategy` options are ignored.
+    #[structopt(long)]
+    portfolio: Option<PathBuf>,
+
     /// Logging level to use: one of "error", "warn", "info", "debug", "trace"
     #[structopt(short, long, default_value = "info")]
     log_level: tracing::Level,
@@ -62,6 +69,10 @@ pub struct Opt {
     /// If set, the planner will exit with a non-zero exit code if it found a solution
     #[structopt(long)]
     unsat: bool,
+
+    /// If provided, the statistics of the solvers run at each depth will be written as JSON to this file upon termination.
+    #[structopt(long)]
+    stats_json: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -120,8 +131,11 @@ fn main() -> Result<()> {
     };
 
     // prints a plan to a standard output and to the provided file, if any
-    let print_plan = move |finite_problem: &FiniteProblem, assignment: &Domains, output_file: Option<&PathBuf>| {
-        if let Ok(plan_out) = format_plan(finite_problem, assignment, htn_mode) {
+    let print_plan = move |finite_problem: &FiniteProblem,
+                           assignment: &Domains,
+                           network: Option<&DispatchableStn>,
+                           output_file: Option<&PathBuf>| {
+        if let Ok(plan_out) = format_plan(finite_problem, assignment, network, htn_mode) {
             println!("\n{plan_out}");
 
             // Write the output to a file if requested
@@ -134,21 +148,45 @@ fn main() -> Result<()> {
             tracing::error!("Problem while formatting plan.")
         }
     };
+    let portfolio = opt.portfolio.as_ref().map(Portfolio::from_file).transpose()?;
+
     let anytime_out_file = if opt.anytime { opt.plan_out_file.clone() } else { None };
+    let mut stats = Vec::new();
     let result = solve(
         spec,
         min_depth,
         max_depth,
         &opt.strategies,
+        portfolio.as_ref(),
         opt.optimize,
         htn_mode,
-        |pb, sol| print_plan(pb, &sol, anytime_out_file.as_ref()),
+        |pb, sol| print_plan(pb, &sol, None, anytime_out_file.as_ref()),
         None,
+        &mut stats,
     )?;
 
+    if let Some(stats_file) = &opt.stats_json {
+        let status = match &result {
+            SolverResult::Sol(_) => "SOLVED",
+            SolverResult::Unsat => "UNSOLVABLE",
+            SolverResult::Timeout(_) => "TIMEOUT",
+        };
+        let stats = serde_json::json!({
+            "status": status,
+            "depths": stats,
+        });
+        let file = File::create(stats_file).with_context(|| format!("Could not create {}", stats_file.display()))?;
+        serde_json::to_writer_pretty(file, &stats)?;
+    }
+
     match result {
-        SolverResult::Sol((finite_problem, assignment)) => {
-            print_plan(&finite_problem, &assignment, opt.plan_out_file.as_ref());
+        SolverResult::Sol((finite_problem, assignment, network)) => {
+            print_plan(
+                &finite_problem,
+                &assignment,
+                network.as_ref(),
+                opt.plan_out_file.as_ref(),
+            );
             anyhow::ensure!(!opt.unsat, "Solution found to an unsat problem.");
         }
         SolverResult::Unsat => {
@@ -120,6 +120,11 @@ pub fn instantiate(
         }
         let label = lbl_of_new(v, &pb.model);
         let fresh: Variable = match v {
+            // non-optional boolean parameters are presence variables of conditional elements of the chronicle
+            // (e.g. conditional effects), and must be placed in the scope of the chronicle
+            Variable::Bool(b) if pb.model.presence_literal(b) == Lit::TRUE => {
+                pb.model.new_presence_variable(prez_lit, label).into()
+            }
             Variable::Bool(_) => pb.model.new_optional_bvar(prez_lit, label).into(),
             Variable::Int(i) => {
                 let (lb, ub) = pb.model.int_bounds(i);
@@ -637,7 +642,11 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
                 clause.push(solver.reify(f_leq(eff_mutex_ends[&i], e2.transition_start)));
 
                 // add coherence constraint
-                solver.enforce(or(clause.as_slice()), [p1, p2]);
+                // The presence of the chronicles is implied by the ones of the effects but must be given explicitly
+                // for the scope to match the one of the clause when effects are conditional.
+                let ch1 = pb.chronicles[i.instance_id].chronicle.presence;
+                let ch2 = pb.chronicles[j.instance_id].chronicle.presence;
+                solver.enforce(or(clause.as_slice()), [p1, p2, ch1, ch2]);
                 num_coherence_constraints += 1;
             }
         }
@@ -682,6 +691,12 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
                 let mut supported_by_eff_conjunction: Vec<Lit> = Vec::with_capacity(32);
                 // support only possible if the effect is present
                 supported_by_eff_conjunction.push(prez_eff);
+                // the presence of a conditional effect implies the one of its chronicle, make it explicit so that
+                // the validity scope of the conjunction does not depend on the presence of the effect's chronicle
+                let prez_eff_chronicle = pb.chronicles[eff_id.instance_id].chronicle.presence;
+                if prez_eff_chronicle != prez_eff {
+                    supported_by_eff_conjunction.push(prez_eff_chronicle);
+                }
                 debug_assert_eq!(cond.state_var.fluent, eff.state_var.fluent);
                 // same state variable
                 for idx in 0..cond.state_var.args.len() {
@@ -781,6 +796,7 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
                 CondID::new_post_increase(inc_id.instance_id, inc_id.eff_id),
                 prez,
                 Condition {
+                    presence: prez,
                     start: inc.transition_end,
                     end: inc.transition_end,
                     state_var: inc.state_var.clone(),
@@ -973,6 +989,9 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
                         continue; // an action cannot be mutex with itself
                     }
                     for eff in &act2.chronicle.effects {
+                        if solver.model.state.exclusive(cond.presence, eff.presence) {
+                            continue;
+                        }
                         // `cond` and `eff` are a condition and an effect from two distinct action
                         if !unifiable_sv(&solver.model, &cond.state_var, &eff.state_var) {
                             continue;
@@ -992,7 +1011,15 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
                         non_overlapping.push(solver.reify(f_lt(cond.end, eff.transition_start)));
                         non_overlapping.push(solver.reify(f_leq(eff.transition_end, cond.start)));
 
-                        solver.enforce(or(non_overlapping), [act1.chronicle.presence, act2.chronicle.presence]);
+                        solver.enforce(
+                            or(non_overlapping),
+                            [
+                                cond.presence,
+                                eff.presence,
+                                act1.chronicle.presence,
+                                act2.chronicle.presence,
+                            ],
+                        );
                         num_mutex_constraints += 1;
                     }
                 }
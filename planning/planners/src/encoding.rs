@@ -81,13 +81,11 @@ impl Encoding {
 /// - a literal that is true iff the effect is present in the solution.
 pub fn effects(pb: &FiniteProblem) -> impl Iterator<Item = (EffID, Lit, &Effect)> {
     pb.chronicles.iter().enumerate().flat_map(|(instance_id, ch)| {
-        ch.chronicle.effects.iter().enumerate().map(move |(eff_id, eff)| {
-            (
-                EffID::new(instance_id, eff_id, is_assignment(eff)),
-                ch.chronicle.presence,
-                eff,
-            )
-        })
+        ch.chronicle
+            .effects
+            .iter()
+            .enumerate()
+            .map(move |(eff_id, eff)| (EffID::new(instance_id, eff_id, is_assignment(eff)), eff.presence, eff))
     })
 }
 
@@ -122,7 +120,7 @@ pub fn conditions(pb: &FiniteProblem) -> impl Iterator<Item = (CondID, Lit, &Con
             .conditions
             .iter()
             .enumerate()
-            .map(move |(cond_id, cond)| (CondID::new_explicit(instance_id, cond_id), ch.chronicle.presence, cond))
+            .map(move |(cond_id, cond)| (CondID::new_explicit(instance_id, cond_id), cond.presence, cond))
     })
 }
 
@@ -8,6 +8,7 @@ use std::fmt::Write;
 use crate::Model;
 use aries::model::extensions::{AssignmentExt, SavedAssignment, Shaped};
 use aries::model::lang::{Atom, Cst};
+use aries::reasoners::stn::theory::DispatchableStn;
 use aries_planning::chronicles::plan::ActionInstance;
 use aries_planning::chronicles::{
     ChronicleInstance, ChronicleKind, ChronicleOrigin, FiniteProblem, SubTask, TaskId, TIME_SCALE,
@@ -289,6 +290,45 @@ pub fn format_pddl_plan(problem: &FiniteProblem, ass: &SavedAssignment) -> Resul
     Ok(out)
 }
 
+/// Formats a plan in the PDDL format, where each action is annotated (in a comment) with the `[min,max]` window
+/// in which it may start according to the dispatchable temporal `network` of the plan.
+pub fn format_dispatchable_plan(
+    problem: &FiniteProblem,
+    ass: &SavedAssignment,
+    network: &DispatchableStn,
+) -> Result<String> {
+    let mut plan = Vec::new();
+    for ch in &problem.chronicles {
+        let start = ch.chronicle.start;
+        let start_value = ass.f_domain(start).lb();
+        let (min, max) = match network.window(start.num.var.into()) {
+            Some((lb, ub)) => (
+                Rational32::new(lb + start.num.shift, start.denom),
+                Rational32::new(ub + start.num.shift, start.denom),
+            ),
+            None => (start_value, start_value),
+        };
+        for a in extract_plan_actions(ch, problem, ass)? {
+            // unrolled actions are shifted with respect to the chronicle they originate from
+            let offset = a.start - start_value;
+            plan.push((a, min + offset, max + offset));
+        }
+    }
+    plan.sort_by_key(|(a, _, _)| a.start);
+
+    let mut out = String::new();
+    for (a, min, max) in &plan {
+        let start = str(a.start);
+        let duration = str(a.duration);
+        write!(out, "{start:>5}: ({}", a.name)?;
+        for &p in &a.params {
+            write!(out, " {}", format_cst(p, &problem.model))?;
+        }
+        writeln!(out, ") [{duration}] ; [{}, {}]", str(*min), str(*max))?;
+    }
+    Ok(out)
+}
+
 /// Formats a hierarchical plan into the format expected by pandaPIparser's verifier
 pub fn format_hddl_plan(problem: &FiniteProblem, ass: &SavedAssignment) -> Result<String> {
     let mut f = String::new();
@@ -1,6 +1,6 @@
 use crate::encode::{encode, populate_with_task_network, populate_with_template_instances, EncodedProblem};
 use crate::encoding::Encoding;
-use crate::fmt::{format_hddl_plan, format_partial_plan, format_pddl_plan};
+use crate::fmt::{format_dispatchable_plan, format_hddl_plan, format_partial_plan, format_pddl_plan};
 use crate::search::{ForwardSearcher, ManualCausalSearch};
 use crate::Solver;
 use anyhow::Result;
@@ -9,16 +9,19 @@ use aries::core::{IntCst, Lit, VarRef, INT_CST_MAX};
 use aries::model::extensions::{AssignmentExt, SavedAssignment};
 use aries::model::lang::IAtom;
 use aries::model::Model;
-use aries::reasoners::stn::theory::{StnConfig, TheoryPropagationLevel};
+use aries::reasoners::stn::theory::{DispatchableStn, StnConfig, TheoryPropagationLevel};
+use aries::solver::parallel::portfolio::Portfolio;
 use aries::solver::parallel::Solution;
 use aries::solver::search::activity::*;
 use aries::solver::search::conflicts::ConflictBasedBrancher;
 use aries::solver::search::lexical::Lexical;
 use aries::solver::search::{Brancher, SearchControl};
+use aries::solver::stats::StatsReport;
 use aries_planning::chronicles::printer::Printer;
 use aries_planning::chronicles::Problem;
 use aries_planning::chronicles::*;
 use env_param::EnvParam;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
@@ -32,8 +35,24 @@ static PRINT_RAW_MODEL: EnvParam<bool> = EnvParam::new("ARIES_PRINT_RAW_MODEL",
 /// If set to true, will print the preprocessed model
 static PRINT_MODEL: EnvParam<bool> = EnvParam::new("ARIES_PRINT_MODEL", "false");
 
+/// If set to true, the dispatchable temporal network of the final plan will be extracted,
+/// allowing to print the plan with a `[min,max]` window for the start of each action.
+static DISPATCHABLE_PLAN: EnvParam<bool> = EnvParam::new("ARIES_DISPATCHABLE_PLAN", "false");
+
 pub type SolverResult<Sol> = aries::solver::parallel::SolverResult<Sol>;
 
+/// A plan, given as the subproblem in which it was found, the instantiation of its variables and,
+/// if requested, the dispatchable temporal network that it satisfies.
+pub type Plan = (Arc<FiniteProblem>, Arc<Domains>, Option<DispatchableStn>);
+
+/// Statistics of the solvers that were run on the subproblem of a given depth.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DepthStats {
+    pub depth: u32,
+    /// Statistics of each parallel worker.
+    pub workers: Vec<StatsReport>,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Metric {
     /// Total duration of the plan
@@ -74,18 +93,25 @@ impl FromStr for Metric {
 /// The `depth` parameter is increased until a plan is found or foes over `max_depth`.
 ///
 /// When a plan is found, the solver returns the corresponding subproblem and the instantiation of
-/// its variables.
+/// its variables. If `ARIES_DISPATCHABLE_PLAN` is set, it is accompanied by the dispatchable temporal network
+/// satisfied by the plan.
+///
+/// If a `portfolio` is provided, it takes precedence over the given `strategies`.
+///
+/// The statistics of the solvers run at each depth are appended to `stats`.
 #[allow(clippy::too_many_arguments)]
 pub fn solve(
     mut base_problem: Problem,
     min_depth: u32,
     max_depth: u32,
     strategies: &[Strat],
+    portfolio: Option<&Portfolio>,
     metric: Option<Metric>,
     htn_mode: bool,
     on_new_sol: impl Fn(&FiniteProblem, Arc<SavedAssignment>) + Clone,
     deadline: Option<Instant>,
-) -> Result<SolverResult<(Arc<FiniteProblem>, Arc<Domains>)>> {
+    stats: &mut Vec<DepthStats>,
+) -> Result<SolverResult<Plan>> {
     if PRINT_RAW_MODEL.get() {
         Printer::print_problem(&base_problem);
     }
@@ -129,26 +155,30 @@ pub fn solve(
             move |ass: Arc<SavedAssignment>| on_new_sol(&pb, ass)
         };
         println!("  [{:.3}s] Populated", start.elapsed().as_secs_f32());
+        let mut workers = Vec::new();
         let result = solve_finite_problem(
             pb.clone(),
             strategies,
+            portfolio,
             metric,
             htn_mode,
             on_new_valid_assignment,
             deadline,
             best_cost - 1,
-        );
+            &mut workers,
+        )?;
         println!("  [{:.3}s] Solved", start.elapsed().as_secs_f32());
+        stats.push(DepthStats { depth, workers });
 
         let result = result.map(|assignment| (pb, assignment));
         match result {
             SolverResult::Unsat => {} // continue (increase depth)
-            SolverResult::Sol((_, (_, cost))) if metric.is_some() && depth < max_depth => {
+            SolverResult::Sol((_, (_, cost, _))) if metric.is_some() && depth < max_depth => {
                 let cost = cost.expect("Not cost provided in optimization problem");
                 assert!(cost < best_cost);
                 best_cost = cost; // continue with new cost bound
             }
-            other => return Ok(other.map(|(pb, (ass, _))| (pb, ass))),
+            other => return Ok(other.map(|(pb, (ass, _, network))| (pb, ass, network))),
         }
     }
     Ok(SolverResult::Unsat)
@@ -179,7 +209,17 @@ fn propagate_and_print(pb: &FiniteProblem) -> bool {
     std::process::exit(0)
 }
 
-pub fn format_plan(problem: &FiniteProblem, assignment: &Domains, htn_mode: bool) -> Result<String> {
+/// Formats the plan, with start windows for all actions if its dispatchable temporal `network` is provided.
+pub fn format_plan(
+    problem: &FiniteProblem,
+    assignment: &Domains,
+    network: Option<&DispatchableStn>,
+    htn_mode: bool,
+) -> Result<String> {
+    let format_actions = || match network {
+        Some(network) => format_dispatchable_plan(problem, assignment, network),
+        None => format_pddl_plan(problem, assignment),
+    };
     let plan = if htn_mode {
         format!(
             "\n**** Decomposition ****\n\n\
@@ -187,10 +227,10 @@ pub fn format_plan(problem: &FiniteProblem, assignment: &Domains, htn_mode: bool
              **** Plan ****\n\n\
              {}",
             format_hddl_plan(problem, assignment)?,
-            format_pddl_plan(problem, assignment)?
+            format_actions()?
         )
     } else {
-        format_pddl_plan(problem, assignment)?
+        format_actions()?
     };
     Ok(plan)
 }
@@ -325,24 +365,32 @@ impl FromStr for Strat {
     }
 }
 
+/// A solution of a subproblem, tagged with its cost and, if requested, its dispatchable temporal network.
+type TaggedSolution = (Solution, Option<IntCst>, Option<DispatchableStn>);
+
 /// Instantiates a solver for the given subproblem and attempts to solve it.
 ///
 /// If more than one strategy is given, each strategy will have its own solver run on a dedicated thread.
 /// If no strategy is given, then a default set of strategies will be automatically selected.
+/// If a portfolio is given, each of its worker is run on a dedicated thread and the strategies are ignored.
+/// A `custom` brancher in the portfolio designates one of the planner's strategies (e.g. `causal` or `forward`).
 ///
 /// If a valid solution of the subproblem is found, the solver will return a satisfying assignment.
+#[allow(clippy::too_many_arguments)]
 fn solve_finite_problem(
     pb: Arc<FiniteProblem>,
     strategies: &[Strat],
+    portfolio: Option<&Portfolio>,
     metric: Option<Metric>,
     htn_mode: bool,
     on_new_solution: impl Fn(Arc<SavedAssignment>),
     deadline: Option<Instant>,
     cost_upper_bound: IntCst,
-) -> SolverResult<(Solution, Option<IntCst>)> {
+    stats: &mut Vec<StatsReport>,
+) -> Result<SolverResult<TaggedSolution>> {
     if let Some(deadline) = deadline {
         if deadline <= Instant::now() {
-            return SolverResult::Timeout(None);
+            return Ok(SolverResult::Timeout(None));
         }
     }
     if PRINT_INITIAL_PROPAGATION.get() {
@@ -354,7 +402,7 @@ fn solve_finite_problem(
         encoding,
     }) = encode(&pb, metric)
     else {
-        return SolverResult::Unsat;
+        return Ok(SolverResult::Unsat);
     };
     if let Some(metric) = metric {
         model.enforce(metric.le_lit(cost_upper_bound), []);
@@ -370,9 +418,18 @@ fn solve_finite_problem(
     } else {
         &GEN_DEFAULT_STRATEGIES
     };
-    let mut solver = aries::solver::parallel::ParSolver::new(solver, strats.len(), |id, s| {
-        strats[id].adapt_solver(s, pb.clone(), encoding.clone())
-    });
+    let mut solver = if let Some(portfolio) = portfolio {
+        let custom = |name: &str, s: &mut Solver| {
+            let strat = Strat::from_str(name).map_err(anyhow::Error::msg)?;
+            strat.adapt_solver(s, pb.clone(), encoding.clone());
+            Ok(())
+        };
+        portfolio.build_solver(solver, custom)?
+    } else {
+        aries::solver::parallel::ParSolver::new(solver, strats.len(), |id, s| {
+            strats[id].adapt_solver(s, pb.clone(), encoding.clone())
+        })
+    };
 
     let result = if let Some(metric) = metric {
         solver.minimize_with(metric, on_new_solution, deadline)
@@ -380,14 +437,31 @@ fn solve_finite_problem(
         solver.solve(deadline)
     };
 
-    // tag result with cost
+    // tag result with cost and, if requested, the temporal network of the plan
     let result = result.map(|s| {
         let cost = metric.map(|metric| s.domain_of(metric).0);
-        (s, cost)
+        (s, cost, None)
     });
+    let result = if DISPATCHABLE_PLAN.get() {
+        // the network is extracted from the solver that concluded the search, which is not available on a timeout
+        let with_network = |(s, cost, _): TaggedSolution| {
+            let network = match solver.winning_solver() {
+                Some(winner) => Some(winner.reasoners.diff.dispatchable_network(&s)),
+                None => {
+                    eprintln!("WARNING: no dispatchable network available for a plan found before the timeout.");
+                    None
+                }
+            };
+            (s, cost, network)
+        };
+        result.map(with_network)
+    } else {
+        result
+    };
 
     if let SolverResult::Sol(_) = result {
         solver.print_stats()
     }
-    result
+    stats.extend(solver.stats_reports());
+    Ok(result)
 }
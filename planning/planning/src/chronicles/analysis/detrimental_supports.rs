@@ -1,4 +1,4 @@
-use crate::chronicles::analysis::is_static;
+use crate::chronicles::analysis::{features, is_static, Feature};
 use crate::chronicles::{EffectOp, Fluent, Problem};
 use aries::core::Lit;
 use aries::model::lang::{Atom, SAtom};
@@ -99,6 +99,10 @@ pub fn find_useless_supports(pb: &Problem) -> HashSet<CausalSupport> {
             return HashSet::new();
         }
     }
+    // the analysis assumes that all effects of a chronicle are present together, abort if some are conditional
+    if features(pb).contains(&Feature::ConditionalEffects) {
+        return HashSet::new();
+    }
 
     // Extract all values that are useful.
     // A value is useful if it is a goal or a condition of a Chronicle template.
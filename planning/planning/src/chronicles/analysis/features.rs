@@ -7,6 +7,8 @@ pub type FeatureSet = HashSet<Feature>;
 pub enum Feature {
     /// The problem features numeric fluents
     Numeric,
+    /// Some effects of the problem are conditional: their presence differs from the one of their chronicle.
+    ConditionalEffects,
 }
 
 pub fn features(pb: &Problem) -> FeatureSet {
@@ -16,5 +18,15 @@ pub fn features(pb: &Problem) -> FeatureSet {
             features.insert(Feature::Numeric);
         }
     }
+    let chronicles = pb
+        .chronicles
+        .iter()
+        .map(|ch| &ch.chronicle)
+        .chain(pb.templates.iter().map(|t| &t.chronicle));
+    for ch in chronicles {
+        if ch.effects.iter().any(|e| e.presence != ch.presence) {
+            features.insert(Feature::ConditionalEffects);
+        }
+    }
     features
 }
@@ -292,6 +292,9 @@ impl Substitute for StateVar {
 /// For assignment effects, this value will persist until another assignment effect starts its own transition.
 #[derive(Clone)]
 pub struct Effect {
+    /// Literal that is true iff the effect is present in the solution.
+    /// This is the presence of the enclosing chronicle, unless the effect is conditional.
+    pub presence: Lit,
     /// Time at which the transition to the new value will start
     pub transition_start: Time,
     /// Time at which the transition will end
@@ -350,6 +353,7 @@ impl Effect {
 impl Substitute for Effect {
     fn substitute(&self, s: &impl Substitution) -> Self {
         Effect {
+            presence: s.sub_lit(self.presence),
             transition_start: s.fsub(self.transition_start),
             transition_end: s.fsub(self.transition_end),
             min_mutex_end: self.min_mutex_end.iter().map(|t| s.fsub(*t)).collect(),
@@ -373,6 +377,9 @@ impl Substitute for EffectOp {
 /// in ANML: `[start,end] state_var == value`
 #[derive(Clone)]
 pub struct Condition {
+    /// Literal that is true iff the condition must hold in the solution.
+    /// This is the presence of the enclosing chronicle, unless the condition is part of a conditional effect.
+    pub presence: Lit,
     pub start: Time,
     pub end: Time,
     pub state_var: StateVar,
@@ -406,6 +413,7 @@ impl Condition {
 impl Substitute for Condition {
     fn substitute(&self, s: &impl Substitution) -> Self {
         Condition {
+            presence: s.sub_lit(self.presence),
             start: s.fsub(self.start),
             end: s.fsub(self.end),
             state_var: self.state_var.substitute(s),
@@ -563,12 +571,14 @@ impl Chronicle {
             vars.add_atoms(task)
         }
         for cond in &self.conditions {
+            vars.add_lit(cond.presence);
             vars.add_atom(cond.start);
             vars.add_atom(cond.end);
             vars.add_atom(cond.value);
             vars.add_sv(&cond.state_var)
         }
         for eff in &self.effects {
+            vars.add_lit(eff.presence);
             vars.add_atom(eff.transition_start);
             vars.add_atom(eff.transition_end);
             match &eff.operation {
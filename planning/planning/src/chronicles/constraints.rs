@@ -30,6 +30,15 @@ impl Constraint {
         }
     }
 
+    /// A disjunction of literals, at least one of which must hold.
+    pub fn or(disjuncts: impl IntoIterator<Item = Lit>) -> Constraint {
+        Constraint {
+            variables: disjuncts.into_iter().map(Atom::from).collect(),
+            tpe: Or,
+            value: None,
+        }
+    }
+
     pub fn lt(a: impl Into<Atom>, b: impl Into<Atom>) -> Constraint {
         Constraint {
             variables: vec![a.into(), b.into()],
@@ -125,6 +125,14 @@ fn extract_constraints(
         .flat_map(|c| c.variables.iter().map(|atom| atom.variable()))
         .collect();
 
+    // The constraints will be replaced by a table over the action parameters, which would lose any constraint
+    // on other variables (e.g. variables observing the value of a state variable in a disjunctive precondition)
+    let is_action_param =
+        |v: VarRef| v == VarRef::ZERO || v == VarRef::ONE || ch.chronicle.name.iter().any(|p| p.variable() == v);
+    if !variables.iter().all(|&v| is_action_param(v)) {
+        return None;
+    }
+
     // variables appearing in the start/end timepoints
     let start_var = ch.chronicle.start.num.var.variable();
     let end_var = ch.chronicle.end.num.var.variable();
@@ -315,12 +323,14 @@ fn extract_constraints(
 
     // finally reintroduce the condition and effect of the transition
     ch.chronicle.conditions.push(Condition {
+        presence: ch.chronicle.presence,
         start: ch.chronicle.start,
         end: ch.chronicle.start,
         state_var: tr.state_var.clone(),
         value: tr.pre,
     });
     ch.chronicle.effects.push(Effect {
+        presence: ch.chronicle.presence,
         transition_start: ch.chronicle.start,
         transition_end: ch.chronicle.end,
         min_mutex_end: vec![],
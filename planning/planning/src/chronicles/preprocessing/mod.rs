@@ -14,6 +14,7 @@ static PREPRO_UNUSABLE_EFFECTS: EnvParam<bool> = EnvParam::new("ARIES_PLANNING_P
 static PREPRO_MERGE_STATEMENTS: EnvParam<bool> = EnvParam::new("ARIES_PLANNING_PREPRO_MERGE_STATEMENTS", "true");
 static PREPRO_ROLL_ACTIONS: EnvParam<bool> = EnvParam::new("ARIES_ROLL", "true");
 
+use crate::chronicles::analysis::{features, Feature};
 use crate::chronicles::Problem;
 pub use merge_conditions_effects::merge_conditions_effects;
 pub use statics::statics_as_tables;
@@ -23,25 +24,32 @@ pub use unused_effects::remove_unusable_effects;
 pub fn preprocess(problem: &mut Problem) {
     let _span = tracing::span!(tracing::Level::TRACE, "PREPRO").entered();
 
-    if PREPRO_MUTEX_PREDICATES.get() {
+    // Most transformations assume that all conditions and effects of a chronicle are present together,
+    // restrict ourselves to the ones that do not rely on it if some effects are conditional.
+    let conditional = features(problem).contains(&Feature::ConditionalEffects);
+    if conditional {
+        tracing::debug!("Conditional effects in the problem, skipping most preprocessing steps");
+    }
+
+    if PREPRO_MUTEX_PREDICATES.get() && !conditional {
         mutex_predicates::preprocess_mutex_predicates(problem);
     }
 
     if PREPRO_UNUSABLE_EFFECTS.get() {
         remove_unusable_effects(problem);
     }
-    if PREPRO_STATE_VARS.get() {
+    if PREPRO_STATE_VARS.get() && !conditional {
         state_variables::lift_predicate_to_state_variables(problem);
     }
     if PREPRO_STATIC.get() {
         statics_as_tables(problem);
     }
 
-    if PREPRO_MERGE_STATEMENTS.get() {
+    if PREPRO_MERGE_STATEMENTS.get() && !conditional {
         merge_conditions_effects(problem);
         merge_unusable_effects(problem);
     }
-    if PREPRO_ROLL_ACTIONS.get() {
+    if PREPRO_ROLL_ACTIONS.get() && !conditional {
         action_rolling::rollup_actions(problem)
     }
 }
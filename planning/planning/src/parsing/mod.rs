@@ -171,29 +171,23 @@ pub fn pddl_to_chronicles(dom: &pddl::Domain, prob: &pddl::Problem) -> Result<Pb
         Ok(atom.into())
     };
     let as_model_atom = |atom: &sexpr::SAtom| as_model_atom_no_borrow(atom, &context);
-    for goal in &prob.goal {
-        // goal is expected to be a conjunction of the form:
-        //  - `(and (= sv1 v1) (= sv2 = v2))`
-        //  - `(= sv1 v1)`
-        //  - `()`
-        let goals = read_conjunction(goal, as_model_atom, context.model.get_symbol_table(), &context)?;
-        for TermLoc(goal, loc) in goals {
-            match goal {
-                Term::Binding(sv, value) => init_ch.conditions.push(Condition {
-                    start: init_ch.end,
-                    end: init_ch.end,
-                    state_var: sv,
-                    value,
-                }),
-                _ => return Err(loc.invalid("Unsupported in goal expression").into()),
-            }
-        }
-    }
     // If we have negative preconditions, we need to assume a closed world assumption.
-    // Indeed, some preconditions might rely on initial facts being false
-    let closed_world = dom.features.contains(&PddlFeature::NegativePreconditions);
+    // Indeed, some preconditions might rely on initial facts being false.
+    // The same holds for conditional effects and disjunctive/existential preconditions,
+    // that may need to observe the value of a state variable.
+    let closed_world = [
+        PddlFeature::Adl,
+        PddlFeature::NegativePreconditions,
+        PddlFeature::ConditionalEffects,
+        PddlFeature::DisjunctivePreconditions,
+        PddlFeature::QuantifiedPreconditions,
+        PddlFeature::ExistentialPreconditions,
+    ]
+    .iter()
+    .any(|f| dom.features.contains(f));
     for (sv, val) in read_init(&prob.init, closed_world, as_model_atom, &context)? {
         init_ch.effects.push(Effect {
+            presence: init_ch.presence,
             transition_start: init_ch.start,
             transition_end: init_ch.start,
             min_mutex_end: Vec::new(),
@@ -202,6 +196,19 @@ pub fn pddl_to_chronicles(dom: &pddl::Domain, prob: &pddl::Problem) -> Result<Pb
         });
     }
 
+    for goal in &prob.goal {
+        let end = init_ch.end;
+        add_precondition(
+            init_container,
+            goal,
+            end,
+            &as_model_atom_no_borrow,
+            &mut init_ch,
+            None,
+            &mut context,
+        )?;
+    }
+
     if let Some(ref task_network) = &prob.task_network {
         read_task_network(
             init_container,
@@ -410,23 +417,28 @@ fn read_chronicle_template(
         cost,
     };
 
+    // Read all effects, grouped by the conditions under which they apply (empty for unconditional effects).
+    // They are added to the chronicle once all other elements have been read, as it requires creating new variables.
+    let mut effects: Vec<ConditionalEffect> = Vec::new();
     for eff in pddl.effects() {
         if pddl.kind() != ChronicleKind::Action && pddl.kind() != ChronicleKind::DurativeAction {
             return Err(eff.invalid("Unexpected instantaneous effect").into());
         }
-        let effects = read_conjunction(eff, as_chronicle_atom, context.model.get_symbol_table(), context)?;
-        for TermLoc(term, loc) in effects {
-            match term {
-                Term::Binding(sv, val) => ch.effects.push(Effect {
-                    transition_start: ch.end,
-                    transition_end: ch.end + Time::EPSILON,
-                    min_mutex_end: Vec::new(),
-                    state_var: sv,
-                    operation: EffectOp::Assign(val),
-                }),
-                _ => return Err(loc.invalid("Unsupported in action effects").into()),
-            }
-        }
+        // the start and end of an instantaneous action are the same timepoint, we associate all its terms to its end
+        let read_terms = |e: &SExpr, t: &AtomConverter| -> Result<Vec<TemporalTerm>> {
+            let terms = read_conjunction(e, t, context.model.get_symbol_table(), context)?;
+            Ok(terms
+                .into_iter()
+                .map(|term| TemporalTerm(TemporalQualification::AtEnd, term))
+                .collect())
+        };
+        read_effects(
+            eff,
+            &as_chronicle_atom,
+            context.model.get_symbol_table(),
+            &read_terms,
+            &mut effects,
+        )?;
     }
 
     for eff in pddl.timed_effects() {
@@ -434,71 +446,14 @@ fn read_chronicle_template(
             return Err(eff.invalid("Unexpected effect").into());
         }
         // conjunction of effects of the form `(and (at-start (= sv1 v1)) (at-end (= sv2 v2)))`
-        let effects = read_temporal_conjunction(eff, as_chronicle_atom, context)?;
-        for TemporalTerm(qualification, term) in effects {
-            match term.0 {
-                Term::Binding(state_var, value) => match qualification {
-                    TemporalQualification::AtStart => {
-                        ch.effects.push(Effect {
-                            transition_start: ch.start,
-                            transition_end: ch.start + FAtom::EPSILON,
-                            min_mutex_end: Vec::new(),
-                            state_var,
-                            operation: EffectOp::Assign(value),
-                        });
-                    }
-                    TemporalQualification::AtEnd => {
-                        ch.effects.push(Effect {
-                            transition_start: ch.end,
-                            transition_end: ch.end + FAtom::EPSILON,
-                            min_mutex_end: Vec::new(),
-                            state_var,
-                            operation: EffectOp::Assign(value),
-                        });
-                    }
-                    TemporalQualification::OverAll => {
-                        return Err(term.1.invalid("Unsupported in action effects").into())
-                    }
-                },
-                Term::Eq(_a, _b) => return Err(term.1.invalid("Unsupported in action effects").into()),
-                Term::Neq(_a, _b) => return Err(term.1.invalid("Unsupported in action effects").into()),
-            }
-        }
-    }
-
-    // a common pattern in PDDL is to have two effect (not x) and (x) on the same state variable.
-    // This is to force mutual exclusion on x. The semantics of PDDL have the negative effect applied first.
-    // This is already enforced by our translation of a positive effect on x as `]start, end] x <- true`
-    // Thus if we have both a positive effect and a negative effect on the same state variable,
-    // we remove the negative one
-    let positive_effects: HashSet<_> = ch
-        .effects
-        .iter()
-        .filter(|e| e.operation == EffectOp::TRUE_ASSIGNMENT)
-        .map(|e| (e.state_var.clone(), e.transition_end, e.transition_start))
-        .collect();
-    ch.effects.retain(|e| {
-        e.operation != EffectOp::FALSE_ASSIGNMENT
-            || !positive_effects.contains(&(e.state_var.clone(), e.transition_end, e.transition_start))
-    });
-
-    // TODO : check if work around still needed
-    for cond in pddl.preconditions() {
-        let conditions = read_conjunction(cond, as_chronicle_atom, context.model.get_symbol_table(), context)?;
-        for TermLoc(term, _) in conditions {
-            match term {
-                Term::Binding(sv, val) => {
-                    ch.conditions.push(Condition {
-                        start: ch.start,
-                        end: ch.start,
-                        state_var: sv,
-                        value: val,
-                    });
-                }
-                Term::Eq(a, b) => ch.constraints.push(Constraint::eq(a, b)),
-                Term::Neq(a, b) => ch.constraints.push(Constraint::neq(a, b)),
-            }
-        }
+        let read_terms = |e: &SExpr, t: &AtomConverter| read_temporal_conjunction(e, t, context);
+        read_effects(
+            eff,
+            &as_chronicle_atom,
+            context.model.get_symbol_table(),
+            &read_terms,
+            &mut effects,
+        )?;
     }
 
     // handle duration element from durative actions
@@ -533,6 +488,7 @@ fn read_chronicle_template(
                 Term::Binding(state_var, value) => match qualification {
                     TemporalQualification::AtStart => {
                         ch.conditions.push(Condition {
+                            presence: ch.presence,
                             start: ch.start,
                             end: ch.start,
                             state_var,
@@ -541,6 +497,7 @@ fn read_chronicle_template(
                     }
                     TemporalQualification::AtEnd => {
                         ch.conditions.push(Condition {
+                            presence: ch.presence,
                             start: ch.end,
                             end: ch.end,
                             state_var,
@@ -549,6 +506,7 @@ fn read_chronicle_template(
                     }
                     TemporalQualification::OverAll => {
                         ch.conditions.push(Condition {
+                            presence: ch.presence,
                             start: ch.start,
                             end: ch.end,
                             state_var,
@@ -562,6 +520,38 @@ fn read_chronicle_template(
         }
     }
 
+    for cond in pddl.preconditions() {
+        add_precondition(
+            c,
+            cond,
+            ch.start,
+            &as_chronicle_atom_no_borrow,
+            &mut ch,
+            Some(&mut params),
+            context,
+        )?;
+    }
+
+    for group in effects {
+        add_conditional_effects(c, group, &mut ch, &mut params, context)?;
+    }
+
+    // a common pattern in PDDL is to have two effect (not x) and (x) on the same state variable.
+    // This is to force mutual exclusion on x. The semantics of PDDL have the negative effect applied first.
+    // This is already enforced by our translation of a positive effect on x as `]start, end] x <- true`
+    // Thus if we have both a positive effect and a negative effect on the same state variable,
+    // we remove the negative one
+    let positive_effects: HashSet<_> = ch
+        .effects
+        .iter()
+        .filter(|e| e.operation == EffectOp::TRUE_ASSIGNMENT)
+        .map(|e| (e.presence, e.state_var.clone(), e.transition_end, e.transition_start))
+        .collect();
+    ch.effects.retain(|e| {
+        e.operation != EffectOp::FALSE_ASSIGNMENT
+            || !positive_effects.contains(&(e.presence, e.state_var.clone(), e.transition_end, e.transition_start))
+    });
+
     if let Some(tn) = pddl.task_network() {
         read_task_network(c, tn, &as_chronicle_atom_no_borrow, &mut ch, Some(&mut params), context)?
     }
@@ -574,6 +564,217 @@ fn read_chronicle_template(
     Ok(template)
 }
 
+/// Adds a group of effects to the chronicle, that only apply if all conditions of the group hold.
+///
+/// The effects of a conditional group are given a new presence literal `b` that is true iff
+/// the chronicle is present and all conditions hold at the time they are evaluated (see [`term_literal`]).
+/// All new variables are added to the parameters of the chronicle.
+fn add_conditional_effects(
+    c: Container,
+    group: ConditionalEffect,
+    ch: &mut Chronicle,
+    params: &mut Vec<Variable>,
+    context: &mut Ctx,
+) -> Result<()> {
+    // conditions and effects are instantaneous, at the start or at the end of the chronicle
+    let (start, end) = (ch.start, ch.end);
+    let timepoint = |qualification: TemporalQualification| match qualification {
+        TemporalQualification::AtStart => Some(start),
+        TemporalQualification::AtEnd => Some(end),
+        TemporalQualification::OverAll => None,
+    };
+
+    let presence = if group.conditions.is_empty() {
+        ch.presence
+    } else {
+        let mut holds = Vec::with_capacity(group.conditions.len());
+        for TemporalTerm(qualification, term) in group.conditions {
+            let Some(t) = timepoint(qualification) else {
+                return Err(term.1.invalid("Unsupported in conditional effects").into());
+            };
+            holds.push(term_literal(c, term, t, ch, Some(params), context)?);
+        }
+        let b = context.model.new_presence_variable(ch.presence, c / VarType::Presence);
+        params.push(b.into());
+        let b = b.true_lit();
+        // b <=> (holds_1 & ... & holds_n)
+        for &h in &holds {
+            ch.constraints.push(Constraint::or([!b, h]));
+        }
+        ch.constraints
+            .push(Constraint::or(std::iter::once(b).chain(holds.iter().map(|&h| !h))));
+        b
+    };
+
+    for TemporalTerm(qualification, TermLoc(term, loc)) in group.effects {
+        let (Term::Binding(state_var, value), Some(t)) = (term, timepoint(qualification)) else {
+            return Err(loc.invalid("Unsupported in action effects").into());
+        };
+        ch.effects.push(Effect {
+            presence,
+            transition_start: t,
+            transition_end: t + FAtom::EPSILON,
+            min_mutex_end: Vec::new(),
+            state_var,
+            operation: EffectOp::Assign(value),
+        });
+    }
+    Ok(())
+}
+
+/// Creates a new boolean variable, present with the chronicle, and adds it to the chronicle's parameters if any.
+fn new_bool_parameter(
+    c: Container,
+    ch: &Chronicle,
+    params: &mut Option<&mut Vec<Variable>>,
+    context: &mut Ctx,
+) -> BVar {
+    let var = context.model.new_optional_bvar(ch.presence, c / VarType::Reification);
+    if let Some(params) = params {
+        params.push(var.into());
+    }
+    var
+}
+
+/// Returns a literal that is true iff the term holds at time `t`, adding to the chronicle
+/// the conditions, constraints and variables required to define it.
+///
+/// The value of a state variable is observed in a new variable `v`, through a condition `sv == v` that is present
+/// with the chronicle. The term holds iff `v` has the expected value.
+fn term_literal(
+    c: Container,
+    TermLoc(term, loc): TermLoc,
+    t: Time,
+    ch: &mut Chronicle,
+    mut params: Option<&mut Vec<Variable>>,
+    context: &mut Ctx,
+) -> Result<Lit> {
+    let l = new_bool_parameter(c, ch, &mut params, context);
+    match term {
+        Term::Binding(state_var, value) => {
+            let value = Lit::try_from(value)
+                .ok()
+                .filter(|&v| v == Lit::TRUE || v == Lit::FALSE)
+                .ok_or_else(|| loc.invalid("Only boolean state variables are supported in this context"))?;
+            ch.conditions.push(Condition {
+                presence: ch.presence,
+                start: t,
+                end: t,
+                state_var,
+                value: l.true_lit().into(),
+            });
+            Ok(if value == Lit::TRUE {
+                l.true_lit()
+            } else {
+                l.false_lit()
+            })
+        }
+        Term::Eq(a, b) => {
+            ch.constraints.push(Constraint::reified_eq(a, b, l.true_lit()));
+            Ok(l.true_lit())
+        }
+        Term::Neq(a, b) => {
+            ch.constraints.push(Constraint::reified_eq(a, b, l.false_lit()));
+            Ok(l.true_lit())
+        }
+    }
+}
+
+/// Adds to the chronicle a precondition (or goal) `e` that must hold at time `t`.
+///
+/// The precondition may contain conjunctions, disjunctions, implications, negations and quantifiers.
+/// It is first put in negation normal form. Existential variables that are not under a disjunction or a universal
+/// quantifier become parameters of the chronicle, other existential (resp. universal) formulas are expanded into
+/// disjunctions (resp. conjunctions) over all objects of the type.
+/// Conjunctions of atoms are directly added as conditions and constraints, while disjunctions are encoded
+/// as a disjunctive constraint over literals that imply each disjunct.
+fn add_precondition(
+    c: Container,
+    e: &SExpr,
+    t: Time,
+    as_chronicle_atom: &impl Fn(&sexpr::SAtom, &Ctx) -> Result<SAtom>,
+    ch: &mut Chronicle,
+    mut params: Option<&mut Vec<Variable>>,
+    context: &mut Ctx,
+) -> Result<()> {
+    let mut formula = Formula::from_sexpr(e, false)?;
+    formula.lift_existentials(c, ch.presence, &mut params, context)?;
+    let as_chronicle_atom = |atom: &sexpr::SAtom| as_chronicle_atom(atom, context);
+    let formula = formula.read(&as_chronicle_atom, context)?;
+    add_formula(c, formula, t, ch, params, context)
+}
+
+/// Adds a (read) precondition formula to the chronicle.
+fn add_formula(
+    c: Container,
+    formula: Formula<TermLoc>,
+    t: Time,
+    ch: &mut Chronicle,
+    mut params: Option<&mut Vec<Variable>>,
+    context: &mut Ctx,
+) -> Result<()> {
+    match formula {
+        Formula::Atom(TermLoc(term, _)) => match term {
+            Term::Binding(sv, val) => ch.conditions.push(Condition {
+                presence: ch.presence,
+                start: t,
+                end: t,
+                state_var: sv,
+                value: val,
+            }),
+            Term::Eq(a, b) => ch.constraints.push(Constraint::eq(a, b)),
+            Term::Neq(a, b) => ch.constraints.push(Constraint::neq(a, b)),
+        },
+        Formula::And(conjuncts) => {
+            for f in conjuncts {
+                add_formula(c, f, t, ch, params.as_deref_mut(), context)?;
+            }
+        }
+        Formula::Or(disjuncts) => {
+            let mut disjunction = Vec::with_capacity(disjuncts.len());
+            for f in disjuncts {
+                disjunction.push(formula_literal(c, f, t, ch, params.as_deref_mut(), context)?);
+            }
+            ch.constraints.push(Constraint::or(disjunction));
+        }
+        Formula::Forall(..) | Formula::Exists(..) => unreachable!("Quantifiers are expanded when reading"),
+    }
+    Ok(())
+}
+
+/// Returns a literal that implies that the (read) formula holds at time `t`.
+/// As formulas are in negation normal form, this is sufficient to encode a disjunction of formulas.
+fn formula_literal(
+    c: Container,
+    formula: Formula<TermLoc>,
+    t: Time,
+    ch: &mut Chronicle,
+    mut params: Option<&mut Vec<Variable>>,
+    context: &mut Ctx,
+) -> Result<Lit> {
+    let (subformulas, conjunctive) = match formula {
+        Formula::Atom(term) => return term_literal(c, term, t, ch, params, context),
+        Formula::And(conjuncts) => (conjuncts, true),
+        Formula::Or(disjuncts) => (disjuncts, false),
+        Formula::Forall(..) | Formula::Exists(..) => unreachable!("Quantifiers are expanded when reading"),
+    };
+    let mut lits = Vec::with_capacity(subformulas.len());
+    for f in subformulas {
+        lits.push(formula_literal(c, f, t, ch, params.as_deref_mut(), context)?);
+    }
+    let l = new_bool_parameter(c, ch, &mut params, context).true_lit();
+    if conjunctive {
+        // l => (l1 & ... & ln)
+        for sub in lits {
+            ch.constraints.push(Constraint::or([!l, sub]));
+        }
+    } else {
+        // l => (l1 | ... | ln)
+        ch.constraints.push(Constraint::or(std::iter::once(!l).chain(lits)));
+    }
+    Ok(l)
+}
+
 /// An adapter to allow treating pddl actions and hddl methods identically
 trait ChronicleTemplateView {
     fn kind(&self) -> ChronicleKind;
@@ -809,6 +1010,7 @@ fn read_task_network(
             match term {
                 Term::Binding(sv, val) => {
                     chronicle.conditions.push(Condition {
+                        presence: chronicle.presence,
                         start: chronicle.start,
                         end: chronicle.start,
                         state_var: sv,
@@ -834,6 +1036,167 @@ enum Term {
 struct TermLoc(Term, Loc);
 struct TemporalTerm(TemporalQualification, TermLoc);
 
+/// A set of effects that only apply if all conditions hold.
+/// Unconditional effects are represented with an empty set of conditions.
+struct ConditionalEffect {
+    conditions: Vec<TemporalTerm>,
+    effects: Vec<TemporalTerm>,
+}
+
+/// A logical formula in negation normal form: negations only appear in its atoms.
+///
+/// Atoms are either (possibly negated) s-expressions, or terms once the formula has been read.
+enum Formula<A> {
+    Atom(A),
+    And(Vec<Formula<A>>),
+    Or(Vec<Formula<A>>),
+    Forall(Vec<TypedSymbol>, Box<Formula<A>>),
+    /// An existentially quantified formula.
+    /// The last element contains the variables of the chronicle that replace the quantified ones, if they were lifted.
+    Exists(Vec<TypedSymbol>, Box<Formula<A>>, Vec<SAtom>),
+}
+
+/// An atom of a formula, with a flag indicating whether it is negated.
+type NegatableAtom<'a> = (&'a SExpr, bool);
+
+impl<'a> Formula<NegatableAtom<'a>> {
+    /// Builds the negation normal form of the expression, or of its negation if `negated` is true.
+    fn from_sexpr(e: &'a SExpr, negated: bool) -> Result<Self> {
+        let all = |args: &'a [SExpr], negated: bool| -> Result<Vec<Self>> {
+            args.iter().map(|arg| Formula::from_sexpr(arg, negated)).collect()
+        };
+        // builds a conjunction, or a disjunction if negated
+        let and = |fs: Vec<Self>| if negated { Formula::Or(fs) } else { Formula::And(fs) };
+        let or = |fs: Vec<Self>| if negated { Formula::And(fs) } else { Formula::Or(fs) };
+        let quantified = |args: &'a [SExpr]| -> Result<(Vec<TypedSymbol>, Box<Self>)> {
+            let [params, body] = args else {
+                return Err(e.invalid("Expected parameters and a formula").into());
+            };
+            let mut params = params
+                .as_list_iter()
+                .ok_or_else(|| params.invalid("Expected parameters"))?;
+            let params = consume_typed_symbols(&mut params)?;
+            Ok((params, Box::new(Formula::from_sexpr(body, negated)?)))
+        };
+
+        if let Some(l) = e.as_list_iter() {
+            if l.is_empty() {
+                return Ok(and(Vec::new())); // empty conjunction
+            }
+        }
+        Ok(if let Some(args) = e.as_application("and") {
+            and(all(args, negated)?)
+        } else if let Some(args) = e.as_application("or") {
+            or(all(args, negated)?)
+        } else if let Some(args) = e.as_application("not") {
+            let [arg] = args else {
+                return Err(e.invalid("Expected a single argument").into());
+            };
+            Formula::from_sexpr(arg, !negated)?
+        } else if let Some(args) = e.as_application("imply") {
+            let [a, b] = args else {
+                return Err(e.invalid("Expected two arguments").into());
+            };
+            // (imply a b) is equivalent to (or (not a) b)
+            or(vec![
+                Formula::from_sexpr(a, !negated)?,
+                Formula::from_sexpr(b, negated)?,
+            ])
+        } else if let Some(args) = e.as_application("forall") {
+            let (params, body) = quantified(args)?;
+            if negated {
+                Formula::Exists(params, body, Vec::new())
+            } else {
+                Formula::Forall(params, body)
+            }
+        } else if let Some(args) = e.as_application("exists") {
+            let (params, body) = quantified(args)?;
+            if negated {
+                Formula::Forall(params, body)
+            } else {
+                Formula::Exists(params, body, Vec::new())
+            }
+        } else {
+            Formula::Atom((e, negated))
+        })
+    }
+
+    /// Creates new variables for all existential variables that only appear under conjunctions.
+    fn lift_existentials(
+        &mut self,
+        c: Container,
+        presence: Lit,
+        params: &mut Option<&mut Vec<Variable>>,
+        context: &mut Ctx,
+    ) -> Result<()> {
+        match self {
+            Formula::And(conjuncts) => {
+                for f in conjuncts {
+                    f.lift_existentials(c, presence, params, context)?;
+                }
+            }
+            Formula::Exists(vars, body, lifted) => {
+                let top_type: Sym = OBJECT_TYPE.into();
+                for var in vars.iter() {
+                    let tpe = var.tpe.as_ref().unwrap_or(&top_type);
+                    let tpe = context
+                        .model
+                        .get_symbol_table()
+                        .types
+                        .id_of(tpe)
+                        .ok_or_else(|| tpe.invalid("Unknown atom"))?;
+                    let arg = context.model.new_optional_sym_var(
+                        tpe,
+                        presence,
+                        c / VarType::Parameter(var.symbol.to_string()),
+                    );
+                    if let Some(params) = params {
+                        params.push(arg.into());
+                    }
+                    lifted.push(arg.into());
+                }
+                body.lift_existentials(c, presence, params, context)?;
+            }
+            Formula::Atom(_) | Formula::Or(_) | Formula::Forall(..) => {}
+        }
+        Ok(())
+    }
+
+    /// Reads all atoms of the formula, expanding the quantifiers whose variables were not lifted.
+    fn read(&self, t: &AtomConverter, context: &Ctx) -> Result<Formula<TermLoc>> {
+        let syms = context.model.get_symbol_table();
+        let all = |fs: &[Self]| -> Result<Vec<Formula<TermLoc>>> { fs.iter().map(|f| f.read(t, context)).collect() };
+        // reads the formula for each instantiation of the quantified variables
+        let instantiations = |vars: &[TypedSymbol], body: &Self| -> Result<Vec<Formula<TermLoc>>> {
+            let mut instances = Vec::new();
+            for_each_instantiation(vars, t, syms, &mut |t| {
+                instances.push(body.read(t, context)?);
+                Ok(())
+            })?;
+            Ok(instances)
+        };
+        Ok(match self {
+            &Formula::Atom((e, negated)) => {
+                let term = read_term(e, t, context)?;
+                Formula::Atom(if negated { negate(term)? } else { term })
+            }
+            Formula::And(conjuncts) => Formula::And(all(conjuncts)?),
+            Formula::Or(disjuncts) => Formula::Or(all(disjuncts)?),
+            Formula::Forall(vars, body) => Formula::And(instantiations(vars, body)?),
+            Formula::Exists(vars, body, lifted) if lifted.is_empty() => Formula::Or(instantiations(vars, body)?),
+            Formula::Exists(vars, body, lifted) => {
+                let t = |x: &sexpr::SAtom| -> Result<SAtom> {
+                    match vars.iter().position(|v| v.symbol.canonical_str() == x.canonical_str()) {
+                        Some(i) => Ok(lifted[i]),
+                        None => t(x),
+                    }
+                };
+                body.read(&t, context)?
+            }
+        })
+    }
+}
+
 /// Temporal qualification that can be applied to an expression.
 enum TemporalQualification {
     AtStart,
@@ -894,21 +1257,9 @@ fn read_conjunction_impl(
         let mut params = conjuncts[0].as_list_iter().context("expected parameters")?;
         let params = consume_typed_symbols(&mut params)?;
         let expr = &conjuncts[1];
-        assert_eq!(params.len(), 1, "Only support a single argument per forall.");
-        let ts = &params[0];
-        let var = &ts.symbol;
-        let default_type = OBJECT_TYPE.into();
-        let tpe = ts.tpe.as_ref().unwrap_or(&default_type);
-        for instance in instances_of(tpe, syms).context("Unknown type")? {
-            let t = |x: &sexpr::SAtom| -> Result<SAtom> {
-                if x.canonical_str() == var.canonical_str() {
-                    Ok(instance)
-                } else {
-                    t(x)
-                }
-            };
-            read_conjunction_impl(expr, &t, out, syms, context)?;
-        }
+        for_each_instantiation(&params, t, syms, &mut |t| {
+            read_conjunction_impl(expr, t, out, syms, context)
+        })?;
     } else {
         // should be directly a predicate
         out.push(read_possibly_negated_term(e, t, context)?);
@@ -916,6 +1267,79 @@ fn read_conjunction_impl(
     Ok(())
 }
 
+/// Function transforming the atoms of an s-expression into their representation in chronicles.
+type AtomConverter<'a> = dyn Fn(&sexpr::SAtom) -> Result<SAtom> + 'a;
+
+/// Invokes `f` for each possible instantiation of the parameters of a `forall` expression.
+/// `f` is given a function that maps each parameter to the object it is instantiated with, and delegates to `t`
+/// for all other atoms.
+fn for_each_instantiation(
+    params: &[TypedSymbol],
+    t: &AtomConverter,
+    syms: &SymbolTable,
+    f: &mut dyn FnMut(&AtomConverter) -> Result<()>,
+) -> Result<()> {
+    let Some((param, others)) = params.split_first() else {
+        return f(t);
+    };
+    let var = &param.symbol;
+    let default_type = OBJECT_TYPE.into();
+    let tpe = param.tpe.as_ref().unwrap_or(&default_type);
+    for instance in instances_of(tpe, syms).context("Unknown type")? {
+        let t = |x: &sexpr::SAtom| -> Result<SAtom> {
+            if x.canonical_str() == var.canonical_str() {
+                Ok(instance)
+            } else {
+                t(x)
+            }
+        };
+        for_each_instantiation(others, &t, syms, f)?;
+    }
+    Ok(())
+}
+
+/// Reads an effect expression into a set of conditional effects.
+///
+/// The expression is a conjunction of effects that may be universally quantified (`forall`) or conditional (`when`).
+/// `read_terms` is responsible for reading the (possibly temporally qualified) conjunction of terms appearing
+/// in the conditions and effects.
+fn read_effects(
+    e: &SExpr,
+    t: &AtomConverter,
+    syms: &SymbolTable,
+    read_terms: &dyn Fn(&SExpr, &AtomConverter) -> Result<Vec<TemporalTerm>>,
+    out: &mut Vec<ConditionalEffect>,
+) -> Result<()> {
+    if let Some(conjuncts) = e.as_application("and") {
+        for c in conjuncts.iter() {
+            read_effects(c, t, syms, read_terms, out)?;
+        }
+    } else if let Some(args) = e.as_application("forall") {
+        let [params, expr] = args else {
+            return Err(e.invalid("Expected parameters and an effect").into());
+        };
+        let mut params = params
+            .as_list_iter()
+            .ok_or_else(|| params.invalid("Expected parameters"))?;
+        let params = consume_typed_symbols(&mut params)?;
+        for_each_instantiation(&params, t, syms, &mut |t| read_effects(expr, t, syms, read_terms, out))?;
+    } else if let Some(args) = e.as_application("when") {
+        let [condition, effect] = args else {
+            return Err(e.invalid("Expected a condition and an effect").into());
+        };
+        out.push(ConditionalEffect {
+            conditions: read_terms(condition, t)?,
+            effects: read_terms(effect, t)?,
+        });
+    } else {
+        out.push(ConditionalEffect {
+            conditions: Vec::new(),
+            effects: read_terms(e, t)?,
+        });
+    }
+    Ok(())
+}
+
 fn read_temporal_conjunction(
     e: &SExpr,
     t: impl Fn(&sexpr::SAtom) -> Result<SAtom>,
@@ -972,18 +1396,7 @@ fn read_temporal_term(expr: &SExpr, t: impl Fn(&sexpr::SAtom) -> Result<SAtom>,
 
 fn read_possibly_negated_term(e: &SExpr, t: impl Fn(&sexpr::SAtom) -> Result<SAtom>, context: &Ctx) -> Result<TermLoc> {
     if let Some([to_negate]) = e.as_application("not") {
-        let TermLoc(t, _) = read_term(to_negate, &t, context)?;
-        let negated = match t {
-            Term::Binding(sv, value) => {
-                if let Ok(value) = Lit::try_from(value) {
-                    Term::Binding(sv, Atom::from(!value))
-                } else {
-                    return Err(to_negate.invalid("Could not apply 'not' to this expression").into());
-                }
-            }
-            Term::Eq(a, b) => Term::Neq(a, b),
-            Term::Neq(a, b) => Term::Eq(a, b),
-        };
+        let TermLoc(negated, _) = negate(read_term(to_negate, &t, context)?)?;
         Ok(TermLoc(negated, e.loc()))
     } else {
         // should be directly a predicate
@@ -991,6 +1404,22 @@ fn read_possibly_negated_term(e: &SExpr, t: impl Fn(&sexpr::SAtom) -> Result<SAt
     }
 }
 
+/// Returns the negation of the given term.
+fn negate(TermLoc(term, loc): TermLoc) -> Result<TermLoc> {
+    let negated = match term {
+        Term::Binding(sv, value) => {
+            if let Ok(value) = Lit::try_from(value) {
+                Term::Binding(sv, Atom::from(!value))
+            } else {
+                return Err(loc.invalid("Could not apply 'not' to this expression").into());
+            }
+        }
+        Term::Eq(a, b) => Term::Neq(a, b),
+        Term::Neq(a, b) => Term::Eq(a, b),
+    };
+    Ok(TermLoc(negated, loc))
+}
+
 fn to_state_variable(mut atoms: Vec<SAtom>, context: &Ctx) -> Result<StateVar> {
     let fluent = if let SAtom::Cst(s) = atoms.remove(0) {
         context.get_fluent(s.sym).context("Not a fluent")?.clone()
@@ -1093,3 +1522,92 @@ fn read_sv(e: &SExpr, desc: &World) -> Result<SvId> {
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chronicles::constraints::ConstraintType;
+    use crate::parsing::pddl::{parse_pddl_domain, parse_pddl_problem};
+    use aries::utils::input::Input;
+    use std::path::Path;
+
+    fn read_problem(domain: &str, problem: &str) -> Result<Problem> {
+        let dom = parse_pddl_domain(Input::from_file(Path::new(domain))?)?;
+        let prob = parse_pddl_problem(Input::from_file(Path::new(problem))?)?;
+        pddl_to_chronicles(&dom, &prob)
+    }
+
+    #[test]
+    fn conditional_effects() -> Result<()> {
+        let pb = read_problem(
+            "../problems/pddl/tests/briefcase.dom.pddl",
+            "../problems/pddl/tests/briefcase.pb.pddl",
+        )?;
+        let action = |name: &str| {
+            pb.templates
+                .iter()
+                .find(|t| t.label.to_string() == name)
+                .map(|t| &t.chronicle)
+                .unwrap()
+        };
+        let move_ch = action("move");
+        // `at-bc` and `(not at-bc)` are unconditional, and each portable can have two conditional effects
+        assert_eq!(move_ch.effects.len(), 6);
+        let conditional: Vec<_> = move_ch
+            .effects
+            .iter()
+            .filter(|e| e.presence != move_ch.presence)
+            .collect();
+        assert_eq!(conditional.len(), 4);
+        // the two effects of each `when` share the same presence, distinct from the one of the other portable
+        assert_eq!(conditional.iter().map(|e| e.presence).unique().count(), 2);
+        for e in &conditional {
+            assert!(pb.context.model.state.implies(e.presence, move_ch.presence));
+        }
+        // the precondition and one observation of the `in` predicate for each portable
+        assert_eq!(move_ch.conditions.len(), 3);
+        assert!(move_ch.conditions.iter().all(|c| c.presence == move_ch.presence));
+
+        let take_out = action("take-out");
+        assert!(take_out.effects.iter().all(|e| e.presence == take_out.presence));
+        Ok(())
+    }
+
+    #[test]
+    fn disjunctive_preconditions() -> Result<()> {
+        let pb = read_problem(
+            "../problems/pddl/tests/keys.dom.pddl",
+            "../problems/pddl/tests/keys.pb.pddl",
+        )?;
+        let action = |name: &str| {
+            pb.templates
+                .iter()
+                .find(|t| t.label.to_string() == name)
+                .map(|t| &t.chronicle)
+                .unwrap()
+        };
+        let disjunctions = |ch: &Chronicle| {
+            ch.constraints
+                .iter()
+                .filter(|c| matches!(c.tpe, ConstraintType::Or))
+                .count()
+        };
+        // `(or (open ?to) (exists ...))` is a disjunction whose exists is expanded over both keys,
+        // with one observation condition for each disjunct in addition to the two plain preconditions
+        let move_ch = action("move");
+        assert_eq!(move_ch.conditions.len(), 5);
+        assert_eq!(disjunctions(move_ch), 2);
+        // the implication is read as `(or (open ?r) (exists ...))`
+        let switch_on = action("switch-on");
+        assert_eq!(switch_on.conditions.len(), 4);
+        assert_eq!(disjunctions(switch_on), 2);
+
+        // the existential goal is lifted into a condition on a fresh variable
+        let init = &pb.chronicles[0].chronicle;
+        assert_eq!(init.conditions.len(), 2);
+        assert_eq!(disjunctions(init), 0);
+        let lifted = init.conditions.iter().find(|c| c.value == Atom::from(false)).unwrap();
+        assert!(matches!(lifted.state_var.args[0], SAtom::Var(_)));
+        Ok(())
+    }
+}
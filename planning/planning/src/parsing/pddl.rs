@@ -90,11 +90,39 @@ pub enum PddlFeature {
     Typing,
     Equality,
     NegativePreconditions,
+    DisjunctivePreconditions,
+    ExistentialPreconditions,
     UniversalPreconditions,
+    QuantifiedPreconditions,
     Hierarchy,
     MethodPreconditions,
     DurativeAction,
     Fluents,
+    ConditionalEffects,
+    Adl,
+}
+impl PddlFeature {
+    /// Features that are implied by this one (e.g. `:adl` implies `:conditional-effects`).
+    pub fn implied(self) -> &'static [PddlFeature] {
+        match self {
+            PddlFeature::Adl => &[
+                PddlFeature::Strips,
+                PddlFeature::Typing,
+                PddlFeature::Equality,
+                PddlFeature::NegativePreconditions,
+                PddlFeature::DisjunctivePreconditions,
+                PddlFeature::QuantifiedPreconditions,
+                PddlFeature::ExistentialPreconditions,
+                PddlFeature::UniversalPreconditions,
+                PddlFeature::ConditionalEffects,
+            ],
+            PddlFeature::QuantifiedPreconditions => &[
+                PddlFeature::ExistentialPreconditions,
+                PddlFeature::UniversalPreconditions,
+            ],
+            _ => &[],
+        }
+    }
 }
 impl std::str::FromStr for PddlFeature {
     type Err = String;
@@ -105,11 +133,16 @@ impl std::str::FromStr for PddlFeature {
             ":typing" => Ok(PddlFeature::Typing),
             ":equality" => Ok(PddlFeature::Equality),
             ":negative-preconditions" => Ok(PddlFeature::NegativePreconditions),
+            ":disjunctive-preconditions" => Ok(PddlFeature::DisjunctivePreconditions),
+            ":existential-preconditions" => Ok(PddlFeature::ExistentialPreconditions),
             ":universal-preconditions" => Ok(PddlFeature::UniversalPreconditions),
+            ":quantified-preconditions" => Ok(PddlFeature::QuantifiedPreconditions),
             ":hierarchy" => Ok(PddlFeature::Hierarchy),
             ":method-preconditions" => Ok(PddlFeature::MethodPreconditions),
             ":durative-actions" => Ok(PddlFeature::DurativeAction),
             ":fluents" => Ok(PddlFeature::Fluents),
+            ":conditional-effects" => Ok(PddlFeature::ConditionalEffects),
+            ":adl" => Ok(PddlFeature::Adl),
             _ => Err(format!("Unknown feature `{s}`")),
         }
     }
@@ -121,11 +154,16 @@ impl Display for PddlFeature {
             PddlFeature::Typing => ":typing",
             PddlFeature::Equality => ":equality",
             PddlFeature::NegativePreconditions => ":negative-preconditions",
+            PddlFeature::DisjunctivePreconditions => ":disjunctive-preconditions",
+            PddlFeature::ExistentialPreconditions => ":existential-preconditions",
             PddlFeature::UniversalPreconditions => ":universal-preconditions",
+            PddlFeature::QuantifiedPreconditions => ":quantified-preconditions",
             PddlFeature::Hierarchy => ":hierarchy",
             PddlFeature::MethodPreconditions => ":method-preconditions",
             PddlFeature::DurativeAction => ":durative-action",
             PddlFeature::Fluents => ":fluents",
+            PddlFeature::ConditionalEffects => ":conditional-effects",
+            PddlFeature::Adl => ":adl",
         };
         write!(f, "{formatted}")
     }
@@ -401,7 +439,11 @@ fn read_domain(dom: SExpr) -> std::result::Result<Domain, ErrLoc> {
                         .ok_or_else(|| feature.invalid("Expected feature name but got list"))?;
                     let f = PddlFeature::from_str(feature.canonical_str()).map_err(|e| feature.invalid(e))?;
 
-                    res.features.push(f);
+                    for f in std::iter::once(f).chain(f.implied().iter().copied()) {
+                        if !res.features.contains(&f) {
+                            res.features.push(f);
+                        }
+                    }
                 }
             }
             ":predicates" => {
@@ -819,6 +861,24 @@ mod tests {
 
         Result::Ok(())
     }
+
+    #[test]
+    fn adl_requirement() -> Result<()> {
+        let dom = parse_pddl_domain(Input::from_string("(define (domain d) (:requirements :typing :adl))"))?;
+        assert!(dom.features.contains(&PddlFeature::Adl));
+        assert!(dom.features.contains(&PddlFeature::ConditionalEffects));
+        assert!(dom.features.contains(&PddlFeature::NegativePreconditions));
+        assert!(dom.features.contains(&PddlFeature::ExistentialPreconditions));
+        // implied features are only recorded once
+        assert_eq!(dom.features.iter().filter(|&&f| f == PddlFeature::Typing).count(), 1);
+
+        let dom = parse_pddl_domain(Input::from_string(
+            "(define (domain d) (:requirements :quantified-preconditions))",
+        ))?;
+        assert!(dom.features.contains(&PddlFeature::ExistentialPreconditions));
+        assert!(dom.features.contains(&PddlFeature::UniversalPreconditions));
+        Ok(())
+    }
     //
     // #[test]
     // fn parsing_pddl_domain() -> Result<(), String> {
@@ -0,0 +1,608 @@
+//! Python bindings for the aries solver.
+//!
+//! The module exposes the [`Model`](aries::model::Model), its variables and the expression builders of
+//! [`lang::expr`](aries::model::lang::expr), together with a [`Solver`](aries::solver::Solver) to find (optimal)
+//! solutions of a model. See `README.md` for an example.
+//!
+//! Expressions are built lazily: the functions of the module (`leq`, `eq`, `or_`, ...) return an `Expr` that only
+//! records its arguments, and the corresponding constraint is built when the expression is enforced or reified.
+
+// false positive on the code generated by pyo3 for functions returning a `PyResult`
+#![allow(clippy::useless_conversion)]
+
+use aries::backtrack::Backtrack;
+use aries::core::{IntCst, Lit as CoreLit, VarRef};
+use aries::model::extensions::SavedAssignment;
+use aries::model::lang::expr::{self, alternative};
+use aries::model::lang::linear::LinearSum as CoreLinearSum;
+use aries::model::lang::max::EqMax;
+use aries::model::lang::{Atom, BVar as CoreBVar, IAtom as CoreIAtom, IVar as CoreIVar};
+use aries::model::Model as CoreModel;
+use aries::solver::Solver as CoreSolver;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::sync::Arc;
+
+type Label = String;
+
+/// A boolean literal, i.e., a boolean variable or its negation (`~lit`).
+#[pyclass(name = "Lit", frozen, eq, hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Lit(CoreLit);
+
+#[pymethods]
+impl Lit {
+    #[classattr]
+    #[pyo3(name = "TRUE")]
+    fn tautology() -> Lit {
+        Lit(CoreLit::TRUE)
+    }
+
+    #[classattr]
+    #[pyo3(name = "FALSE")]
+    fn contradiction() -> Lit {
+        Lit(CoreLit::FALSE)
+    }
+
+    fn __invert__(&self) -> Lit {
+        Lit(!self.0)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// A boolean variable.
+#[pyclass(name = "BVar", frozen)]
+#[derive(Clone, Copy)]
+struct BVar(CoreBVar);
+
+#[pymethods]
+impl BVar {
+    /// The literal that is true iff the variable is true.
+    fn lit(&self) -> Lit {
+        Lit(self.0.true_lit())
+    }
+
+    fn __invert__(&self) -> Lit {
+        Lit(!self.0.true_lit())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", VarRef::from(self.0))
+    }
+}
+
+/// An integer variable.
+#[pyclass(name = "IVar", frozen)]
+#[derive(Clone, Copy)]
+struct IVar(CoreIVar);
+
+#[pymethods]
+impl IVar {
+    /// The literal `self <= value`.
+    fn leq(&self, value: IntCst) -> Lit {
+        Lit(self.0.leq(value))
+    }
+
+    /// The literal `self < value`.
+    fn lt(&self, value: IntCst) -> Lit {
+        Lit(self.0.lt(value))
+    }
+
+    /// The literal `self >= value`.
+    fn geq(&self, value: IntCst) -> Lit {
+        Lit(self.0.geq(value))
+    }
+
+    /// The literal `self > value`.
+    fn gt(&self, value: IntCst) -> Lit {
+        Lit(self.0.gt(value))
+    }
+
+    fn __add__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        match other {
+            LinearArg::Int(IntArg::Cst(c)) => IAtom(self.0 + c).into_py(py),
+            other => LinearSum(CoreLinearSum::from(self.0) + other.sum()).into_py(py),
+        }
+    }
+
+    fn __radd__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        self.__add__(py, other)
+    }
+
+    fn __sub__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        match other {
+            LinearArg::Int(IntArg::Cst(c)) => IAtom(self.0 - c).into_py(py),
+            other => LinearSum(CoreLinearSum::from(self.0) - other.sum()).into_py(py),
+        }
+    }
+
+    fn __rsub__(&self, other: LinearArg) -> LinearSum {
+        LinearSum(other.sum() - self.0)
+    }
+
+    fn __mul__(&self, factor: IntCst) -> LinearSum {
+        LinearSum(CoreLinearSum::from(self.0) * factor)
+    }
+
+    fn __rmul__(&self, factor: IntCst) -> LinearSum {
+        self.__mul__(factor)
+    }
+
+    fn __neg__(&self) -> LinearSum {
+        LinearSum(-CoreLinearSum::from(self.0))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", VarRef::from(self.0))
+    }
+}
+
+/// An integer variable shifted by a constant (`var + shift`).
+#[pyclass(name = "IAtom", frozen)]
+#[derive(Clone, Copy)]
+struct IAtom(CoreIAtom);
+
+#[pymethods]
+impl IAtom {
+    #[getter]
+    fn var(&self) -> IVar {
+        IVar(self.0.var)
+    }
+
+    #[getter]
+    fn shift(&self) -> IntCst {
+        self.0.shift
+    }
+
+    fn __add__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        match other {
+            LinearArg::Int(IntArg::Cst(c)) => IAtom(self.0 + c).into_py(py),
+            other => LinearSum(CoreLinearSum::from(self.0) + other.sum()).into_py(py),
+        }
+    }
+
+    fn __radd__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        self.__add__(py, other)
+    }
+
+    fn __sub__(&self, py: Python<'_>, other: LinearArg) -> PyObject {
+        match other {
+            LinearArg::Int(IntArg::Cst(c)) => IAtom(self.0 - c).into_py(py),
+            other => LinearSum(CoreLinearSum::from(self.0) - other.sum()).into_py(py),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?} + {}", VarRef::from(self.0.var), self.0.shift)
+    }
+}
+
+/// A linear sum of integer variables, built with the arithmetic operators (e.g. `2 * x + y - 3`).
+#[pyclass(name = "LinearSum", frozen)]
+#[derive(Clone)]
+struct LinearSum(CoreLinearSum);
+
+#[pymethods]
+impl LinearSum {
+    fn __add__(&self, other: LinearArg) -> LinearSum {
+        LinearSum(self.0.clone() + other.sum())
+    }
+
+    fn __radd__(&self, other: LinearArg) -> LinearSum {
+        self.__add__(other)
+    }
+
+    fn __sub__(&self, other: LinearArg) -> LinearSum {
+        LinearSum(self.0.clone() - other.sum())
+    }
+
+    fn __rsub__(&self, other: LinearArg) -> LinearSum {
+        LinearSum(other.sum() - self.0.clone())
+    }
+
+    fn __mul__(&self, factor: IntCst) -> LinearSum {
+        LinearSum(self.0.clone() * factor)
+    }
+
+    fn __rmul__(&self, factor: IntCst) -> LinearSum {
+        self.__mul__(factor)
+    }
+
+    fn __neg__(&self) -> LinearSum {
+        LinearSum(-self.0.clone())
+    }
+
+    /// The constraint `self <= upper_bound`.
+    fn leq(&self, upper_bound: LinearArg) -> Expr {
+        Expr(ExprKind::Linear(self.0.clone() - upper_bound.sum()))
+    }
+
+    /// The constraint `self >= lower_bound`.
+    fn geq(&self, lower_bound: LinearArg) -> Expr {
+        Expr(ExprKind::Linear(lower_bound.sum() - self.0.clone()))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// An integer argument: a variable, a shifted variable or a constant.
+#[derive(FromPyObject, Clone, Copy)]
+enum IntArg {
+    Var(IVar),
+    Atom(IAtom),
+    Cst(IntCst),
+}
+
+impl From<IntArg> for CoreIAtom {
+    fn from(value: IntArg) -> Self {
+        match value {
+            IntArg::Var(v) => v.0.into(),
+            IntArg::Atom(a) => a.0,
+            IntArg::Cst(c) => c.into(),
+        }
+    }
+}
+
+/// A boolean argument: a literal or a boolean variable.
+#[derive(FromPyObject, Clone, Copy)]
+enum LitArg {
+    Lit(Lit),
+    Var(BVar),
+}
+
+impl From<LitArg> for CoreLit {
+    fn from(value: LitArg) -> Self {
+        match value {
+            LitArg::Lit(l) => l.0,
+            LitArg::Var(v) => v.0.true_lit(),
+        }
+    }
+}
+
+/// An argument of a linear expression.
+#[derive(FromPyObject, Clone)]
+enum LinearArg {
+    Sum(LinearSum),
+    Int(IntArg),
+}
+
+impl LinearArg {
+    fn sum(self) -> CoreLinearSum {
+        match self {
+            LinearArg::Sum(s) => s.0,
+            LinearArg::Int(i) => CoreIAtom::from(i).into(),
+        }
+    }
+}
+
+/// An argument of an equality, either integer or boolean.
+#[derive(FromPyObject, Clone, Copy)]
+enum AtomArg {
+    Bool(LitArg),
+    Int(IntArg),
+}
+
+impl From<AtomArg> for Atom {
+    fn from(value: AtomArg) -> Self {
+        match value {
+            AtomArg::Bool(l) => CoreLit::from(l).into(),
+            AtomArg::Int(i) => CoreIAtom::from(i).into(),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum ExprKind {
+    Lit(CoreLit),
+    Leq(CoreIAtom, CoreIAtom),
+    Eq(Atom, Atom),
+    Neq(Atom, Atom),
+    Or(Vec<CoreLit>),
+    And(Vec<CoreLit>),
+    Alternative(CoreIAtom, Vec<CoreIAtom>),
+    EqMax(CoreIAtom, Vec<CoreIAtom>),
+    /// The constraint `sum <= 0`
+    Linear(CoreLinearSum),
+}
+
+/// A boolean expression, that can be enforced in a model or reified into a literal.
+#[pyclass(name = "Expr", frozen)]
+#[derive(Clone)]
+struct Expr(ExprKind);
+
+/// An argument that can be enforced or reified.
+#[derive(FromPyObject)]
+enum ExprArg {
+    Expr(Expr),
+    Lit(LitArg),
+}
+
+impl From<ExprArg> for ExprKind {
+    fn from(value: ExprArg) -> Self {
+        match value {
+            ExprArg::Expr(e) => e.0,
+            ExprArg::Lit(l) => ExprKind::Lit(l.into()),
+        }
+    }
+}
+
+impl ExprKind {
+    fn enforce(self, model: &mut CoreModel<Label>, scope: Vec<CoreLit>) {
+        match self {
+            ExprKind::Lit(l) => model.enforce(l, scope),
+            ExprKind::Leq(a, b) => model.enforce(expr::leq(a, b), scope),
+            ExprKind::Eq(a, b) => model.enforce(expr::eq(a, b), scope),
+            ExprKind::Neq(a, b) => model.enforce(expr::neq(a, b), scope),
+            ExprKind::Or(lits) => model.enforce(expr::or(lits), scope),
+            ExprKind::And(lits) => model.enforce(expr::and(lits), scope),
+            ExprKind::Alternative(main, alts) => model.enforce(alternative(main, alts), scope),
+            ExprKind::EqMax(lhs, elems) => model.enforce(EqMax::new(lhs, elems), scope),
+            ExprKind::Linear(sum) => model.enforce(sum.leq(0), scope),
+        }
+    }
+
+    fn reify(self, model: &mut CoreModel<Label>) -> PyResult<CoreLit> {
+        Ok(match self {
+            ExprKind::Lit(l) => l,
+            ExprKind::Leq(a, b) => model.reify(expr::leq(a, b)),
+            ExprKind::Eq(a, b) => model.reify(expr::eq(a, b)),
+            ExprKind::Neq(a, b) => model.reify(expr::neq(a, b)),
+            ExprKind::Or(lits) => model.reify(expr::or(lits)),
+            ExprKind::And(lits) => model.reify(expr::and(lits)),
+            ExprKind::Linear(sum) => model.reify(sum.leq(0)),
+            ExprKind::Alternative(..) | ExprKind::EqMax(..) => {
+                return Err(PyRuntimeError::new_err("This constraint cannot be reified"))
+            }
+        })
+    }
+}
+
+/// The constraint `lhs <= rhs`.
+#[pyfunction]
+fn leq(lhs: IntArg, rhs: IntArg) -> Expr {
+    Expr(ExprKind::Leq(lhs.into(), rhs.into()))
+}
+
+/// The constraint `lhs < rhs`.
+#[pyfunction]
+fn lt(lhs: IntArg, rhs: IntArg) -> Expr {
+    Expr(ExprKind::Leq(lhs.into(), CoreIAtom::from(rhs) - 1))
+}
+
+/// The constraint `lhs >= rhs`.
+#[pyfunction]
+fn geq(lhs: IntArg, rhs: IntArg) -> Expr {
+    leq(rhs, lhs)
+}
+
+/// The constraint `lhs > rhs`.
+#[pyfunction]
+fn gt(lhs: IntArg, rhs: IntArg) -> Expr {
+    lt(rhs, lhs)
+}
+
+/// The constraint `lhs = rhs`, where both sides are either integers or booleans.
+#[pyfunction]
+fn eq(lhs: AtomArg, rhs: AtomArg) -> Expr {
+    Expr(ExprKind::Eq(lhs.into(), rhs.into()))
+}
+
+/// The constraint `lhs != rhs`, where both sides are either integers or booleans.
+#[pyfunction]
+fn neq(lhs: AtomArg, rhs: AtomArg) -> Expr {
+    Expr(ExprKind::Neq(lhs.into(), rhs.into()))
+}
+
+/// The disjunction of the literals.
+#[pyfunction]
+fn or_(lits: Vec<LitArg>) -> Expr {
+    Expr(ExprKind::Or(lits.into_iter().map(CoreLit::from).collect()))
+}
+
+/// The conjunction of the literals.
+#[pyfunction]
+fn and_(lits: Vec<LitArg>) -> Expr {
+    Expr(ExprKind::And(lits.into_iter().map(CoreLit::from).collect()))
+}
+
+/// The implication `a => b`.
+#[pyfunction]
+fn implies(a: LitArg, b: LitArg) -> Expr {
+    Expr(ExprKind::Or(vec![!CoreLit::from(a), b.into()]))
+}
+
+/// The constraint that exactly one of the (optional) alternatives is present, and equal to `main`.
+#[pyfunction]
+#[pyo3(name = "alternative")]
+fn alternative_constraint(main: IntArg, alternatives: Vec<IntArg>) -> Expr {
+    Expr(ExprKind::Alternative(
+        main.into(),
+        alternatives.into_iter().map(CoreIAtom::from).collect(),
+    ))
+}
+
+/// The constraint `lhs = max(elements)`, ignoring the absent elements.
+#[pyfunction]
+fn eq_max(lhs: IntArg, elements: Vec<IntArg>) -> Expr {
+    Expr(ExprKind::EqMax(
+        lhs.into(),
+        elements.into_iter().map(CoreIAtom::from).collect(),
+    ))
+}
+
+/// A model, made of variables and constraints on them.
+#[pyclass(name = "Model")]
+#[derive(Clone)]
+struct Model(CoreModel<Label>);
+
+#[pymethods]
+impl Model {
+    #[new]
+    fn new() -> Self {
+        Model(CoreModel::new())
+    }
+
+    /// Creates a new integer variable with domain `[lb, ub]`.
+    #[pyo3(signature = (lb, ub, label = String::new()))]
+    fn new_ivar(&mut self, lb: IntCst, ub: IntCst, label: String) -> IVar {
+        IVar(self.0.new_ivar(lb, ub, label))
+    }
+
+    /// Creates a new integer variable with domain `[lb, ub]` that is only present when the `presence` literal is true.
+    #[pyo3(signature = (lb, ub, presence, label = String::new()))]
+    fn new_optional_ivar(&mut self, lb: IntCst, ub: IntCst, presence: LitArg, label: String) -> IVar {
+        IVar(self.0.new_optional_ivar(lb, ub, presence.into(), label))
+    }
+
+    /// Creates a new boolean variable.
+    #[pyo3(signature = (label = String::new()))]
+    fn new_bvar(&mut self, label: String) -> BVar {
+        BVar(self.0.new_bvar(label))
+    }
+
+    /// Enforces the expression to be true whenever all literals of the scope are true.
+    #[pyo3(signature = (expr, scope = Vec::new()))]
+    fn enforce(&mut self, expr: ExprArg, scope: Vec<LitArg>) {
+        let scope = scope.into_iter().map(CoreLit::from).collect();
+        ExprKind::from(expr).enforce(&mut self.0, scope)
+    }
+
+    /// Returns a literal that is true iff the expression is true.
+    fn reify(&mut self, expr: ExprArg) -> PyResult<Lit> {
+        ExprKind::from(expr).reify(&mut self.0).map(Lit)
+    }
+
+    /// Returns the presence literal of the variable.
+    fn presence(&self, var: IVar) -> Lit {
+        Lit(self.0.state.presence(VarRef::from(var.0)))
+    }
+}
+
+/// A variable whose value can be read from a solution.
+#[derive(FromPyObject)]
+enum ValueArg {
+    Bool(LitArg),
+    Int(IntArg),
+}
+
+/// A solution of a model.
+#[pyclass(name = "Solution", frozen)]
+struct Solution(Arc<SavedAssignment>);
+
+#[pymethods]
+impl Solution {
+    /// Returns the value of an integer variable (or `None` if it is absent), or of a boolean variable or literal.
+    fn value(&self, py: Python<'_>, var: ValueArg) -> PyObject {
+        match var {
+            ValueArg::Bool(l) => self.0.entails(l.into()).into_py(py),
+            ValueArg::Int(i) => {
+                let atom = CoreIAtom::from(i);
+                if self.0.entails(!self.0.presence(atom.var)) {
+                    py.None()
+                } else {
+                    (self.0.lb(atom.var) + atom.shift).into_py(py)
+                }
+            }
+        }
+    }
+
+    fn __getitem__(&self, py: Python<'_>, var: ValueArg) -> PyObject {
+        self.value(py, var)
+    }
+
+    /// Returns true if the literal is true in the solution.
+    fn entails(&self, lit: LitArg) -> bool {
+        self.0.entails(lit.into())
+    }
+}
+
+/// A solver for a given model. The model is copied and later changes are not seen by the solver.
+#[pyclass(name = "Solver", unsendable)]
+struct Solver(CoreSolver<Label>);
+
+#[pymethods]
+impl Solver {
+    #[new]
+    fn new(model: &Model) -> Self {
+        Solver(CoreSolver::new(model.0.clone()))
+    }
+
+    /// Returns a solution of the model, or `None` if it has no solution.
+    fn solve(&mut self) -> PyResult<Option<Solution>> {
+        let result = self.0.solve().map_err(|e| PyRuntimeError::new_err(e.to_string()));
+        self.0.reset();
+        Ok(result?.map(Solution))
+    }
+
+    /// Returns a tuple `(cost, solution)` with an optimal solution minimizing the objective,
+    /// or `None` if the model has no solution.
+    fn minimize(&mut self, objective: IntArg) -> PyResult<Option<(IntCst, Solution)>> {
+        let result = self.0.minimize(CoreIAtom::from(objective));
+        self.0.reset();
+        let result = result.map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(result.map(|(cost, sol)| (cost, Solution(sol))))
+    }
+
+    /// Returns a tuple `(cost, solution)` with an optimal solution maximizing the objective,
+    /// or `None` if the model has no solution.
+    fn maximize(&mut self, objective: IntArg) -> PyResult<Option<(IntCst, Solution)>> {
+        let result = self.0.maximize(CoreIAtom::from(objective));
+        self.0.reset();
+        let result = result.map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(result.map(|(cost, sol)| (cost, Solution(sol))))
+    }
+
+    /// Returns all solutions of the model, projected on the given variables.
+    /// Each solution is a list with the value of each variable (booleans are represented by 0 and 1).
+    fn enumerate(&mut self, variables: Vec<ValueArg>) -> PyResult<Vec<Vec<IntCst>>> {
+        let variables: Vec<VarRef> = variables
+            .into_iter()
+            .map(|v| match v {
+                ValueArg::Bool(l) => {
+                    let lit = CoreLit::from(l);
+                    if lit == lit.variable().geq(1) {
+                        Ok(lit.variable())
+                    } else {
+                        Err(PyRuntimeError::new_err("Only boolean variables can be enumerated"))
+                    }
+                }
+                ValueArg::Int(IntArg::Var(v)) => Ok(v.0.into()),
+                ValueArg::Int(_) => Err(PyRuntimeError::new_err("Only variables can be enumerated")),
+            })
+            .collect::<PyResult<_>>()?;
+        let result = self.0.enumerate(&variables);
+        self.0.reset();
+        result.map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "aries")]
+fn aries_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Lit>()?;
+    m.add_class::<BVar>()?;
+    m.add_class::<IVar>()?;
+    m.add_class::<IAtom>()?;
+    m.add_class::<LinearSum>()?;
+    m.add_class::<Expr>()?;
+    m.add_class::<Model>()?;
+    m.add_class::<Solution>()?;
+    m.add_class::<Solver>()?;
+    m.add_function(wrap_pyfunction!(leq, m)?)?;
+    m.add_function(wrap_pyfunction!(lt, m)?)?;
+    m.add_function(wrap_pyfunction!(geq, m)?)?;
+    m.add_function(wrap_pyfunction!(gt, m)?)?;
+    m.add_function(wrap_pyfunction!(eq, m)?)?;
+    m.add_function(wrap_pyfunction!(neq, m)?)?;
+    m.add_function(wrap_pyfunction!(or_, m)?)?;
+    m.add_function(wrap_pyfunction!(and_, m)?)?;
+    m.add_function(wrap_pyfunction!(implies, m)?)?;
+    m.add_function(wrap_pyfunction!(alternative_constraint, m)?)?;
+    m.add_function(wrap_pyfunction!(eq_max, m)?)?;
+    Ok(())
+}
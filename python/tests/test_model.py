@@ -0,0 +1,139 @@
+import aries
+from aries import Model, Solver, leq, lt, geq, gt, eq, neq, or_, and_, implies, alternative, eq_max
+
+
+def test_solve():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    y = m.new_ivar(0, 10, "y")
+    m.enforce(lt(x, y))
+    m.enforce(geq(x, 5))
+    sol = Solver(m).solve()
+    assert sol is not None
+    assert 5 <= sol[x] < sol[y] <= 10
+
+
+def test_unsat():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    m.enforce(gt(x, 10))
+    assert Solver(m).solve() is None
+
+
+def test_literals():
+    m = Model()
+    a = m.new_bvar("a")
+    b = m.new_bvar("b")
+    m.enforce(or_([a, b]))
+    m.enforce(implies(a, b))
+    m.enforce(~a)
+    sol = Solver(m).solve()
+    assert sol is not None
+    assert sol[a] is False
+    assert sol[b] is True
+    assert sol.entails(~a)
+
+
+def test_reification():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    y = m.new_ivar(0, 10, "y")
+    x_before_y = m.reify(leq(x + 1, y))
+    m.enforce(x_before_y)
+    m.enforce(eq(y, 3))
+    sol = Solver(m).solve()
+    assert sol.entails(x_before_y)
+    assert sol[x] <= 2
+    assert sol[y] == 3
+    assert sol[x + 2] == sol[x] + 2
+
+
+def test_ivar_literals():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    m.enforce(and_([x.geq(3), x.leq(3)]))
+    sol = Solver(m).solve()
+    assert sol[x] == 3
+    assert sol.entails(x.lt(4))
+    assert not sol.entails(x.gt(3))
+
+
+def test_linear():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    y = m.new_ivar(0, 10, "y")
+    m.enforce((2 * x + 3 * y).geq(17))
+    total = m.new_ivar(0, 20, "total")
+    m.enforce((x + y - total).leq(0))
+    m.enforce((x + y).geq(total))
+    cost, sol = Solver(m).minimize(total)
+    assert cost == 6
+    assert 2 * sol[x] + 3 * sol[y] >= 17
+    assert sol[x] + sol[y] == 6
+
+
+def test_optimization():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    y = m.new_ivar(0, 10, "y")
+    m.enforce(neq(x, y))
+    m.enforce(leq(x, 7))
+    m.enforce(leq(y, x))
+    cost, sol = Solver(m).maximize(y)
+    assert cost == 6
+    assert sol[x] == 7
+    assert Solver(m).minimize(x)[0] == 1
+
+
+def test_enumerate():
+    m = Model()
+    x = m.new_ivar(0, 2, "x")
+    y = m.new_ivar(0, 2, "y")
+    a = m.new_bvar("a")
+    m.enforce(eq(a, m.reify(lt(x, y))))
+    solutions = Solver(m).enumerate([x, y, a])
+    assert sorted(solutions) == sorted([[i, j, int(i < j)] for i in range(3) for j in range(3)])
+
+
+def test_eq_max():
+    m = Model()
+    xs = [m.new_ivar(0, 10, f"x{i}") for i in range(3)]
+    mx = m.new_ivar(0, 10, "max")
+    m.enforce(eq_max(mx, xs))
+    m.enforce(eq(xs[1], 7))
+    cost, sol = Solver(m).minimize(mx)
+    assert cost == 7
+    assert sol[mx] == max(sol[x] for x in xs)
+
+
+def test_alternative():
+    m = Model()
+    main = m.new_ivar(0, 10, "main")
+    presences = [m.new_bvar(f"p{i}") for i in range(3)]
+    alts = [m.new_optional_ivar(2 * i, 10, p, f"alt{i}") for i, p in enumerate(presences)]
+    m.enforce(alternative(main, alts))
+    cost, sol = Solver(m).minimize(main)
+    assert cost == 0
+    assert [sol[p] for p in presences] == [True, False, False]
+    assert sol[alts[0]] == 0
+    assert sol[alts[1]] is None
+    assert sol.entails(~m.presence(alts[2]))
+
+
+def test_solver_reuse():
+    m = Model()
+    x = m.new_ivar(0, 10, "x")
+    solver = Solver(m)
+    assert solver.solve() is not None
+    assert solver.minimize(x)[0] == 0
+    assert solver.maximize(x)[0] == 10
+    # the solver works on a copy of the model
+    m.enforce(gt(x, 10))
+    assert solver.solve() is not None
+    assert Solver(m).solve() is None
+
+
+def test_constants():
+    m = Model()
+    m.enforce(aries.Lit.FALSE)
+    assert Solver(m).solve() is None
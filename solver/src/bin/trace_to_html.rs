@@ -0,0 +1,29 @@
+//! Converts a search trace in the JSONL format (as produced by setting `ARIES_TRACE_FILE`)
+//! into a standalone HTML page displaying the search tree of each solver.
+//!
+//! Usage: `trace_to_html TRACE.jsonl [OUTPUT.html]` (writes to stdout if no output file is given)
+
+use anyhow::{bail, Context, Result};
+use aries::solver::trace::{read_jsonl, SearchTree};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.len() > 2 {
+        bail!("Usage: trace_to_html TRACE.jsonl [OUTPUT.html]");
+    }
+    let input = File::open(&args[0]).with_context(|| format!("Could not open {}", &args[0]))?;
+    let records = read_jsonl(BufReader::new(input))?;
+    let trees = SearchTree::build_all(&records);
+
+    let mut output: Box<dyn Write> = match args.get(1) {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).with_context(|| format!("Could not create {path}"))?,
+        )),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    SearchTree::write_html(&trees, &mut output)?;
+    output.flush()?;
+    Ok(())
+}
@@ -79,6 +79,15 @@ impl ImplicationGraph {
     pub fn direct_implications_of(&self, lit: Lit) -> impl Iterator<Item = Lit> + '_ {
         self.edges.watches_on(lit)
     }
+
+    /// Returns all edges `(from, to)` of the graph where `from` is a bound on `svar`.
+    /// Note that for each edge `(x -> y)` the graph also contains the contrapositive edge `(!y -> !x)`.
+    pub fn edges_from(&self, svar: SignedVar) -> impl Iterator<Item = (Lit, Lit)> + '_ {
+        self.edges
+            .watch_set(svar)
+            .all_watches()
+            .map(move |w| (w.to_lit(svar), w.watcher))
+    }
 }
 
 impl Clone for ImplicationGraph {
@@ -9,6 +9,7 @@ use crate::core::state::int_domains::IntDomains;
 use crate::core::state::{Cause, DomainsSnapshot, Explainer, Explanation, ExplanationQueue, InvalidUpdate, OptDomain};
 use crate::core::*;
 use crate::solver::UnsatCore;
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
 
 #[cfg(debug_assertions)]
@@ -40,6 +41,13 @@ pub struct Domains {
     presence: RefMap<VarRef, Lit>,
     /// A graph to encode the relations between presence variables.
     implications: ImplicationGraph,
+    /// For variables with a sparse domain, the literals `var != value` of the values that may be removed
+    /// from the inside of their domain.
+    ///
+    /// Holes are not native to the domain representation, which only maintains bounds: each hole is a literal
+    /// linked to the bounds of the variable by clauses (see `Model::value_removal`). Hence only the values with a
+    /// registered literal may be holes, and the bounds only skip a hole once these clauses are propagated.
+    removals: RefMap<VarRef, BTreeMap<IntCst, Lit>>,
     /// A queue used internally when building explanations. Only useful to avoid repeated allocations.
     queue: ExplanationQueue,
 }
@@ -50,6 +58,7 @@ impl Domains {
             doms: IntDomains::new(),
             presence: Default::default(),
             implications: Default::default(),
+            removals: Default::default(),
             queue: Default::default(),
         };
         debug_assert!(domains.entails(Lit::TRUE));
@@ -113,6 +122,14 @@ impl Domains {
         self.implies(prez_a, prez_b)
     }
 
+    /// Returns all implications `(from, to)` that were added with [`Domains::add_implication`],
+    /// together with their contrapositive `(!to, !from)`.
+    pub fn implications(&self) -> impl Iterator<Item = (Lit, Lit)> + '_ {
+        self.variables()
+            .flat_map(|v| [SignedVar::plus(v), SignedVar::minus(v)])
+            .flat_map(|svar| self.implications.edges_from(svar))
+    }
+
     /// Returns true if `a` is known to imply `b`
     pub fn implies(&self, a: Lit, b: Lit) -> bool {
         if self.entails(b) || self.entails(!a) {
@@ -172,6 +189,18 @@ impl Domains {
         self.doms.lb(var)
     }
 
+    /// Returns the bounds that the variable had at the root decision level.
+    pub fn root_bounds(&self, v: VarRef) -> (IntCst, IntCst) {
+        let root_ub = |var: SignedVar| {
+            self.doms
+                .upper_bounds_history(var)
+                .find(|(_, ev)| ev.map_or(true, |ev| self.trail().decision_level(ev) == DecLvl::ROOT))
+                .map(|(ub, _)| ub)
+                .unwrap()
+        };
+        (-root_ub(SignedVar::minus(v)), root_ub(SignedVar::plus(v)))
+    }
+
     /// Returns true if the integer domain of the variable is a singleton or an empty set.
     ///
     /// Note that an empty set is valid for optional variables and implies that
@@ -180,6 +209,86 @@ impl Domains {
         self.lb(var) >= self.ub(var)
     }
 
+    /// Returns true if the value is in the domain of the variable, i.e., it is within its bounds and
+    /// was not removed from a sparse domain.
+    pub fn contains(&self, var: VarRef, value: IntCst) -> bool {
+        let (lb, ub) = self.bounds(var);
+        lb <= value && value <= ub && !self.removal_literal(var, value).is_some_and(|l| self.entails(l))
+    }
+
+    /// Iterates, by increasing value, on the holes of a sparse domain that are within the bounds of the variable.
+    ///
+    /// This is linear in the number of removal literals registered for the variable, regardless of the size of its domain.
+    pub fn holes(&self, var: VarRef) -> impl Iterator<Item = IntCst> + '_ {
+        let (lb, ub) = self.bounds(var);
+        self.removals
+            .get(var)
+            .filter(|_| lb <= ub)
+            .into_iter()
+            .flat_map(move |values| values.range(lb..=ub))
+            .filter(|(_, &removed)| self.entails(removed))
+            .map(|(&value, _)| value)
+    }
+
+    /// Iterates, by increasing values, on the maximal intervals `[first, last]` of the domain of the variable.
+    /// Intervals are separated by the holes of a sparse domain.
+    pub fn intervals(&self, var: VarRef) -> impl Iterator<Item = (IntCst, IntCst)> + '_ {
+        let (lb, ub) = self.bounds(var);
+        // the bounds are never holes, and `ub + 1` closes the last interval
+        let mut next_first = lb;
+        self.holes(var).chain(std::iter::once(ub + 1)).filter_map(move |hole| {
+            let interval = (next_first, hole - 1);
+            next_first = hole + 1;
+            (interval.0 <= interval.1).then_some(interval)
+        })
+    }
+
+    /// Iterates on the values in the domain of the variable, skipping the holes of a sparse domain.
+    pub fn values(&self, var: VarRef) -> impl Iterator<Item = IntCst> + '_ {
+        self.intervals(var).flat_map(|(first, last)| first..=last)
+    }
+
+    /// Returns the value in the domain of the variable that is the closest to `value`, the smallest one on ties.
+    ///
+    /// The domain of the variable must not be empty.
+    pub fn closest_value(&self, var: VarRef, value: IntCst) -> IntCst {
+        let (lb, ub) = self.bounds(var);
+        let value = value.clamp(lb, ub);
+        if self.contains(var, value) {
+            return value;
+        }
+        // the value is in a hole, between the last value of an interval and the first value of the next one
+        let below = self.intervals(var).take_while(|&(first, _)| first < value).last();
+        let above = self.intervals(var).find(|&(first, _)| first > value);
+        match (below, above) {
+            (Some((_, last)), Some((first, _))) if value - last > first - value => first,
+            (Some((_, last)), _) => last,
+            (None, Some((first, _))) => first,
+            (None, None) => value,
+        }
+    }
+
+    /// Number of values in the domain of the variable.
+    pub fn size(&self, var: VarRef) -> u64 {
+        let (lb, ub) = self.bounds(var);
+        if lb > ub {
+            return 0;
+        }
+        (ub as i64 - lb as i64 + 1) as u64 - self.holes(var).count() as u64
+    }
+
+    /// Returns the literal that is true iff `var != value`, if one was registered for the sparse domain of the variable.
+    pub fn removal_literal(&self, var: VarRef, value: IntCst) -> Option<Lit> {
+        self.removals.get(var).and_then(|values| values.get(&value)).copied()
+    }
+
+    /// Registers `lit` as the literal that is true iff `var != value`.
+    ///
+    /// The caller is responsible for linking the literal to the bounds of the variable (see `Model::value_removal`).
+    pub(crate) fn add_removal_literal(&mut self, var: VarRef, value: IntCst, lit: Lit) {
+        self.removals.get_mut_or_insert(var, BTreeMap::new).insert(value, lit);
+    }
+
     pub fn entails(&self, lit: Lit) -> bool {
         debug_assert!(!self.doms.entails(lit) || !self.doms.entails(!lit));
         self.doms.entails(lit)
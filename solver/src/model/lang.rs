@@ -1,13 +1,17 @@
 pub mod alternative;
 mod atom;
 mod boolean;
+pub mod circuit;
 mod cst;
+pub mod euf;
 pub mod expr;
 mod fixed;
 mod int;
+pub mod interval;
 pub mod linear;
 pub mod max;
 pub mod mul;
+pub mod regular;
 pub mod reification;
 mod sym;
 mod validity_scope;
@@ -18,6 +22,7 @@ pub use boolean::BVar;
 pub use cst::Cst;
 pub use fixed::{FAtom, FVar};
 pub use int::{IAtom, IVar};
+pub use interval::IntervalVar;
 pub use validity_scope::*;
 
 use crate::core::{IntCst, INT_CST_MAX, INT_CST_MIN};
@@ -0,0 +1,240 @@
+//! Routing constraints over successor variables.
+//!
+//! A set of `n` nodes is described by an array of successor variables `succ[0..n]` where `succ[i] = j` indicates that
+//! the node `j` is visited immediately after node `i`. Each successor variable must have its domain included in `[0, n-1]`.
+//!
+//! Internally, each pair of nodes `(i, j)` is associated to an *arc literal*, true iff `succ[i] = j` (see [`arc`]).
+//! The constraints are handled by a dedicated propagator that detects subtours and explains its prunings with the arc literals.
+
+use crate::core::{IntCst, Lit, VarRef};
+use crate::model::lang::expr::{and, implies, leq};
+use crate::model::lang::{IAtom, IVar};
+use crate::model::{Label, Model};
+use crate::reif::{ReifExpr, Reifiable};
+use itertools::Itertools;
+
+/// Constraint enforcing that the successor variables form a single (Hamiltonian) circuit visiting all nodes.
+pub struct Circuit {
+    succ: Vec<IVar>,
+}
+
+impl Circuit {
+    pub fn new(succ: impl IntoIterator<Item = IVar>) -> Self {
+        Circuit {
+            succ: succ.into_iter().collect(),
+        }
+    }
+}
+
+/// Constraint enforcing that the successor variables form a single circuit over a subset of the nodes.
+/// A node `i` that is not part of the circuit is its own successor (`succ[i] = i`).
+/// All nodes are allowed to be excluded, in which case the circuit is empty.
+pub struct SubCircuit {
+    succ: Vec<IVar>,
+}
+
+impl SubCircuit {
+    pub fn new(succ: impl IntoIterator<Item = IVar>) -> Self {
+        SubCircuit {
+            succ: succ.into_iter().collect(),
+        }
+    }
+}
+
+/// Returns the literal that is true iff `succ[i] = j`, i.e., `j` is visited immediately after `i`.
+///
+/// The literal is interned in the model, so that several calls with the same parameters return the same literal.
+pub fn arc<Lbl: Label>(model: &mut Model<Lbl>, succ: &[IVar], i: usize, j: usize) -> Lit {
+    let j = j as IntCst;
+    model.reify(and([succ[i].leq(j), succ[i].geq(j)]))
+}
+
+fn arcs<Lbl: Label>(model: &mut Model<Lbl>, succ: &[IVar]) -> Vec<Vec<Lit>> {
+    let n = succ.len();
+    for &s in succ {
+        let (lb, ub) = model.state.bounds(s.into());
+        assert!(
+            0 <= lb && ub < n as IntCst,
+            "Domain of a successor variable is not included in [0, {}]",
+            n - 1
+        );
+    }
+    (0..n)
+        .map(|i| (0..n).map(|j| arc(model, succ, i, j)).collect_vec())
+        .collect_vec()
+}
+
+impl<Lbl: Label> Reifiable<Lbl> for Circuit {
+    fn decompose(self, model: &mut Model<Lbl>) -> ReifExpr {
+        let arcs = arcs(model, &self.succ);
+        ReifExpr::Circuit(NFCircuit { arcs, optional: false })
+    }
+}
+
+impl<Lbl: Label> Reifiable<Lbl> for SubCircuit {
+    fn decompose(self, model: &mut Model<Lbl>) -> ReifExpr {
+        let arcs = arcs(model, &self.succ);
+        ReifExpr::Circuit(NFCircuit { arcs, optional: true })
+    }
+}
+
+/// Normal form of the `Circuit` and `SubCircuit` constraints, where `arcs[i][j]` is true iff `j` is the successor of `i`.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct NFCircuit {
+    pub arcs: Vec<Vec<Lit>>,
+    /// If true, nodes may be excluded from the circuit (self-loop arcs `arcs[i][i]` are allowed).
+    pub optional: bool,
+}
+
+impl NFCircuit {
+    pub fn variables(&self) -> impl Iterator<Item = VarRef> + '_ {
+        self.arcs.iter().flatten().map(|l| l.variable())
+    }
+
+    /// Returns true if the successors of the nodes form a valid circuit.
+    /// `succ` returns the successor of each node, or `None` if the node does not have exactly one successor.
+    pub(crate) fn is_valid(&self, succ: impl Fn(usize) -> Option<usize>) -> bool {
+        let n = self.arcs.len();
+        let Some(succ) = (0..n).map(succ).collect::<Option<Vec<_>>>() else {
+            return false;
+        };
+        if !self.optional && (0..n).any(|i| succ[i] == i) {
+            return false;
+        }
+        let in_circuit = (0..n).filter(|&i| succ[i] != i).collect_vec();
+        let Some(&first) = in_circuit.first() else {
+            return true; // empty circuit
+        };
+        // follow the circuit from the first node and check that it visits all nodes of the circuit
+        let mut visited = 1;
+        let mut cur = succ[first];
+        while cur != first {
+            if succ[cur] == cur || visited >= in_circuit.len() {
+                return false;
+            }
+            visited += 1;
+            cur = succ[cur];
+        }
+        visited == in_circuit.len()
+    }
+}
+
+/// Links the successor variables to temporal variables:
+/// if `j` is the successor of `i`, then `end[i] + travel[i][j] <= start[j]`.
+///
+/// Arcs entering the `origin` node are ignored, as they close the circuit.
+/// Each of the constraints is a reified difference constraint (handled by the STN) that is implied by the corresponding
+/// arc literal.
+pub fn transition_times<Lbl: Label>(
+    model: &mut Model<Lbl>,
+    succ: &[IVar],
+    start: &[IAtom],
+    end: &[IAtom],
+    travel: &[Vec<IntCst>],
+    origin: usize,
+) {
+    let n = succ.len();
+    assert!(start.len() == n && end.len() == n && travel.len() == n);
+    for i in 0..n {
+        for j in (0..n).filter(|&j| j != i && j != origin) {
+            let arc = arc(model, succ, i, j);
+            if model.state.entails(!arc) {
+                continue;
+            }
+            let precedence = model.reify(leq(end[i] + travel[i][j], start[j]));
+            model.enforce(implies(arc, precedence), []);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::Solver;
+
+    type Model = crate::model::Model<String>;
+
+    fn succ_vars(model: &mut Model, n: usize) -> Vec<IVar> {
+        (0..n)
+            .map(|i| model.new_ivar(0, n as IntCst - 1, format!("succ{i}")))
+            .collect()
+    }
+
+    /// Counts the number of solutions of the model, projected on the successor variables.
+    fn count_solutions(model: Model, succ: &[IVar], check: impl Fn(&[IntCst])) -> usize {
+        let mut solver = Solver::new(model);
+        let vars = succ.iter().map(|&s| VarRef::from(s)).collect_vec();
+        let solutions = solver.enumerate(&vars).unwrap();
+        for sol in &solutions {
+            check(sol);
+        }
+        solutions.len()
+    }
+
+    #[test]
+    fn test_circuit_enumeration() {
+        for n in 2..6 {
+            let mut model = Model::new();
+            let succ = succ_vars(&mut model, n);
+            model.enforce(Circuit::new(succ.clone()), []);
+            let nf = NFCircuit {
+                arcs: vec![vec![Lit::TRUE; n]; n],
+                optional: false,
+            };
+            let count = count_solutions(model, &succ, |values| {
+                assert!(nf.is_valid(|i| Some(values[i] as usize)), "{values:?}");
+            });
+            // (n-1)! hamiltonian circuits
+            assert_eq!(count, (1..n).product::<usize>(), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_subcircuit_enumeration() {
+        // number of subcircuits on n nodes: sum_k C(n,k) * (k-1)!  for k >= 2, plus the empty circuit
+        let expected = [(2, 2), (3, 6), (4, 21), (5, 85)];
+        for (n, expected) in expected {
+            let mut model = Model::new();
+            let succ = succ_vars(&mut model, n);
+            model.enforce(SubCircuit::new(succ.clone()), []);
+            let nf = NFCircuit {
+                arcs: vec![vec![Lit::TRUE; n]; n],
+                optional: true,
+            };
+            let count = count_solutions(model, &succ, |values| {
+                assert!(nf.is_valid(|i| Some(values[i] as usize)), "{values:?}");
+            });
+            assert_eq!(count, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_transition_times() {
+        // 4 locations on a line, at positions 0, 10, 3, 7: the optimal tour from 0 visits them in order of position
+        let positions: [IntCst; 4] = [0, 10, 3, 7];
+        let n = positions.len();
+        let travel = (0..n)
+            .map(|i| (0..n).map(|j| (positions[i] - positions[j]).abs()).collect_vec())
+            .collect_vec();
+        let mut model = Model::new();
+        let succ = succ_vars(&mut model, n);
+        model.enforce(Circuit::new(succ.clone()), []);
+        let times = (0..n)
+            .map(|i| IAtom::from(model.new_ivar(0, 100, format!("t{i}"))))
+            .collect_vec();
+        let back = model.new_ivar(0, 100, "back");
+        model.enforce(leq(times[0], 0), []);
+        transition_times(&mut model, &succ, &times, &times, &travel, 0);
+        // time at which we come back to the origin
+        for i in 1..n {
+            let last = arc(&mut model, &succ, i, 0);
+            let precedence = model.reify(leq(times[i] + travel[i][0], back));
+            model.enforce(implies(last, precedence), []);
+        }
+
+        let mut solver = Solver::new(model);
+        let (cost, _) = solver.minimize(back).unwrap().unwrap();
+        // go to the furthest location and come back
+        assert_eq!(cost, 20);
+    }
+}
@@ -0,0 +1,160 @@
+//! Uninterpreted functions.
+//!
+//! An application `f(x1, ..., xn) = r` states that the variable `r` is the value of the function `f` on the arguments
+//! `x1, ..., xn`. Nothing is known about `f`, except that it is a function: two applications of `f` on equal
+//! arguments must have equal results (functional consistency).
+//!
+//! Arguments and results are typically symbolic variables ([`SVar`](crate::model::lang::SVar)) but may be any integer
+//! variable, which for instance allows representing the lifted state variables of a planning problem
+//! (`at(?r) = ?l`) without enumerating their groundings.
+//!
+//! Applications are handled by a dedicated congruence closure reasoner that explains all its inferences.
+
+use crate::core::VarRef;
+use crate::model::symbols::SymId;
+use crate::reif::ReifExpr;
+use itertools::Itertools;
+use std::fmt::{Debug, Formatter};
+
+/// Identifier of an uninterpreted function.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FunId(u32);
+
+impl FunId {
+    pub const fn new(id: u32) -> Self {
+        FunId(id)
+    }
+}
+
+impl From<SymId> for FunId {
+    fn from(sym: SymId) -> Self {
+        FunId(usize::from(sym) as u32)
+    }
+}
+
+impl Debug for FunId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "f{}", self.0)
+    }
+}
+
+/// Constraint stating that `result = fun(args)`.
+///
+/// The constraint is only active when all its variables are present.
+#[derive(Eq, PartialEq, Hash, Clone)]
+pub struct Apply {
+    pub fun: FunId,
+    pub args: Vec<VarRef>,
+    pub result: VarRef,
+}
+
+impl Apply {
+    pub fn new(
+        fun: impl Into<FunId>,
+        args: impl IntoIterator<Item = impl Into<VarRef>>,
+        result: impl Into<VarRef>,
+    ) -> Self {
+        Apply {
+            fun: fun.into(),
+            args: args.into_iter().map(|a| a.into()).collect(),
+            result: result.into(),
+        }
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = VarRef> + '_ {
+        self.args.iter().copied().chain(std::iter::once(self.result))
+    }
+}
+
+impl Debug for Apply {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}({}) = {:?}",
+            self.fun,
+            self.args.iter().map(|a| format!("{a:?}")).join(", "),
+            self.result
+        )
+    }
+}
+
+impl From<Apply> for ReifExpr {
+    fn from(value: Apply) -> Self {
+        ReifExpr::Apply(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::IntCst;
+    use crate::model::lang::expr::{eq, neq};
+    use crate::solver::Solver;
+
+    type Model = crate::model::Model<String>;
+
+    #[test]
+    fn test_pigeonhole() {
+        // f(x) = a, f(y) = b, f(z) = c  with a, b, c pairwise different and x, y, z in [0, 1]:
+        // two arguments are equal, which makes two results equal
+        let mut model = Model::new();
+        let f = FunId::new(0);
+        let args = ["x", "y", "z"].map(|n| model.new_ivar(0, 1, n));
+        let results = ["a", "b", "c"].map(|n| model.new_ivar(0, 10, n));
+        for (x, r) in args.iter().zip(results.iter()) {
+            model.enforce(Apply::new(f, [*x], *r), []);
+        }
+        for (i, j) in [(0, 1), (0, 2), (1, 2)] {
+            model.enforce(neq(results[i], results[j]), []);
+        }
+        let mut solver = Solver::new(model);
+        assert!(solver.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_equal_arguments() {
+        // f(x) = a, f(y) = b  with x = y and a != b, over domains too large to enumerate the arguments
+        let mut model = Model::new();
+        let f = FunId::new(0);
+        let x = model.new_ivar(0, 1_000_000, "x");
+        let y = model.new_ivar(0, 1_000_000, "y");
+        let a = model.new_ivar(0, 10, "a");
+        let b = model.new_ivar(0, 10, "b");
+        model.enforce(Apply::new(f, [x], a), []);
+        model.enforce(Apply::new(f, [y], b), []);
+        model.enforce(eq(x, y), []);
+        model.enforce(neq(a, b), []);
+        let mut solver = Solver::new(model);
+        assert!(solver.solve().unwrap().is_none());
+        // the equality of the arguments is used without binding them
+        assert!(solver.stats.num_decisions < 10);
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let mut model = Model::new();
+        let f = FunId::new(0);
+        let args = ["x", "y", "z"].map(|n| model.new_ivar(0, 2, n));
+        let results = ["a", "b", "c"].map(|n| model.new_ivar(0, 2, n));
+        for (x, r) in args.iter().zip(results.iter()) {
+            model.enforce(Apply::new(f, [*x], *r), []);
+        }
+        let vars = args
+            .iter()
+            .chain(results.iter())
+            .map(|&v| VarRef::from(v))
+            .collect_vec();
+        let mut solver = Solver::new(model);
+        let solutions = solver.enumerate(&vars).unwrap();
+        for s in &solutions {
+            for (i, j) in (0..3).tuple_combinations() {
+                assert!(s[i] != s[j] || s[3 + i] == s[3 + j], "{s:?}");
+            }
+        }
+        // each assignment of the arguments allows 3^k results, where k is the number of distinct arguments
+        let expected: usize = (0..27)
+            .map(|k: IntCst| 3usize.pow([k % 3, (k / 3) % 3, k / 9].iter().unique().count() as u32))
+            .sum();
+        assert_eq!(solutions.len(), expected);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::core::literals::Disjunction;
 use crate::core::*;
 use crate::model::lang::alternative::Alternative;
-use crate::model::lang::{Atom, FAtom, IAtom, SAtom};
+use crate::model::lang::{Atom, FAtom, IAtom, IVar, SAtom};
 use crate::model::{Label, Model};
 use crate::reif::{DifferenceExpression, ReifExpr, Reifiable};
 use env_param::EnvParam;
@@ -169,8 +169,7 @@ impl<Lbl: Label> Reifiable<Lbl> for Eq {
                     (SAtom::Var(x), SAtom::Cst(v)) | (SAtom::Cst(v), SAtom::Var(x)) => {
                         let var = x.var;
                         let value = v.sym.int_value();
-                        let (lb, ub) = model.state.bounds(var);
-                        if (lb..=ub).contains(&value) {
+                        if model.state.contains(var, value) {
                             ReifExpr::EqVal(x.var, v.sym.int_value())
                         } else {
                             ReifExpr::Lit(Lit::FALSE)
@@ -188,6 +187,14 @@ impl<Lbl: Label> Reifiable<Lbl> for Eq {
 }
 
 fn int_eq<Lbl: Label>(a: IAtom, b: IAtom, model: &mut Model<Lbl>) -> ReifExpr {
+    // reuse the value-removal literal of a sparse domain, if any
+    for (x, y) in [(a, b), (b, a)] {
+        if x.var != IVar::ZERO && y.var == IVar::ZERO {
+            if let Some(removed) = model.state.removal_literal(x.var.into(), y.shift - x.shift) {
+                return (!removed).into();
+            }
+        }
+    }
     let lr = model.reify(leq(a, b));
     let rl = model.reify(leq(b, a));
     and([lr, rl]).into()
@@ -0,0 +1,271 @@
+//! Interval variables and the associated scheduling constraints, modelled after the ones of CP Optimizer.
+//!
+//! An interval variable represents an optional activity with a start, an end and a duration.
+//! When the interval is present, the relation `start + duration = end` holds.
+//! All temporal constraints between intervals are only enforced when the involved intervals are present.
+
+use crate::core::{IntCst, Lit};
+use crate::model::lang::expr::leq;
+use crate::model::lang::linear::LinearSum;
+use crate::model::lang::max::{EqMax, EqMin};
+use crate::model::lang::{IAtom, IVar};
+use crate::model::{Label, Model};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// Identifies one of the variables of an interval, used to derive the labels of the variables at creation time.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum IntervalPart {
+    Start,
+    End,
+    Duration,
+}
+
+impl Display for IntervalPart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalPart::Start => write!(f, "start"),
+            IntervalPart::End => write!(f, "end"),
+            IntervalPart::Duration => write!(f, "duration"),
+        }
+    }
+}
+
+/// An optional interval `[start, end]`, with `start + duration = end`.
+///
+/// All variables of the interval share the `presence` literal of the interval.
+/// When the duration is fixed, no variable is created for the end and the duration:
+/// `end` is simply an offset of the `start` variable, and `duration` is a constant.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct IntervalVar {
+    pub presence: Lit,
+    pub start: IVar,
+    pub end: IAtom,
+    pub duration: IAtom,
+}
+
+impl IntervalVar {
+    pub fn start(&self) -> IAtom {
+        self.start.into()
+    }
+
+    pub fn end(&self) -> IAtom {
+        self.end
+    }
+
+    pub fn duration(&self) -> IAtom {
+        self.duration
+    }
+
+    /// Returns true if the duration of the interval is fixed (i.e. the `end` is an offset of the `start`).
+    pub fn has_fixed_duration(&self) -> bool {
+        self.duration.var == IVar::ZERO
+    }
+}
+
+impl<Lbl: Label> Model<Lbl> {
+    /// Creates a new interval that is always present.
+    ///
+    /// The interval will start no earlier than `lb`, end no later than `ub`, and have a duration in `[min_duration, max_duration]`.
+    /// The `label` function provides the label of each of the created variables.
+    pub fn new_interval_var(
+        &mut self,
+        lb: IntCst,
+        ub: IntCst,
+        min_duration: IntCst,
+        max_duration: IntCst,
+        label: impl Fn(IntervalPart) -> Lbl,
+    ) -> IntervalVar {
+        self.new_optional_interval_var(lb, ub, min_duration, max_duration, Lit::TRUE, label)
+    }
+
+    /// Creates a new interval that is present iff the `presence` literal is true.
+    ///
+    /// The difference constraints `start + min_duration <= end <= start + max_duration` are posted (and handled by the STN),
+    /// together with the linear constraint `start + duration = end` when the duration is not fixed.
+    pub fn new_optional_interval_var(
+        &mut self,
+        lb: IntCst,
+        ub: IntCst,
+        min_duration: IntCst,
+        max_duration: IntCst,
+        presence: Lit,
+        label: impl Fn(IntervalPart) -> Lbl,
+    ) -> IntervalVar {
+        assert!(0 <= min_duration && min_duration <= max_duration);
+        let start = self.new_optional_ivar(lb, ub - min_duration, presence, label(IntervalPart::Start));
+        if min_duration == max_duration {
+            return IntervalVar {
+                presence,
+                start,
+                end: start + min_duration,
+                duration: min_duration.into(),
+            };
+        }
+        let end = self.new_optional_ivar(lb + min_duration, ub, presence, label(IntervalPart::End));
+        let duration = self.new_optional_ivar(min_duration, max_duration, presence, label(IntervalPart::Duration));
+        self.enforce(leq(start + min_duration, end), [presence]);
+        self.enforce(leq(end, start + max_duration), [presence]);
+        // start + duration - end = 0
+        let sum = LinearSum::of(vec![IAtom::from(start), duration.into()]) - IAtom::from(end);
+        self.enforce(sum.clone().leq(0), [presence]);
+        self.enforce(sum.geq(0), [presence]);
+        IntervalVar {
+            presence,
+            start,
+            end: end.into(),
+            duration: duration.into(),
+        }
+    }
+}
+
+/// Enforces `a.end + delay <= b.start` when both intervals are present.
+pub fn end_before_start<Lbl: Label>(model: &mut Model<Lbl>, a: IntervalVar, b: IntervalVar, delay: IntCst) {
+    model.enforce(leq(a.end + delay, b.start), [a.presence, b.presence]);
+}
+
+/// Enforces `a.start + delay <= b.start` when both intervals are present.
+pub fn start_before_start<Lbl: Label>(model: &mut Model<Lbl>, a: IntervalVar, b: IntervalVar, delay: IntCst) {
+    model.enforce(leq(a.start + delay, b.start), [a.presence, b.presence]);
+}
+
+/// Enforces `a.end + delay <= b.end` when both intervals are present.
+pub fn end_before_end<Lbl: Label>(model: &mut Model<Lbl>, a: IntervalVar, b: IntervalVar, delay: IntCst) {
+    model.enforce(leq(a.end + delay, b.end), [a.presence, b.presence]);
+}
+
+/// Enforces `a.start + delay <= b.end` when both intervals are present.
+pub fn start_before_end<Lbl: Label>(model: &mut Model<Lbl>, a: IntervalVar, b: IntervalVar, delay: IntCst) {
+    model.enforce(leq(a.start + delay, b.end), [a.presence, b.presence]);
+}
+
+/// Enforces that the `main` interval spans all present `subs` intervals:
+/// `main` is present iff at least one of the `subs` is present, it starts with the first present sub-interval
+/// and ends with the last one.
+///
+/// The presence of each sub-interval must imply the presence of `main`.
+pub fn span<Lbl: Label>(model: &mut Model<Lbl>, main: IntervalVar, subs: &[IntervalVar]) {
+    assert!(!subs.is_empty(), "span with no sub-intervals");
+    for sub in subs {
+        debug_assert!(
+            model.state.implies(sub.presence, main.presence),
+            "The presence of a sub-interval does not imply the one of the spanning interval"
+        );
+    }
+    model.enforce(EqMin::new(main.start, subs.iter().map(|s| s.start)), [main.presence]);
+    // note: both constraints also require at least one sub-interval to be present when `main` is present
+    model.enforce(EqMax::new(main.end, subs.iter().map(|s| s.end)), [main.presence]);
+}
+
+/// Enforces that when `main` is present, exactly one of the `alternatives` is present and has the same start and end
+/// as `main`. When `main` is absent, all alternatives are absent.
+///
+/// The presence of each alternative must imply the presence of `main`.
+pub fn alternative<Lbl: Label>(model: &mut Model<Lbl>, main: IntervalVar, alternatives: &[IntervalVar]) {
+    assert!(!alternatives.is_empty(), "alternative with no candidates");
+    for alt in alternatives {
+        debug_assert!(
+            model.state.implies(alt.presence, main.presence),
+            "The presence of an alternative does not imply the one of the main interval"
+        );
+    }
+    let starts = alternatives.iter().map(|a| a.start()).collect_vec();
+    model.enforce(
+        crate::model::lang::expr::alternative(main.start, starts),
+        [main.presence],
+    );
+    if !main.has_fixed_duration() || alternatives.iter().any(|a| a.duration != main.duration) {
+        let ends = alternatives.iter().map(|a| a.end).collect_vec();
+        model.enforce(crate::model::lang::expr::alternative(main.end, ends), [main.presence]);
+    }
+}
+
+/// Enforces that each of the `others` intervals starts and ends together with `main` when both are present.
+pub fn synchronize<Lbl: Label>(model: &mut Model<Lbl>, main: IntervalVar, others: &[IntervalVar]) {
+    for other in others {
+        let scope = [main.presence, other.presence];
+        model.enforce(leq(main.start, other.start), scope);
+        model.enforce(leq(other.start, main.start), scope);
+        model.enforce(leq(main.end, other.end), scope);
+        model.enforce(leq(other.end, main.end), scope);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::extensions::AssignmentExt;
+    use crate::solver::Solver;
+
+    type Model = crate::model::Model<String>;
+
+    fn label(name: &'static str) -> impl Fn(IntervalPart) -> String {
+        move |part| format!("{part}({name})")
+    }
+
+    #[test]
+    fn test_variable_duration() {
+        let mut model = Model::new();
+        let a = model.new_interval_var(0, 100, 5, 10, label("a"));
+        let b = model.new_interval_var(0, 100, 3, 3, label("b"));
+        assert!(!a.has_fixed_duration());
+        assert!(b.has_fixed_duration());
+        end_before_start(&mut model, a, b, 2);
+        model.enforce(leq(IAtom::from(20), b.start), []);
+        model.enforce(leq(a.start, 0), []);
+
+        let mut solver = Solver::new(model);
+        let (makespan, sol) = solver.minimize(b.end).unwrap().unwrap();
+        assert_eq!(makespan, 23);
+        let start = sol.var_domain(a.start).lb;
+        let end = sol.var_domain(a.end).lb;
+        let duration = sol.var_domain(a.duration).lb;
+        assert_eq!(start + duration, end);
+        assert!((5..=10).contains(&duration));
+    }
+
+    #[test]
+    fn test_span_and_alternative() {
+        let mut model = Model::new();
+        let main = model.new_interval_var(0, 100, 0, 100, label("main"));
+        let alts = [4, 7, 2]
+            .into_iter()
+            .enumerate()
+            .map(|(i, dur)| {
+                let presence = model.new_presence_variable(main.presence, format!("alt{i}")).true_lit();
+                model.new_optional_interval_var(0, 100, dur, dur, presence, |part| format!("{part}(alt{i})"))
+            })
+            .collect_vec();
+        alternative(&mut model, main, &alts);
+        // forbid the shortest alternative
+        model.enforce(!alts[2].presence, []);
+
+        let whole = model.new_interval_var(0, 100, 0, 100, label("whole"));
+        let first = model.new_interval_var(3, 100, 2, 2, label("first"));
+        span(&mut model, whole, &[first, main]);
+        end_before_start(&mut model, first, main, 0);
+
+        let mut solver = Solver::new(model);
+        let (makespan, sol) = solver.minimize(whole.end).unwrap().unwrap();
+        // first: [3, 5], main with the first alternative: [5, 9]
+        assert_eq!(makespan, 9);
+        assert!(sol.entails(alts[0].presence));
+        assert_eq!(sol.var_domain(whole.start).lb, 3);
+        assert_eq!(sol.var_domain(main.start).lb, 5);
+        assert_eq!(sol.var_domain(main.duration).lb, 4);
+    }
+
+    #[test]
+    fn test_synchronize() {
+        let mut model = Model::new();
+        let a = model.new_interval_var(10, 100, 4, 8, label("a"));
+        let b = model.new_interval_var(0, 100, 6, 10, label("b"));
+        synchronize(&mut model, a, &[b]);
+
+        let mut solver = Solver::new(model);
+        let (makespan, sol) = solver.minimize(a.end).unwrap().unwrap();
+        assert_eq!(makespan, 16);
+        assert_eq!(sol.var_domain(b.start).lb, 10);
+        assert_eq!(sol.var_domain(b.end).lb, 16);
+    }
+}
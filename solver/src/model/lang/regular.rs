@@ -0,0 +1,461 @@
+//! Constraints on the sequence of values taken by an array of integer variables.
+//!
+//! The [`Regular`] constraint requires the sequence of values of its variables to be a word accepted by
+//! a deterministic finite automaton ([`Dfa`]), whose symbols are integer values.
+//! Common patterns (stretches, sequences, bounded number of occurrences in sliding windows) are provided as
+//! automaton constructors and, with the `regex` feature, an automaton can also be built from a regular expression
+//! (see `Dfa::from_regex`).
+//!
+//! Internally, each pair `(i, v)` of a position and a symbol of the automaton is associated to a literal that
+//! is true iff `vars[i] = v`. The constraint is handled by a propagator on the layered graph of the automaton
+//! unrolled over the variables, that removes the values that are not part of any accepting path.
+
+use crate::core::{IntCst, Lit, VarRef};
+use crate::model::lang::IVar;
+use crate::model::{Label, Model};
+use crate::reif::{ReifExpr, Reifiable};
+use itertools::Itertools;
+#[cfg(feature = "regex")]
+use regex_automata::dfa::{dense, Automaton, StartKind};
+#[cfg(feature = "regex")]
+use regex_automata::util::{start, syntax};
+#[cfg(feature = "regex")]
+use regex_automata::{Anchored, MatchKind};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// A deterministic finite automaton whose symbols are integer values.
+/// A missing transition leads to an implicit rejecting state.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct Dfa {
+    initial: usize,
+    accepting: Vec<bool>,
+    /// Outgoing transitions of each state, sorted by symbol.
+    transitions: Vec<Vec<(IntCst, usize)>>,
+}
+
+impl Dfa {
+    /// Creates an automaton with `num_states` states and no transitions, where no state is accepting.
+    pub fn new(num_states: usize, initial: usize) -> Self {
+        assert!(initial < num_states);
+        Dfa {
+            initial,
+            accepting: vec![false; num_states],
+            transitions: vec![Vec::new(); num_states],
+        }
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn initial(&self) -> usize {
+        self.initial
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    pub fn set_accepting(&mut self, state: usize) {
+        self.accepting[state] = true;
+    }
+
+    /// Adds a transition from `from` to `to` on `symbol`, replacing any previous transition from `from` on `symbol`.
+    pub fn add_transition(&mut self, from: usize, symbol: IntCst, to: usize) {
+        assert!(to < self.num_states());
+        let transitions = &mut self.transitions[from];
+        match transitions.binary_search_by_key(&symbol, |&(s, _)| s) {
+            Ok(i) => transitions[i].1 = to,
+            Err(i) => transitions.insert(i, (symbol, to)),
+        }
+    }
+
+    /// Returns the state reached from `state` by reading `symbol`, or `None` if there is no such transition.
+    pub fn next(&self, state: usize, symbol: IntCst) -> Option<usize> {
+        let transitions = &self.transitions[state];
+        transitions
+            .binary_search_by_key(&symbol, |&(s, _)| s)
+            .ok()
+            .map(|i| transitions[i].1)
+    }
+
+    /// Returns all symbols appearing on a transition of the automaton.
+    pub fn alphabet(&self) -> BTreeSet<IntCst> {
+        self.transitions.iter().flatten().map(|&(s, _)| s).collect()
+    }
+
+    /// Returns true if the automaton accepts the word.
+    pub fn accepts(&self, word: impl IntoIterator<Item = IntCst>) -> bool {
+        let mut state = self.initial;
+        for symbol in word {
+            match self.next(state, symbol) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.accepting[state]
+    }
+
+    /// Builds an automaton by exploring the states reachable from `initial` over the given alphabet.
+    fn explore<S: Clone + Eq + Hash>(
+        initial: S,
+        alphabet: &[IntCst],
+        next: impl Fn(&S, IntCst) -> Option<S>,
+        accepting: impl Fn(&S) -> bool,
+    ) -> Dfa {
+        let mut ids: HashMap<S, usize> = HashMap::new();
+        let mut states = vec![initial.clone()];
+        ids.insert(initial, 0);
+        let mut transitions = Vec::new();
+        let mut cur = 0;
+        while cur < states.len() {
+            for &symbol in alphabet {
+                if let Some(succ) = next(&states[cur], symbol) {
+                    let id = *ids.entry(succ.clone()).or_insert_with(|| {
+                        states.push(succ);
+                        states.len() - 1
+                    });
+                    transitions.push((cur, symbol, id));
+                }
+            }
+            cur += 1;
+        }
+        let mut dfa = Dfa::new(states.len(), 0);
+        for (from, symbol, to) in transitions {
+            dfa.add_transition(from, symbol, to);
+        }
+        for (id, state) in states.iter().enumerate() {
+            if accepting(state) {
+                dfa.set_accepting(id);
+            }
+        }
+        dfa.trim();
+        dfa
+    }
+
+    /// Removes all transitions to states from which no accepting state can be reached.
+    fn trim(&mut self) {
+        let mut live = self.accepting.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for q in 0..self.num_states() {
+                if !live[q] && self.transitions[q].iter().any(|&(_, next)| live[next]) {
+                    live[q] = true;
+                    changed = true;
+                }
+            }
+        }
+        for transitions in &mut self.transitions {
+            transitions.retain(|&(_, next)| live[next]);
+        }
+    }
+
+    /// Builds an automaton accepting the words fully matched by a regular expression.
+    ///
+    /// Each symbol is written as a single character: digits `0`-`9` stand for the values `0` to `9` and
+    /// letters `a`-`z` for the values `10` to `35`. For instance, `(0|1)*2{2,3}` accepts any sequence of `0` and `1`
+    /// terminated by two or three `2`. The expression follows the syntax of the `regex` crate, without unicode support.
+    #[cfg(feature = "regex")]
+    pub fn from_regex(pattern: &str) -> anyhow::Result<Dfa> {
+        let regex = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored)
+                    .minimize(true),
+            )
+            .syntax(syntax::Config::new().unicode(false).utf8(false))
+            .build(pattern)?;
+        let initial = regex.start_state(&start::Config::new().anchored(Anchored::Yes))?;
+        let alphabet = (0..36).collect_vec();
+        let dfa = Self::explore(
+            initial,
+            &alphabet,
+            |&state, symbol| {
+                let c = char::from_digit(symbol as u32, 36).unwrap();
+                let next = regex.next_state(state, c as u8);
+                (!regex.is_dead_state(next) && !regex.is_quit_state(next)).then_some(next)
+            },
+            // matches are delayed by one byte in the DFA, a word is matched if the end of input leads to a match state
+            |&state| regex.is_match_state(regex.next_eoi_state(state)),
+        );
+        Ok(dfa)
+    }
+
+    /// Builds an automaton accepting the words where each maximal stretch of consecutive occurrences of a value
+    /// has a length within the given bounds.
+    ///
+    /// Each element of `stretches` is a tuple `(value, min, max)` and only the values listed are accepted.
+    /// The `max` length can be `usize::MAX` to leave the stretches of a value unbounded.
+    pub fn stretch(stretches: &[(IntCst, usize, usize)]) -> Dfa {
+        assert!(stretches.iter().all(|&(_, min, max)| min <= max));
+        let bounds = |value: IntCst| {
+            stretches
+                .iter()
+                .find(|s| s.0 == value)
+                .map(|&(_, min, max)| (min.max(1), max))
+        };
+        let alphabet = stretches.iter().map(|s| s.0).collect_vec();
+        // state: value and length of the current stretch, where the length of unbounded stretches saturates at their minimum
+        Self::explore(
+            None,
+            &alphabet,
+            |state: &Option<(IntCst, usize)>, symbol| match *state {
+                Some((value, len)) if value == symbol => {
+                    let (min, max) = bounds(value).unwrap();
+                    if len < max && (max != usize::MAX || len < min) {
+                        Some(Some((value, len + 1)))
+                    } else if max == usize::MAX {
+                        Some(Some((value, len)))
+                    } else {
+                        None
+                    }
+                }
+                Some((value, len)) if len < bounds(value).unwrap().0 => None,
+                _ => Some(Some((symbol, 1))),
+            },
+            |state| match *state {
+                Some((value, len)) => len >= bounds(value).unwrap().0,
+                None => true,
+            },
+        )
+    }
+
+    /// Builds an automaton over `alphabet` accepting the words where every window of `window` consecutive symbols
+    /// contains between `min` and `max` occurrences of the `values`.
+    /// Words shorter than the window are not constrained.
+    pub fn sequence(alphabet: &[IntCst], values: &[IntCst], window: usize, min: usize, max: usize) -> Dfa {
+        assert!(window > 0);
+        // state: for each of the latest symbols (up to `window - 1`), whether it is one of the `values`
+        Self::explore(
+            Vec::new(),
+            alphabet,
+            |history: &Vec<bool>, symbol| {
+                let mut history = history.clone();
+                history.push(values.contains(&symbol));
+                if history.len() == window {
+                    let count = history.iter().filter(|&&b| b).count();
+                    if count < min || max < count {
+                        return None;
+                    }
+                    history.remove(0);
+                }
+                Some(history)
+            },
+            |_| true,
+        )
+    }
+
+    /// Builds an automaton over `alphabet` accepting the words where every window of `window` consecutive symbols
+    /// contains at most `k` occurrences of the `values`.
+    pub fn at_most_in_window(alphabet: &[IntCst], values: &[IntCst], window: usize, k: usize) -> Dfa {
+        Self::sequence(alphabet, values, window, 0, k)
+    }
+}
+
+/// Constraint enforcing that the sequence of values of the variables is accepted by the automaton.
+pub struct Regular {
+    vars: Vec<IVar>,
+    dfa: Dfa,
+}
+
+impl Regular {
+    pub fn new(vars: impl IntoIterator<Item = IVar>, dfa: Dfa) -> Self {
+        Regular {
+            vars: vars.into_iter().collect(),
+            dfa,
+        }
+    }
+}
+
+impl<Lbl: Label> Reifiable<Lbl> for Regular {
+    fn decompose(self, model: &mut Model<Lbl>) -> ReifExpr {
+        let alphabet = self.dfa.alphabet();
+        let values = self
+            .vars
+            .iter()
+            .map(|&var| {
+                let domain = alphabet
+                    .iter()
+                    .copied()
+                    .filter(|&value| model.state.contains(var.into(), value))
+                    .collect_vec();
+                domain
+                    .into_iter()
+                    .map(|value| (value, !model.value_removal(var, value)))
+                    .collect_vec()
+            })
+            .collect_vec();
+        ReifExpr::Regular(NFRegular { values, dfa: self.dfa })
+    }
+}
+
+/// Normal form of the `Regular` constraint, where `values[i]` contains, for each symbol `v` of the automaton in
+/// the domain of the `i`-th variable, a literal that is true iff the variable takes the value `v`.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct NFRegular {
+    pub values: Vec<Vec<(IntCst, Lit)>>,
+    pub dfa: Dfa,
+}
+
+impl NFRegular {
+    pub fn variables(&self) -> impl Iterator<Item = VarRef> + '_ {
+        self.values.iter().flatten().map(|(_, l)| l.variable())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::Solver;
+
+    type Model = crate::model::Model<String>;
+
+    /// All words of length `n` over the alphabet.
+    fn words(alphabet: &[IntCst], n: usize) -> Vec<Vec<IntCst>> {
+        (0..n)
+            .map(|_| alphabet.iter().copied())
+            .multi_cartesian_product()
+            .collect_vec()
+    }
+
+    /// Checks that the solutions of the `Regular` constraint over `n` variables are exactly the accepted words.
+    fn check_regular(dfa: &Dfa, alphabet: &[IntCst], n: usize, accepts: impl Fn(&[IntCst]) -> bool) {
+        let (lb, ub) = (*alphabet.iter().min().unwrap(), *alphabet.iter().max().unwrap());
+        let mut model = Model::new();
+        let vars = (0..n).map(|i| model.new_ivar(lb, ub, format!("x{i}"))).collect_vec();
+        model.enforce(Regular::new(vars.clone(), dfa.clone()), []);
+        let mut solver = Solver::new(model);
+        let vars = vars.iter().map(|&v| VarRef::from(v)).collect_vec();
+        let mut solutions = solver.enumerate(&vars).unwrap();
+        solutions.sort();
+
+        let expected = words(alphabet, n).into_iter().filter(|w| accepts(w)).collect_vec();
+        for word in words(alphabet, n) {
+            assert_eq!(dfa.accepts(word.iter().copied()), accepts(&word), "{word:?}");
+        }
+        assert_eq!(solutions, expected);
+    }
+
+    /// Length of the maximal stretches of the word.
+    fn stretches(word: &[IntCst]) -> Vec<(IntCst, usize)> {
+        word.iter()
+            .chunk_by(|&&v| v)
+            .into_iter()
+            .map(|(v, group)| (v, group.count()))
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_regex() {
+        let dfa = Dfa::from_regex("(0|1)*2{2,3}").unwrap();
+        assert!(dfa.accepts([0, 1, 1, 2, 2]));
+        assert!(dfa.accepts([2, 2, 2]));
+        assert!(!dfa.accepts([2, 2, 2, 2]));
+        assert!(!dfa.accepts([0, 2, 1, 2, 2]));
+        assert!(!dfa.accepts([]));
+        assert_eq!(dfa.alphabet(), [0, 1, 2].into());
+
+        let dfa = Dfa::from_regex("[a-c]?.").unwrap();
+        assert!(dfa.accepts([10, 35]));
+        assert!(dfa.accepts([7]));
+        assert!(!dfa.accepts([7, 7]));
+
+        assert!(Dfa::from_regex("(0").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_regular_enumeration() {
+        // no more than two consecutive night shifts (2), and never a day shift (1) right after a night shift
+        let dfa = Dfa::from_regex("(0|1|20|220)*(2|22)?").unwrap();
+        for n in 1..6 {
+            check_regular(&dfa, &[0, 1, 2], n, |w| {
+                w.windows(3).all(|w| w != [2, 2, 2]) && w.windows(2).all(|w| w != [2, 1])
+            });
+        }
+    }
+
+    #[test]
+    fn test_stretch() {
+        let dfa = Dfa::stretch(&[(0, 2, usize::MAX), (1, 1, 2), (2, 2, 3)]);
+        for n in 1..7 {
+            check_regular(&dfa, &[0, 1, 2], n, |w| {
+                stretches(w).iter().all(|&(v, len)| match v {
+                    0 => len >= 2,
+                    1 => len <= 2,
+                    _ => (2..=3).contains(&len),
+                })
+            });
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        let dfa = Dfa::sequence(&[0, 1, 2], &[1, 2], 3, 1, 2);
+        for n in 1..7 {
+            check_regular(&dfa, &[0, 1, 2], n, |w| {
+                w.windows(3)
+                    .all(|w| (1..=2).contains(&w.iter().filter(|&&v| v != 0).count()))
+            });
+        }
+        let dfa = Dfa::at_most_in_window(&[0, 1], &[1], 4, 1);
+        for n in 1..8 {
+            check_regular(&dfa, &[0, 1], n, |w| {
+                w.windows(4).all(|w| w.iter().sum::<IntCst>() <= 1)
+            });
+        }
+    }
+
+    #[test]
+    fn test_regular_intersection() {
+        // at most two consecutive night shifts and at least two days off in any window of 5 days
+        let stretch = Dfa::stretch(&[(0, 1, usize::MAX), (1, 1, usize::MAX), (2, 1, 2)]);
+        let rest = Dfa::sequence(&[0, 1, 2], &[0], 5, 2, 5);
+        let mut model = Model::new();
+        let vars = (0..7).map(|i| model.new_ivar(0, 2, format!("d{i}"))).collect_vec();
+        model.enforce(Regular::new(vars.clone(), stretch.clone()), []);
+        model.enforce(Regular::new(vars.clone(), rest.clone()), []);
+        let mut solver = Solver::new(model);
+        let vars = vars.iter().map(|&v| VarRef::from(v)).collect_vec();
+        let mut solutions = solver.enumerate(&vars).unwrap();
+        solutions.sort();
+
+        let expected = words(&[0, 1, 2], 7)
+            .into_iter()
+            .filter(|w| stretch.accepts(w.iter().copied()) && rest.accepts(w.iter().copied()))
+            .collect_vec();
+        assert!(!expected.is_empty());
+        assert_eq!(solutions, expected);
+    }
+
+    #[test]
+    fn test_optional_regular() {
+        // accepts the words `22` and `01`
+        let mut dfa = Dfa::new(4, 0);
+        dfa.add_transition(0, 2, 1);
+        dfa.add_transition(1, 2, 3);
+        dfa.add_transition(0, 0, 2);
+        dfa.add_transition(2, 1, 3);
+        dfa.set_accepting(3);
+        let mut model = Model::new();
+        let p = model.new_presence_variable(Lit::TRUE, "p").true_lit();
+        let q = model.new_presence_variable(Lit::TRUE, "q").true_lit();
+        let x = model.new_optional_ivar(0, 2, p, "x");
+        let y = model.new_optional_ivar(1, 2, q, "y");
+        // the constraint is only active when both variables are present
+        model.enforce(Regular::new([x, y], dfa), [p, q]);
+        // `y` cannot be 1, which prevents `x` from being 0 when the constraint is active
+        model.enforce(y.geq(2), [q]);
+        let mut solver = Solver::new(model);
+        let solutions = solver.enumerate(&[p.variable(), q.variable(), x.into()]).unwrap();
+        for x_value in 0..=2 {
+            assert!(solutions.contains(&vec![1, 0, x_value]));
+        }
+        assert!(solutions.contains(&vec![1, 1, 2]));
+        assert!(!solutions.contains(&vec![1, 1, 0]));
+    }
+}
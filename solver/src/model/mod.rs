@@ -7,4 +7,5 @@ pub use model_impl::*;
 pub mod extensions;
 pub mod lang;
 pub mod symbols;
+pub mod symmetry;
 pub mod types;
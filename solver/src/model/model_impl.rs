@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::fmt::Formatter;
 use std::sync::Arc;
@@ -293,6 +294,82 @@ impl<Lbl: Label> Model<Lbl> {
         IVar::new(dvar)
     }
 
+    /// Creates a new integer variable whose domain is the given set of values.
+    ///
+    /// The missing values between the smallest and largest ones are holes of a sparse domain (see [`Self::remove_value`]).
+    pub fn new_sparse_ivar(&mut self, values: impl IntoIterator<Item = IntCst>, label: impl Into<Lbl>) -> IVar {
+        self.create_sparse_ivar(values, None, label)
+    }
+
+    pub fn new_optional_sparse_ivar(
+        &mut self,
+        values: impl IntoIterator<Item = IntCst>,
+        presence: Lit,
+        label: impl Into<Lbl>,
+    ) -> IVar {
+        self.create_sparse_ivar(values, Some(presence), label)
+    }
+
+    fn create_sparse_ivar(
+        &mut self,
+        values: impl IntoIterator<Item = IntCst>,
+        presence: Option<Lit>,
+        label: impl Into<Lbl>,
+    ) -> IVar {
+        let values: BTreeSet<IntCst> = values.into_iter().collect();
+        let (Some(&lb), Some(&ub)) = (values.first(), values.last()) else {
+            panic!("Empty domain for a sparse variable")
+        };
+        let var = self.create_ivar(lb, ub, presence, label);
+        for value in (lb..=ub).filter(|v| !values.contains(v)) {
+            self.remove_value(var, value);
+        }
+        var
+    }
+
+    /// Returns a literal that is true iff the variable is different from `value`.
+    ///
+    /// The literal is interned and linked by clauses to the bounds of the variable, so that the domain of the
+    /// variable may have a hole at `value`: the bounds skip a removed value and a variable bound to `value`
+    /// makes the literal false. The literal is defined in the scope of the variable.
+    pub fn value_removal(&mut self, var: impl Into<VarRef>, value: IntCst) -> Lit {
+        let var = var.into();
+        if let Some(lit) = self.state.removal_literal(var, value) {
+            return lit;
+        }
+        let (lb, ub) = self.state.bounds(var);
+        if value < lb || ub < value {
+            return Lit::TRUE;
+        } else if lb == ub {
+            return Lit::FALSE;
+        }
+        let presence = self.state.presence(var);
+        let removed = self.state.new_optional_var(0, 1, presence);
+        self.shape.set_type(removed, Type::Bool);
+        let removed = removed.geq(1);
+        // removed  <=>  (var < value) or (var > value)
+        self.enforce(or([!removed, var.leq(value - 1), var.geq(value + 1)]), [presence]);
+        self.enforce(or([removed, var.geq(value)]), [presence]);
+        self.enforce(or([removed, var.leq(value)]), [presence]);
+        self.state.add_removal_literal(var, value, removed);
+        removed
+    }
+
+    /// Removes the value from the domain of the variable, leaving a hole if the value is strictly between its bounds.
+    pub fn remove_value(&mut self, var: impl Into<VarRef>, value: IntCst) {
+        let var = var.into();
+        let presence = self.state.presence(var);
+        if let Some(lit) = self.state.removal_literal(var, value) {
+            self.enforce(lit, [presence]);
+        } else {
+            self.enforce(or([var.leq(value - 1), var.geq(value + 1)]), [presence]);
+            let (lb, ub) = self.state.bounds(var);
+            if lb <= value && value <= ub {
+                self.state.add_removal_literal(var, value, Lit::TRUE);
+            }
+        }
+    }
+
     pub fn new_sym_var(&mut self, tpe: TypeId, label: impl Into<Lbl>) -> SVar {
         self.create_sym_var(tpe, None, label)
     }
@@ -419,6 +496,14 @@ impl<Lbl: Label> Model<Lbl> {
                     *expr = ReifExpr::Lit(Lit::FALSE)
                 } else if *v2 == lb && *v2 == ub {
                     *expr = ReifExpr::Lit(Lit::TRUE)
+                } else if let Some(removed) = self.state.removal_literal(*v1, *v2) {
+                    // reuse the value-removal literal of the sparse domain
+                    *expr = ReifExpr::Lit(!removed)
+                }
+            }
+            ReifExpr::NeqVal(v1, v2) => {
+                if let Some(removed) = self.state.removal_literal(*v1, *v2) {
+                    *expr = ReifExpr::Lit(removed)
                 }
             }
             _ => {}
@@ -605,3 +690,74 @@ impl<Lbl: Label> Shaped<Lbl> for Model<Lbl> {
         &self.shape
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::Solver;
+
+    type Model = super::Model<&'static str>;
+
+    #[test]
+    fn test_sparse_domains() {
+        let mut model = Model::new();
+        let x = model.new_sparse_ivar([1, 4, 7, 9], "x");
+        let y = model.new_ivar(0, 10, "y");
+        // y != 5 and y != 6 in the middle of its domain, through the removal literals
+        let y5 = model.value_removal(y, 5);
+        let y6 = model.value_removal(y, 6);
+        assert_eq!(model.value_removal(y, 5), y5);
+        assert_eq!(model.value_removal(y, 11), Lit::TRUE);
+        // equalities with a constant reuse the removal literal
+        assert_eq!(model.reify(crate::model::lang::expr::eq(y, 6)), !y6);
+        model.enforce(y5, []);
+        model.enforce(y6, []);
+        model.enforce(crate::model::lang::expr::eq(x, y), []);
+
+        let mut solver = Solver::new(model);
+        solver.propagate().unwrap();
+        let domains = &solver.model.state;
+        assert_eq!(domains.values(x.into()).collect::<Vec<_>>(), [1, 4, 7, 9]);
+        assert_eq!(domains.holes(y.into()).collect::<Vec<_>>(), [5, 6]);
+        assert_eq!(domains.intervals(y.into()).collect::<Vec<_>>(), [(1, 4), (7, 9)]);
+        assert_eq!(domains.size(y.into()), 7);
+        assert!(!domains.contains(y.into(), 5) && domains.contains(y.into(), 4));
+        assert_eq!(domains.closest_value(y.into(), 5), 4);
+        assert_eq!(domains.closest_value(y.into(), 6), 7);
+        assert_eq!(domains.closest_value(x.into(), 3), 4);
+
+        // the bounds skip the holes of the domain
+        solver.decide(Lit::geq(x, 5));
+        solver.propagate().unwrap();
+        assert_eq!(solver.model.state.bounds(x.into()), (7, 9));
+        assert_eq!(solver.model.state.bounds(y.into()), (7, 9));
+        solver.reset();
+
+        let solutions = solver.enumerate(&[x.into(), y.into()]).unwrap();
+        let mut values: Vec<IntCst> = solutions.iter().map(|s| s[0]).collect();
+        values.sort();
+        assert_eq!(values, [1, 4, 7, 9]);
+    }
+
+    #[test]
+    fn test_sparse_domain_pruning() {
+        // x = y  with  y >= 1, where x is either 0 or 1000: the hole leaves a single value for y
+        let solve = |sparse: bool| {
+            let mut model = Model::new();
+            let x = if sparse {
+                model.new_sparse_ivar([0, 1000], "x")
+            } else {
+                model.new_ivar(0, 1000, "x")
+            };
+            let y = model.new_ivar(0, 1000, "y");
+            model.enforce(crate::model::lang::expr::eq(x, y), []);
+            model.enforce(y.geq(1), []);
+            let mut solver = Solver::new(model);
+            let solution = solver.solve().unwrap().unwrap();
+            (solution.var_domain(y).lb, solver.stats.num_decisions)
+        };
+        assert_eq!(solve(true), (1000, 0));
+        let (_, num_decisions) = solve(false);
+        assert!(num_decisions > 0);
+    }
+}
@@ -0,0 +1,747 @@
+//! Detection of the symmetries of a model and their breaking with lex-leader constraints.
+//!
+//! The model is encoded as a vertex-colored graph whose automorphisms are symmetries of the model:
+//!
+//!  - each variable is a vertex, colored by its type and initial domain;
+//!  - each literal appearing in the model is a vertex attached to its variable, colored by its relation and value;
+//!  - each constraint is a vertex attached to its reification literal and colored by its kind and constants.
+//!    An argument of the constraint is attached through a *role* vertex that records its position in the constraint,
+//!    unless the constraint is invariant by permutation of its arguments (e.g. a disjunction);
+//!  - presence literals of optional variables and implications between literals are encoded with role vertices as well.
+//!
+//! Automorphisms are searched with a simple individualization-refinement procedure (as in nauty/saucy)
+//! that returns a set of generators of (a subgroup of) the automorphism group.
+//! Each generator is a permutation of the variables that maps the set of constraints onto itself,
+//! i.e., a *variable symmetry*. Value symmetries are captured when the values are encoded by boolean variables
+//! (e.g. the `x[i][j]` literals of an assignment of items to interchangeable bins).
+//!
+//! Given a generator `g`, the lex-leader constraint `X <=lex g(X)` (where `X` is the sequence of all variables in
+//! their creation order) only removes solutions for which a symmetric one remains.
+
+use crate::collections::ref_store::RefMap;
+use crate::core::*;
+use crate::model::lang::euf::FunId;
+use crate::model::lang::expr::{and, geq, implies, leq};
+use crate::model::lang::regular::Dfa;
+use crate::model::lang::Type;
+use crate::model::{Constraint, Label, Model};
+use crate::reif::ReifExpr;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// Maximum number of refinements done by the automorphism search (one per node of the search tree and per level of
+/// the chain of stabilizers), beyond which the search is abandoned.
+/// Generators found so far are still valid.
+pub const DEFAULT_SEARCH_BUDGET: usize = 10_000;
+
+/// A permutation of the variables of a model that maps the set of its constraints onto itself.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Symmetry {
+    /// Pairs `(x, g(x))` for all variables moved by the symmetry, sorted by `x`.
+    pub mapping: Vec<(VarRef, VarRef)>,
+}
+
+impl Symmetry {
+    /// Returns the image of the variable by this symmetry.
+    pub fn image(&self, var: VarRef) -> VarRef {
+        match self.mapping.binary_search_by_key(&var, |(x, _)| *x) {
+            Ok(i) => self.mapping[i].1,
+            Err(_) => var,
+        }
+    }
+}
+
+/// Returns generators of the variable symmetries of the model.
+///
+/// The variables in `fixed` (e.g. the objective of an optimization problem or variables used in assumptions)
+/// are mapped to themselves by all returned symmetries.
+/// Domains are read from the current state of the model, which should thus be at the root.
+pub fn find_symmetries<Lbl: Label>(model: &Model<Lbl>, fixed: &[VarRef]) -> Vec<Symmetry> {
+    find_symmetries_with_budget(model, fixed, DEFAULT_SEARCH_BUDGET)
+}
+
+/// Same as [`find_symmetries`] with an explicit limit on the number of refinements (see [`DEFAULT_SEARCH_BUDGET`]).
+pub fn find_symmetries_with_budget<Lbl: Label>(model: &Model<Lbl>, fixed: &[VarRef], budget: usize) -> Vec<Symmetry> {
+    let graph = ModelGraph::build(model, fixed);
+    let mut search = AutomorphismSearch {
+        graph: &graph.graph,
+        budget,
+    };
+    search
+        .generators()
+        .into_iter()
+        .filter_map(|perm| {
+            let mapping: Vec<(VarRef, VarRef)> = graph
+                .variables
+                .iter()
+                .enumerate()
+                .filter_map(|(vertex, &var)| {
+                    let image = perm[vertex] as usize;
+                    (image != vertex).then(|| (var, graph.variables[image]))
+                })
+                .collect();
+            // the permutation may only move constraints or role vertices (e.g. duplicated constraints)
+            (!mapping.is_empty()).then_some(Symmetry { mapping })
+        })
+        .collect()
+}
+
+/// Posts the lex-leader constraint `X <=lex g(X)` for the symmetry `g`,
+/// where `X` is the sequence of the variables moved by `g` in increasing order.
+///
+/// Since the lex-leader constraints of several symmetries must agree on the order of variables,
+/// the sequence is truncated before the first optional variable (truncating the sequence only weakens the constraint).
+/// Returns the length of the posted sequence.
+pub fn post_lex_leader<Lbl: Label>(model: &mut Model<Lbl>, symmetry: &Symmetry) -> usize {
+    // literal that is true iff all variables of the sequence so far are equal to their image
+    let mut prefix_equal = Lit::TRUE;
+    let mut length = 0;
+    for &(x, y) in &symmetry.mapping {
+        if model.state.presence(x) != Lit::TRUE || model.state.presence(y) != Lit::TRUE {
+            break;
+        }
+        if length > 0 {
+            // prefix_equal <=> (prefix_equal_before /\ x_prev == y_prev), where x_prev <= y_prev already holds
+            let (x_prev, y_prev) = symmetry.mapping[length - 1];
+            let ge = model.reify(geq(x_prev, y_prev));
+            prefix_equal = model.reify(and([prefix_equal, ge]));
+        }
+        let le = model.reify(leq(x, y));
+        model.enforce(implies(prefix_equal, le), []);
+        length += 1;
+    }
+    length
+}
+
+/// Finds the symmetries of the model and posts a lex-leader constraint for each of them.
+/// Returns the number of symmetries that were broken.
+pub fn break_symmetries<Lbl: Label>(model: &mut Model<Lbl>, fixed: &[VarRef]) -> usize {
+    find_symmetries(model, fixed)
+        .iter()
+        .filter(|symmetry| post_lex_leader(model, symmetry) > 0)
+        .count()
+}
+
+/// Color of a vertex of the graph. Two vertices with distinct colors are never mapped to one another.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Color {
+    Var {
+        lb: IntCst,
+        ub: IntCst,
+        tpe: Option<Type>,
+    },
+    /// A variable that must be mapped to itself.
+    Fixed(VarRef),
+    Lit {
+        plus: bool,
+        ub: IntCst,
+    },
+    /// Argument of a constraint with the given role, and an optional constant attached to it.
+    Role(&'static str, IntCst),
+    Constraint(&'static str, Vec<IntCst>),
+    Regular(Dfa, Vec<Vec<IntCst>>),
+    Apply(FunId),
+    Implication,
+}
+
+/// An undirected graph whose vertices are labeled by an integer color.
+struct ColoredGraph {
+    colors: Vec<u32>,
+    /// Sorted adjacency list of each vertex.
+    adjacency: Vec<Vec<u32>>,
+}
+
+impl ColoredGraph {
+    fn num_vertices(&self) -> usize {
+        self.colors.len()
+    }
+
+    fn is_automorphism(&self, perm: &[u32]) -> bool {
+        (0..self.num_vertices()).all(|u| {
+            let image = perm[u] as usize;
+            self.colors[u] == self.colors[image]
+                && self.adjacency[u]
+                    .iter()
+                    .all(|&v| self.adjacency[image].binary_search(&perm[v as usize]).is_ok())
+        })
+    }
+}
+
+/// Encoding of a model into a colored graph.
+struct ModelGraph {
+    graph: ColoredGraph,
+    /// Variables of the model, in increasing order. The vertex of the i-th variable is the i-th vertex of the graph.
+    variables: Vec<VarRef>,
+}
+
+struct GraphBuilder {
+    colors: HashMap<Color, u32>,
+    vertex_colors: Vec<u32>,
+    edges: Vec<(u32, u32)>,
+    variables: RefMap<VarRef, u32>,
+    literals: HashMap<Lit, u32>,
+}
+
+impl GraphBuilder {
+    fn vertex(&mut self, color: Color) -> u32 {
+        let num_colors = self.colors.len() as u32;
+        let color = *self.colors.entry(color).or_insert(num_colors);
+        self.vertex_colors.push(color);
+        self.vertex_colors.len() as u32 - 1
+    }
+
+    fn edge(&mut self, a: u32, b: u32) {
+        self.edges.push((a, b));
+    }
+
+    fn lit(&mut self, lit: Lit) -> u32 {
+        if let Some(&vertex) = self.literals.get(&lit) {
+            return vertex;
+        }
+        let vertex = self.vertex(Color::Lit {
+            plus: lit.svar().is_plus(),
+            ub: lit.ub_value(),
+        });
+        self.edge(vertex, self.variables[lit.variable()]);
+        self.literals.insert(lit, vertex);
+        vertex
+    }
+
+    /// Attaches the vertex `arg` to the vertex `parent` through a role vertex.
+    fn role(&mut self, parent: u32, role: &'static str, cst: IntCst, arg: u32) {
+        let vertex = self.vertex(Color::Role(role, cst));
+        self.edge(parent, vertex);
+        self.edge(vertex, arg);
+    }
+
+    fn var_role(&mut self, parent: u32, role: &'static str, cst: IntCst, var: VarRef) {
+        self.role(parent, role, cst, self.variables[var]);
+    }
+
+    fn lit_role(&mut self, parent: u32, role: &'static str, cst: IntCst, lit: Lit) {
+        let arg = self.lit(lit);
+        self.role(parent, role, cst, arg);
+    }
+
+    fn signed_var_role(&mut self, parent: u32, role: &'static str, cst: IntCst, svar: SignedVar) {
+        let role = match (role, svar.is_plus()) {
+            ("lhs", true) => "lhs+",
+            ("lhs", false) => "lhs-",
+            (_, true) => "item+",
+            (_, false) => "item-",
+        };
+        self.var_role(parent, role, cst, svar.variable());
+    }
+
+    fn constraint(&mut self, expr: &ReifExpr, value: Lit) {
+        let color = match expr {
+            ReifExpr::Lit(_) => Color::Constraint("lit", vec![]),
+            ReifExpr::MaxDiff(diff) => Color::Constraint("max-diff", vec![diff.ub]),
+            ReifExpr::Eq(_, _) => Color::Constraint("eq", vec![]),
+            ReifExpr::Neq(_, _) => Color::Constraint("neq", vec![]),
+            ReifExpr::EqVal(_, v) => Color::Constraint("eq-val", vec![*v]),
+            ReifExpr::NeqVal(_, v) => Color::Constraint("neq-val", vec![*v]),
+            ReifExpr::Or(_) => Color::Constraint("or", vec![]),
+            ReifExpr::And(_) => Color::Constraint("and", vec![]),
+            ReifExpr::Linear(lin) => Color::Constraint("linear", vec![lin.upper_bound]),
+            ReifExpr::Alternative(_) => Color::Constraint("alternative", vec![]),
+            ReifExpr::EqMax(_) => Color::Constraint("eq-max", vec![]),
+            ReifExpr::EqVarMulLit(_) => Color::Constraint("eq-var-mul-lit", vec![]),
+            ReifExpr::Circuit(c) => Color::Constraint("circuit", vec![c.arcs.len() as IntCst, c.optional as IntCst]),
+            ReifExpr::Regular(r) => Color::Regular(
+                r.dfa.clone(),
+                r.values.iter().map(|vs| vs.iter().map(|(v, _)| *v).collect()).collect(),
+            ),
+            ReifExpr::Apply(app) => Color::Apply(app.fun),
+        };
+        let c = self.vertex(color);
+        let value = self.lit(value);
+        self.edge(c, value);
+        match expr {
+            ReifExpr::Lit(l) => self.lit_role(c, "arg", 0, *l),
+            ReifExpr::MaxDiff(diff) => {
+                self.var_role(c, "b", 0, diff.b);
+                self.var_role(c, "a", 0, diff.a);
+            }
+            ReifExpr::Eq(a, b) | ReifExpr::Neq(a, b) => {
+                self.var_role(c, "arg", 0, *a);
+                self.var_role(c, "arg", 0, *b);
+            }
+            ReifExpr::EqVal(a, _) | ReifExpr::NeqVal(a, _) => self.var_role(c, "arg", 0, *a),
+            ReifExpr::Or(lits) | ReifExpr::And(lits) => {
+                for &l in lits {
+                    self.lit_role(c, "arg", 0, l);
+                }
+            }
+            ReifExpr::Linear(lin) => {
+                for item in &lin.sum {
+                    self.var_role(c, "term", item.factor, item.var);
+                }
+            }
+            ReifExpr::Alternative(alt) => {
+                self.var_role(c, "main", 0, alt.main);
+                for item in &alt.alternatives {
+                    self.var_role(c, "alt", item.cst, item.var);
+                }
+            }
+            ReifExpr::EqMax(em) => {
+                self.signed_var_role(c, "lhs", 0, em.lhs);
+                for item in &em.rhs {
+                    self.signed_var_role(c, "item", item.cst, item.var);
+                }
+            }
+            ReifExpr::EqVarMulLit(mul) => {
+                self.var_role(c, "lhs", 0, mul.lhs);
+                self.var_role(c, "rhs", 0, mul.rhs);
+                self.lit_role(c, "lit", 0, mul.lit);
+            }
+            ReifExpr::Circuit(circuit) => {
+                // positional encoding: symmetries between nodes of the circuit are not detected
+                let n = circuit.arcs.len();
+                for (i, row) in circuit.arcs.iter().enumerate() {
+                    for (j, &arc) in row.iter().enumerate() {
+                        self.lit_role(c, "arc", (i * n + j) as IntCst, arc);
+                    }
+                }
+            }
+            ReifExpr::Regular(regular) => {
+                for (i, values) in regular.values.iter().enumerate() {
+                    let position = self.vertex(Color::Role("position", i as IntCst));
+                    self.edge(c, position);
+                    // the values themselves are part of the color of the constraint, only their index is needed
+                    for (k, &(_, l)) in values.iter().enumerate() {
+                        self.lit_role(position, "value", k as IntCst, l);
+                    }
+                }
+            }
+            ReifExpr::Apply(app) => {
+                for (i, &arg) in app.args.iter().enumerate() {
+                    self.var_role(c, "arg", i as IntCst, arg);
+                }
+                self.var_role(c, "result", 0, app.result);
+            }
+        }
+    }
+}
+
+impl ModelGraph {
+    fn build<Lbl: Label>(model: &Model<Lbl>, fixed: &[VarRef]) -> Self {
+        let mut builder = GraphBuilder {
+            colors: Default::default(),
+            vertex_colors: Default::default(),
+            edges: Default::default(),
+            variables: Default::default(),
+            literals: Default::default(),
+        };
+        let variables = model.state.variables().collect_vec();
+        for &var in &variables {
+            let color = if var == VarRef::ZERO || var == VarRef::ONE || fixed.contains(&var) {
+                Color::Fixed(var)
+            } else {
+                let (lb, ub) = model.state.bounds(var);
+                let tpe = model.shape.types.get(var).copied();
+                Color::Var { lb, ub, tpe }
+            };
+            let vertex = builder.vertex(color);
+            builder.variables.insert(var, vertex);
+        }
+        for &var in &variables {
+            let presence = model.state.presence(var);
+            if presence != Lit::TRUE {
+                builder.lit_role(builder.variables[var], "presence", 0, presence);
+            }
+        }
+        for (from, to) in model.state.implications() {
+            let implication = builder.vertex(Color::Implication);
+            builder.lit_role(implication, "from", 0, from);
+            builder.lit_role(implication, "to", 0, to);
+        }
+        for Constraint::Reified(expr, value) in &model.shape.constraints {
+            builder.constraint(expr, *value);
+        }
+
+        let mut adjacency = vec![Vec::new(); builder.vertex_colors.len()];
+        for &(a, b) in &builder.edges {
+            adjacency[a as usize].push(b);
+            adjacency[b as usize].push(a);
+        }
+        for neighbors in &mut adjacency {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        ModelGraph {
+            graph: ColoredGraph {
+                colors: builder.vertex_colors,
+                adjacency,
+            },
+            variables,
+        }
+    }
+}
+
+/// Search for the generators of the automorphism group of a colored graph.
+///
+/// A coloring (one color per vertex) is *equitable* if any two vertices of the same color have the same number of
+/// neighbors of each color. Refinement splits the colors of a coloring until it is equitable.
+/// Because refinement is invariant by isomorphism, an automorphism mapping `v` to `w` can only exist if
+/// the refinements of the coloring where `v` (resp. `w`) is given a new unique color are compatible.
+/// Individualizing vertices until the coloring is discrete (i.e. each vertex has a distinct color) thus gives
+/// a candidate permutation that is checked on the graph.
+struct AutomorphismSearch<'a> {
+    graph: &'a ColoredGraph,
+    /// Remaining number of refinements that may be done, each node of the search tree and each level of the chain
+    /// of stabilizers costing one refinement.
+    budget: usize,
+}
+
+impl AutomorphismSearch<'_> {
+    fn generators(&mut self) -> Vec<Vec<u32>> {
+        let mut generators = Vec::new();
+        let mut orbits = Orbits::new(self.graph.num_vertices());
+        let mut coloring = vec![self.graph.colors.clone()];
+        if !self.refine(&mut coloring) {
+            unreachable!("a coloring is always compatible with itself")
+        }
+        let mut coloring = coloring.pop().unwrap();
+
+        // explore the chain of stabilizers: at each level, look for an automorphism mapping the first vertex of the
+        // first non-trivial cell to each other vertex of the cell, then fix this vertex.
+        while let Some(cell) = first_non_trivial_cell(&coloring) {
+            let v = cell[0];
+            for &w in &cell[1..] {
+                if orbits.same(v, w) {
+                    continue; // an automorphism mapping v to w is already generated
+                }
+                if self.budget == 0 {
+                    return generators;
+                }
+                let left = individualize(&coloring, v);
+                let right = individualize(&coloring, w);
+                if let Some(perm) = self.find_mapping(left, right) {
+                    orbits.add_permutation(&perm);
+                    generators.push(perm);
+                }
+            }
+            if self.budget == 0 {
+                return generators;
+            }
+            self.budget -= 1;
+            let mut next = vec![individualize(&coloring, v)];
+            let _ = self.refine(&mut next);
+            coloring = next.pop().unwrap();
+        }
+        generators
+    }
+
+    /// Looks for an automorphism that maps each vertex colored by `left` to the vertex with the same color in `right`.
+    fn find_mapping(&mut self, left: Vec<u32>, right: Vec<u32>) -> Option<Vec<u32>> {
+        if self.budget == 0 {
+            return None;
+        }
+        self.budget -= 1;
+        let mut colorings = vec![left, right];
+        if !self.refine(&mut colorings) {
+            return None;
+        }
+        let right = colorings.pop().unwrap();
+        let left = colorings.pop().unwrap();
+        match first_non_trivial_cell(&left) {
+            None => {
+                let mut vertex_of_color = vec![0; right.len()];
+                for (vertex, &color) in right.iter().enumerate() {
+                    vertex_of_color[color as usize] = vertex as u32;
+                }
+                let perm: Vec<u32> = left.iter().map(|&color| vertex_of_color[color as usize]).collect();
+                self.graph.is_automorphism(&perm).then_some(perm)
+            }
+            Some(cell) => {
+                let v = cell[0];
+                let color = left[v as usize];
+                let candidates = (0..right.len() as u32).filter(|&w| right[w as usize] == color);
+                for w in candidates {
+                    if let Some(perm) = self.find_mapping(individualize(&left, v), individualize(&right, w)) {
+                        return Some(perm);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Refines all colorings in lockstep until they are equitable.
+    /// Colors are renumbered canonically, so that the refined colorings remain comparable.
+    /// Returns false if the colorings are found incompatible, i.e., if no automorphism may map the vertices of
+    /// one coloring to the vertices of the same color in another.
+    fn refine(&self, colorings: &mut [Vec<u32>]) -> bool {
+        let mut num_colors = count_colors(&colorings[0]);
+        loop {
+            let signatures: Vec<Vec<(u32, Vec<u32>)>> = colorings
+                .iter()
+                .map(|coloring| {
+                    (0..coloring.len())
+                        .map(|v| {
+                            let mut neighbors: Vec<u32> =
+                                self.graph.adjacency[v].iter().map(|&n| coloring[n as usize]).collect();
+                            neighbors.sort_unstable();
+                            (coloring[v], neighbors)
+                        })
+                        .collect()
+                })
+                .collect();
+            let mut reference = signatures[0].clone();
+            reference.sort_unstable();
+            for other in &signatures[1..] {
+                let mut other = other.clone();
+                other.sort_unstable();
+                if other != reference {
+                    return false;
+                }
+            }
+            reference.dedup();
+            for (coloring, signatures) in colorings.iter_mut().zip(signatures) {
+                for (v, signature) in signatures.into_iter().enumerate() {
+                    coloring[v] = reference.binary_search(&signature).unwrap() as u32;
+                }
+            }
+            if reference.len() == num_colors {
+                return true;
+            }
+            num_colors = reference.len();
+        }
+    }
+}
+
+fn count_colors(coloring: &[u32]) -> usize {
+    let mut colors = coloring.to_vec();
+    colors.sort_unstable();
+    colors.dedup();
+    colors.len()
+}
+
+/// Returns the vertices of the color with the smallest index that has more than one vertex.
+fn first_non_trivial_cell(coloring: &[u32]) -> Option<Vec<u32>> {
+    let mut counts = vec![0usize; coloring.len()];
+    for &color in coloring {
+        counts[color as usize] += 1;
+    }
+    let color = counts.iter().position(|&count| count > 1)? as u32;
+    Some(
+        (0..coloring.len() as u32)
+            .filter(|&v| coloring[v as usize] == color)
+            .collect(),
+    )
+}
+
+/// Gives a new unique color to the vertex.
+fn individualize(coloring: &[u32], vertex: u32) -> Vec<u32> {
+    let mut coloring = coloring.to_vec();
+    coloring[vertex as usize] = coloring.len() as u32;
+    coloring
+}
+
+/// Orbits of the vertices under the group generated by the permutations found so far (union-find).
+struct Orbits {
+    parent: Vec<u32>,
+}
+
+impl Orbits {
+    fn new(n: usize) -> Self {
+        Orbits {
+            parent: (0..n as u32).collect(),
+        }
+    }
+
+    fn find(&mut self, v: u32) -> u32 {
+        let mut root = v;
+        while self.parent[root as usize] != root {
+            root = self.parent[root as usize];
+        }
+        let mut v = v;
+        while self.parent[v as usize] != root {
+            let next = self.parent[v as usize];
+            self.parent[v as usize] = root;
+            v = next;
+        }
+        root
+    }
+
+    fn same(&mut self, a: u32, b: u32) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    fn add_permutation(&mut self, perm: &[u32]) {
+        for (v, &image) in perm.iter().enumerate() {
+            let (a, b) = (self.find(v as u32), self.find(image));
+            self.parent[a as usize] = b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::lang::expr::{neq, or};
+    use crate::model::lang::linear::LinearSum;
+    use crate::model::lang::{BVar, IVar};
+    use crate::solver::{Exit, Solver};
+    use itertools::Itertools;
+
+    type Model = crate::model::Model<String>;
+
+    #[test]
+    fn test_interchangeable_variables() {
+        // x, y and z are pairwise distinct and only appear in symmetric constraints
+        let mut model = Model::new();
+        let vars = (0..3).map(|i| model.new_ivar(0, 5, format!("x{i}"))).collect_vec();
+        let other = model.new_ivar(0, 10, "other");
+        for (i, &a) in vars.iter().enumerate() {
+            for &b in &vars[i + 1..] {
+                model.enforce(neq(a, b), []);
+            }
+        }
+        model.enforce(leq(vars[0] + 1, other), []);
+
+        // x0 is the only variable in relation with `other`, only x1 and x2 are interchangeable
+        let symmetries = find_symmetries(&model, &[]);
+        assert_eq!(symmetries.len(), 1);
+        let x1 = VarRef::from(vars[1]);
+        let x2 = VarRef::from(vars[2]);
+        // the symmetry also swaps the literals reifying the constraints on x1 and x2
+        assert_eq!(symmetries[0].mapping[..2], [(x1, x2), (x2, x1)]);
+        assert_eq!(symmetries[0].image(VarRef::from(vars[0])), VarRef::from(vars[0]));
+
+        // fixing x1 removes the symmetry
+        assert!(find_symmetries(&model, &[x1]).is_empty());
+
+        // without the constraint on x0, all permutations of the three variables are symmetries
+        let mut model = Model::new();
+        let vars = (0..3).map(|i| model.new_ivar(0, 5, format!("x{i}"))).collect_vec();
+        for (i, &a) in vars.iter().enumerate() {
+            for &b in &vars[i + 1..] {
+                model.enforce(neq(a, b), []);
+            }
+        }
+        assert_eq!(find_symmetries(&model, &[]).len(), 2);
+        // the search is abandoned when running out of budget, keeping the generators found so far
+        assert!(find_symmetries_with_budget(&model, &[], 0).is_empty());
+        assert_eq!(find_symmetries_with_budget(&model, &[], 3).len(), 1);
+        assert_eq!(find_symmetries_with_budget(&model, &[], 4).len(), 2);
+    }
+
+    #[test]
+    fn test_asymmetric_constraints() {
+        // x <= y is not symmetric in x and y
+        let mut model = Model::new();
+        let x = model.new_ivar(0, 5, "x");
+        let y = model.new_ivar(0, 5, "y");
+        model.enforce(leq(x, y), []);
+        assert!(find_symmetries(&model, &[]).is_empty());
+
+        // variables with distinct domains cannot be interchanged
+        let mut model = Model::new();
+        let x = model.new_ivar(0, 5, "x");
+        let y = model.new_ivar(0, 6, "y");
+        model.enforce(neq(x, y), []);
+        assert!(find_symmetries(&model, &[]).is_empty());
+    }
+
+    /// Number of ways of packing items of the given weights in `num_bins` bins of the given capacity.
+    /// Bins are interchangeable, and so are items of the same weight.
+    fn bin_packing(weights: &[IntCst], num_bins: usize, capacity: IntCst) -> (Model, Vec<Vec<BVar>>) {
+        let mut model = Model::new();
+        let assignment = weights
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                (0..num_bins)
+                    .map(|b| model.new_bvar(format!("x[{i}][{b}]")))
+                    .collect_vec()
+            })
+            .collect_vec();
+        for row in &assignment {
+            // each item is placed in exactly one bin
+            let lits = row.iter().map(|b| b.true_lit()).collect_vec();
+            model.enforce(or(lits.clone()), []);
+            for (b, &l1) in lits.iter().enumerate() {
+                for &l2 in &lits[b + 1..] {
+                    model.enforce(or([!l1, !l2]), []);
+                }
+            }
+        }
+        for b in 0..num_bins {
+            let mut load = LinearSum::zero();
+            for (i, row) in assignment.iter().enumerate() {
+                load += row[b].int_view() * weights[i];
+            }
+            model.enforce(load.leq(capacity), []);
+        }
+        (model, assignment)
+    }
+
+    fn count_solutions(model: Model, assignment: &[Vec<BVar>]) -> usize {
+        let vars = assignment.iter().flatten().map(|&b| VarRef::from(b)).collect_vec();
+        let mut solver = Solver::new(model);
+        solver.enumerate(&vars).unwrap().len()
+    }
+
+    #[test]
+    fn test_bin_packing_lex_leader() {
+        let weights = [3, 3, 2, 2, 1];
+        let (model, assignment) = bin_packing(&weights, 3, 4);
+        let num_solutions = count_solutions(model.clone(), &assignment);
+        assert!(num_solutions > 0);
+
+        let mut reduced = model.clone();
+        let num_broken = break_symmetries(&mut reduced, &[]);
+        assert!(num_broken > 0);
+        let num_reduced = count_solutions(reduced, &assignment);
+        // at least one solution remains in each class of symmetric solutions, and bin symmetry divides the count
+        assert!(num_reduced >= 1);
+        assert!(num_reduced * 6 <= num_solutions, "{num_reduced} {num_solutions}");
+
+        // infeasibility is preserved
+        let (mut model, assignment) = bin_packing(&[3, 3, 3, 3], 3, 4);
+        break_symmetries(&mut model, &[]);
+        assert_eq!(count_solutions(model, &assignment), 0);
+    }
+
+    #[test]
+    fn test_solver_option() {
+        // minimize the number of bins used
+        let weights = [4, 3, 3, 2, 2, 2];
+        let (mut model, assignment) = bin_packing(&weights, 4, 6);
+        let used = (0..4)
+            .map(|b| {
+                let lits = assignment.iter().map(|row| row[b].true_lit()).collect_vec();
+                model.reify(or(lits))
+            })
+            .collect_vec();
+        let mut num_used = LinearSum::zero();
+        for &u in &used {
+            num_used += IVar::new(u.variable());
+        }
+        let obj = model.new_ivar(0, 4, "obj");
+        model.enforce(num_used.leq(obj), []);
+
+        for symmetry_breaking in [false, true] {
+            let mut solver = Solver::new(model.clone());
+            solver.symmetry_breaking = symmetry_breaking;
+            let (cost, _) = solver.minimize(obj).unwrap().unwrap();
+            assert_eq!(cost, 3);
+        }
+    }
+
+    #[test]
+    fn test_later_query_on_moved_variables() {
+        use crate::backtrack::Backtrack;
+
+        let (model, assignment) = bin_packing(&[3, 3, 2, 2, 1], 3, 4);
+        let vars = assignment.iter().flatten().map(|&b| VarRef::from(b)).collect_vec();
+        let mut solver = Solver::new(model);
+        solver.symmetry_breaking = true;
+        assert!(solver.solve().unwrap().is_some());
+        solver.reset();
+        // the lex-leader constraints posted for the first query would remove symmetric solutions
+        assert!(matches!(solver.enumerate(&vars), Err(Exit::MovedBySymmetries(_))));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::core::state::{Cause, Domains, DomainsSnapshot, Explanation};
+use crate::core::Lit;
+use crate::model::lang::circuit::NFCircuit;
+use crate::reasoners::cp::{Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Propagator for the `Circuit` and `SubCircuit` constraints, that eliminates subtours.
+///
+/// It assumes that the "exactly one successor" and "exactly one predecessor" parts of the constraint
+/// are handled by clauses, and only reasons on the arcs that are currently true:
+///
+///  - for each chain `s -> ... -> e` of true arcs, the closing arc `e -> s` is removed if the chain does not
+///    visit all nodes (or for a subcircuit, if a node outside the chain is required to be in the circuit).
+///  - a closed cycle that does not visit all nodes is a contradiction (for a subcircuit, all nodes outside
+///    the cycle are excluded from the circuit).
+#[derive(Clone)]
+pub(crate) struct CircuitPropagator {
+    arcs: Vec<Vec<Lit>>,
+    optional: bool,
+}
+
+impl CircuitPropagator {
+    pub fn new(circuit: &NFCircuit) -> Self {
+        CircuitPropagator {
+            arcs: circuit.arcs.clone(),
+            optional: circuit.optional,
+        }
+    }
+
+    fn num_nodes(&self) -> usize {
+        self.arcs.len()
+    }
+
+    /// Returns the successor of each node through a true arc (self-loops are ignored).
+    /// Returns `None` if a node has several successors or several predecessors, which will be detected by the clauses.
+    fn successors(&self, entails: impl Fn(Lit) -> bool) -> Option<Vec<Option<usize>>> {
+        let n = self.num_nodes();
+        let mut succ = vec![None; n];
+        let mut has_pred = vec![false; n];
+        for (i, arcs) in self.arcs.iter().enumerate() {
+            for (j, &arc) in arcs.iter().enumerate() {
+                if i != j && entails(arc) {
+                    if succ[i].is_some() || has_pred[j] {
+                        return None;
+                    }
+                    succ[i] = Some(j);
+                    has_pred[j] = true;
+                }
+            }
+        }
+        Some(succ)
+    }
+
+    /// Follows the successors from `start`, and returns the nodes visited until reaching a node without successor
+    /// (in which case the result is a chain) or coming back to `start` (in which case the result is a cycle).
+    fn follow(succ: &[Option<usize>], start: usize) -> (Vec<usize>, bool) {
+        let mut nodes = vec![start];
+        let mut cur = start;
+        while let Some(next) = succ[cur] {
+            if next == start {
+                return (nodes, true);
+            }
+            if nodes.len() > succ.len() {
+                break; // entered a cycle not containing the start, cannot happen with a single predecessor per node
+            }
+            nodes.push(next);
+            cur = next;
+        }
+        (nodes, false)
+    }
+
+    /// Returns a node outside of `nodes` that is required to be part of the subcircuit, if any.
+    fn required_outside(&self, nodes: &[usize], entails: impl Fn(Lit) -> bool) -> Option<usize> {
+        (0..self.num_nodes()).find(|&k| !nodes.contains(&k) && entails(!self.arcs[k][k]))
+    }
+
+    fn push_arcs(&self, nodes: &[usize], cycle: bool, out: &mut Explanation) {
+        for w in nodes.windows(2) {
+            out.push(self.arcs[w[0]][w[1]]);
+        }
+        if cycle {
+            out.push(self.arcs[*nodes.last().unwrap()][nodes[0]]);
+        }
+    }
+}
+
+impl Propagator for CircuitPropagator {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        // only arcs that become true and nodes that are required to be in the circuit may trigger an inference
+        for (i, arcs) in self.arcs.iter().enumerate() {
+            for (j, &arc) in arcs.iter().enumerate() {
+                context.add_lit_watch(if i == j { !arc } else { arc }, id);
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "circuit"
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        let n = self.num_nodes();
+        let Some(succ) = self.successors(|l| domains.entails(l)) else {
+            return Ok(());
+        };
+        let mut has_pred = vec![false; n];
+        for &j in succ.iter().flatten() {
+            has_pred[j] = true;
+        }
+        let mut visited = vec![false; n];
+
+        // chains, starting from the nodes without predecessor
+        for head in (0..n).filter(|&i| !has_pred[i] && succ[i].is_some()) {
+            let (chain, _) = Self::follow(&succ, head);
+            let tail = *chain.last().unwrap();
+            for &k in &chain {
+                visited[k] = true;
+            }
+            let forbidden = if self.optional {
+                self.required_outside(&chain, |l| domains.entails(l)).is_some()
+            } else {
+                chain.len() < n
+            };
+            if forbidden {
+                domains.set(!self.arcs[tail][head], cause)?;
+            }
+        }
+
+        // all remaining nodes with a successor are part of a cycle
+        for start in 0..n {
+            if visited[start] || succ[start].is_none() {
+                continue;
+            }
+            let (cycle, closed) = Self::follow(&succ, start);
+            debug_assert!(closed);
+            for &k in &cycle {
+                visited[k] = true;
+            }
+            if self.optional {
+                // all other nodes are excluded from the circuit
+                for k in (0..n).filter(|k| !cycle.contains(k)) {
+                    domains.set(self.arcs[k][k], cause)?;
+                }
+            } else if cycle.len() < n {
+                let mut explanation = Explanation::with_capacity(cycle.len());
+                self.push_arcs(&cycle, true, &mut explanation);
+                return Err(Contradiction::Explanation(explanation));
+            }
+        }
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, state: &DomainsSnapshot, out_explanation: &mut Explanation) {
+        // Note: several arc literals may be on the same variable, so the literal may be entailed by several of them.
+        // We thus look for any inference that would have entailed the literal.
+        let n = self.num_nodes();
+        let succ = self
+            .successors(|l| state.entails(l))
+            .expect("Propagation made with several successors or predecessors");
+
+        if self.optional {
+            // node `k` excluded because of a cycle that does not contain it
+            for k in (0..n).filter(|&k| self.arcs[k][k].entails(literal)) {
+                let cycle = (0..n)
+                    .map(|i| Self::follow(&succ, i))
+                    .find(|(cycle, closed)| *closed && !cycle.contains(&k));
+                if let Some((cycle, _)) = cycle {
+                    self.push_arcs(&cycle, true, out_explanation);
+                    return;
+                }
+            }
+        }
+
+        // removal of a closing arc `tail -> head`
+        for (tail, head) in (0..n).flat_map(|i| (0..n).map(move |j| (i, j))) {
+            if tail == head || !(!self.arcs[tail][head]).entails(literal) {
+                continue;
+            }
+            let (chain, closed) = Self::follow(&succ, head);
+            if closed || chain.len() < 2 || chain.last() != Some(&tail) {
+                continue;
+            }
+            if self.optional {
+                if let Some(k) = self.required_outside(&chain, |l| state.entails(l)) {
+                    self.push_arcs(&chain, false, out_explanation);
+                    out_explanation.push(!self.arcs[k][k]);
+                    return;
+                }
+            } else if chain.len() < n {
+                self.push_arcs(&chain, false, out_explanation);
+                return;
+            }
+        }
+        panic!("Unexpected literal to explain")
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
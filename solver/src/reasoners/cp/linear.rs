@@ -196,7 +196,7 @@ impl LinearSumLeq {
 
 impl Propagator for LinearSumLeq {
     fn setup(&self, id: PropagatorId, context: &mut Watches) {
-        context.add_watch(self.active.variable(), id);
+        context.add_lit_watch(self.active, id);
         for e in &self.elements {
             if !e.is_constant() {
                 context.add_lb_watch(e.var, id);
@@ -204,6 +204,15 @@ impl Propagator for LinearSumLeq {
         }
     }
 
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+
+    fn idempotent(&self) -> bool {
+        // only upper bounds are updated and only lower bounds are read, unless a variable appears twice in the sum
+        self.elements.iter().map(|e| e.var.variable()).all_unique()
+    }
+
     fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
         if domains.entails(self.active) {
             // constraint is active, propagate
@@ -378,8 +387,8 @@ mod tests {
     /* =============================== Helpers ============================== */
 
     fn check_bounds(e: &SumElem, d: &Domains, lb: IntCst, ub: IntCst) {
-        assert_eq!(e.get_lower_bound(d), lb.into());
-        assert_eq!(e.get_upper_bound(d), ub.into());
+        assert_eq!(e.get_lower_bound(d), lb as i64);
+        assert_eq!(e.get_upper_bound(d), ub as i64);
     }
 
     fn check_bounds_var(v: VarRef, d: &Domains, lb: IntCst, ub: IntCst) {
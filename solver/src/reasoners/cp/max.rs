@@ -17,15 +17,20 @@ impl MaxElem {
 }
 
 /// Propagator for a constraint that enforces that at least on element from the RHS is present and greater than or
-/// equal to the element at the LHS. The scope of the propagator is the presence of the LHS.
+/// equal to the element at the LHS. The scope of the propagator is either the presence of the LHS or a literal
+/// reifying the constraint.
 ///
-/// Constraint:  `prez(lhs)   =>    OR_i  prez(rhs[i]) & (rhs[i] >= lhs)`
+/// Constraint:  `scope   =>    OR_i  prez(rhs[i]) & (rhs[i] >= lhs)`
 /// Assumes that:   `forall i , prez(rhs[i]) => prez(lhs)`  i.e.  RHS elements are in the (sub?)scope of LHS
+///            and  `scope => prez(lhs)`
+///
+/// When the scope is a reification literal, the bounds of the LHS and RHS are only updated once the literal is true,
+/// and the literal is made false when no element of the RHS can be greater than or equal to the LHS.
 ///
 /// This is not sufficient to implement a propagator of the Max constraint and is only used as of several propagators in a decomposition.
 #[derive(Clone)]
 pub(crate) struct AtLeastOneGeq {
-    /// presence of LHS and scope of the constraint
+    /// scope of the constraint, either the presence of the LHS or a literal that reifies the constraint
     pub scope: Lit,
     pub lhs: SignedVar,
     pub elements: Vec<MaxElem>,
@@ -41,6 +46,10 @@ impl Propagator for AtLeastOneGeq {
         }
     }
 
+    fn name(&self) -> &'static str {
+        "max"
+    }
+
     fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
         if domains.entails(!self.scope) {
             return Ok(()); // inactive, skip propagation
@@ -74,11 +83,13 @@ impl Propagator for AtLeastOneGeq {
             }
         }
 
+        // if the scope is only a reification of the constraint, the bounds can only be updated once it is known to hold
+        let enforced = self.scope == domains.presence(self.lhs) || domains.entails(self.scope);
         match candidates {
             Candidates::Empty => {
-                let max_presence = domains.presence(self.lhs.variable());
-                domains.set(!max_presence, cause)?; // PROP 1
+                domains.set(!self.scope, cause)?; // PROP 1
             }
+            _ if !enforced => return Ok(()),
             _ => {
                 domains.set_ub(self.lhs, rhs_max, cause)?; // PROP 2
             }
@@ -88,7 +99,7 @@ impl Propagator for AtLeastOneGeq {
             // lb(elem.var + elem.cst) <- lb(lhs)
             // lb(elem.var) <- lb(lhs) - elem.cst
             domains.set_lb(elem.var, domains.lb(self.lhs) - elem.cst, cause)?; // PROP 3
-            if domains.entails(domains.presence(self.lhs)) {
+            if domains.entails(self.scope) && domains.entails(domains.presence(self.lhs)) {
                 // if the constraint is active, the elem must be present
                 domains.set(elem.presence, cause)?; // PROP 4
             }
@@ -98,7 +109,8 @@ impl Propagator for AtLeastOneGeq {
     }
 
     fn explain(&self, literal: Lit, domains: &DomainsSnapshot, out_explanation: &mut Explanation) {
-        debug_assert_eq!(self.scope, domains.presence(self.lhs.variable()));
+        // literals that made the propagation of bounds possible, if the scope is not the presence of the lhs
+        let reification = Some(self.scope).filter(|&scope| scope != domains.presence(self.lhs));
         if literal == !self.scope {
             // PROP 1
             let max_lb = domains.lb(self.lhs);
@@ -132,6 +144,7 @@ impl Propagator for AtLeastOneGeq {
                     out_explanation.push(lit);
                 }
             }
+            out_explanation.extend(reification);
         } else {
             // PROP 3 or 4, find the element that was propagated
             let (idx, elem) = self
@@ -159,12 +172,16 @@ impl Propagator for AtLeastOneGeq {
             }
             if literal == elem.presence {
                 // PROP 4
-                out_explanation.push(self.scope) // TODO: should this always be there as the scope ?
+                out_explanation.push(self.scope); // TODO: should this always be there as the scope ?
+                if reification.is_some() {
+                    out_explanation.push(domains.presence(self.lhs));
+                }
             } else {
                 // PROP 3
                 let inferrable = Lit::geq(elem.var, max_lb - elem.cst);
                 debug_assert!(inferrable.entails(literal));
                 out_explanation.push(Lit::geq(self.lhs, max_lb));
+                out_explanation.extend(reification);
             }
         }
     }
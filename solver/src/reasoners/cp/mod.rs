@@ -1,8 +1,10 @@
 #![allow(unused)] // TODO: remove once stabilized
 
+pub mod circuit;
 pub mod linear;
 pub mod max;
 pub mod mul;
+pub mod regular;
 
 use crate::backtrack::{Backtrack, DecLvl, ObsTrailCursor};
 use crate::collections::ref_store::{RefMap, RefVec};
@@ -14,23 +16,64 @@ use crate::core::state::{
 use crate::core::{IntCst, Lit, SignedVar, VarRef, INT_CST_MAX, INT_CST_MIN};
 use crate::create_ref_type;
 use crate::model::extensions::AssignmentExt;
+use crate::model::lang::circuit::NFCircuit;
 use crate::model::lang::linear::NFLinearLeq;
 use crate::model::lang::mul::NFEqVarMulLit;
+use crate::model::lang::regular::NFRegular;
+use crate::reasoners::cp::circuit::CircuitPropagator;
 use crate::reasoners::cp::linear::{LinearSumLeq, SumElem};
 use crate::reasoners::cp::max::AtLeastOneGeq;
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::cp::regular::RegularPropagator;
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
+use crate::utils::cpu_time::{CycleCount, StartCycleCount};
 use anyhow::Context;
+use itertools::Itertools;
 use mul::VarEqVarMulLit;
 use set::IterableRefSet;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 // ========== Constraint ===========
 
 create_ref_type!(PropagatorId);
 
+/// Cost class of a propagator.
+/// When several propagators are scheduled, the ones of the cheapest class are run first, so that all cheap propagators
+/// reach a fixpoint before any expensive one is run.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Priority {
+    /// Propagators running in constant time (e.g. over a fixed number of variables).
+    Cheap,
+    /// Propagators whose cost is linear in the number of variables (e.g. linear sums).
+    Linear,
+    /// Propagators with a super-linear cost (e.g. reasoning on a graph).
+    Expensive,
+}
+
+impl Priority {
+    const NUM_CLASSES: usize = 3;
+}
+
 trait Propagator: Send {
+    /// Registers the events on which the propagator should be woken up.
+    /// The propagator should only watch the bounds whose update may trigger an inference (e.g. only the lower bounds
+    /// of the variables of a `sum <= ub` constraint).
     fn setup(&self, id: PropagatorId, context: &mut Watches);
+
+    /// Name of the kind of the propagator, used to aggregate its profiling counters.
+    fn name(&self) -> &'static str;
+
+    /// Cost class of the propagator.
+    fn priority(&self) -> Priority {
+        Priority::Linear
+    }
+
+    /// Returns true if the propagator is idempotent, i.e., if running it twice in a row never infers anything new.
+    /// An idempotent propagator is not woken up by its own inferences.
+    fn idempotent(&self) -> bool {
+        false
+    }
+
     fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction>;
     fn propagate_event(&self, _event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
         self.propagate(domains, cause)
@@ -49,12 +92,21 @@ impl<T: Propagator> Explainer for T {
 
 pub struct DynPropagator {
     constraint: Box<dyn Propagator>,
+    /// Cached properties of the propagator
+    priority: Priority,
+    idempotent: bool,
+    /// Index of the profiling counters of the propagator's kind in `Stats::per_propagator`.
+    /// Only meaningful once the propagator has been added to the reasoner.
+    kind: usize,
 }
 
 impl Clone for DynPropagator {
     fn clone(&self) -> Self {
         DynPropagator {
             constraint: self.constraint.clone_box(),
+            priority: self.priority,
+            idempotent: self.idempotent,
+            kind: self.kind,
         }
     }
 }
@@ -62,6 +114,9 @@ impl Clone for DynPropagator {
 impl<T: Propagator + 'static> From<T> for DynPropagator {
     fn from(propagator: T) -> Self {
         DynPropagator {
+            priority: propagator.priority(),
+            idempotent: propagator.idempotent(),
+            kind: 0,
             constraint: Box::new(propagator),
         }
     }
@@ -93,6 +148,11 @@ impl Watches {
         self.add_ub_watch(-watched, propagator_id)
     }
 
+    /// Wakes up the propagator when the literal becomes true (e.g. when a presence literal is set).
+    fn add_lit_watch(&mut self, watched: Lit, propagator_id: PropagatorId) {
+        self.add_ub_watch(watched.svar(), propagator_id)
+    }
+
     fn get_ub_watches(&self, var: impl Into<SignedVar>) -> &[PropagatorId] {
         let var = var.into();
         self.propagations.get(var).map(|v| v.as_slice()).unwrap_or(&self.empty)
@@ -102,15 +162,58 @@ impl Watches {
 #[derive(Clone)]
 pub struct Stats {
     pub num_propagations: u64,
+    /// Profiling counters of each kind of propagator, in the order in which the kinds were first added.
+    pub per_propagator: Vec<PropagatorStats>,
+}
+
+impl Stats {
+    /// Returns the index of the profiling counters of the given kind of propagator, creating them if needed.
+    fn kind(&mut self, name: &'static str) -> usize {
+        match self.per_propagator.iter().position(|s| s.name == name) {
+            Some(kind) => kind,
+            None => {
+                self.per_propagator.push(PropagatorStats {
+                    name,
+                    ..Default::default()
+                });
+                self.per_propagator.len() - 1
+            }
+        }
+    }
+
+    /// Profiling counters of the given kind of propagator, if any propagator of this kind was added.
+    pub fn of(&self, name: &str) -> Option<&PropagatorStats> {
+        self.per_propagator.iter().find(|s| s.name == name)
+    }
 }
 
 #[allow(clippy::derivable_impls)]
 impl Default for Stats {
     fn default() -> Self {
-        Self { num_propagations: 0 }
+        Self {
+            num_propagations: 0,
+            per_propagator: Default::default(),
+        }
     }
 }
 
+/// Profiling counters of a kind of propagator.
+#[derive(Clone, Default)]
+pub struct PropagatorStats {
+    /// Name of the kind of propagator.
+    pub name: &'static str,
+    /// Number of times a propagator of this kind was run.
+    pub runs: u64,
+    /// Number of runs that inferred at least one new bound.
+    pub useful_runs: u64,
+    /// Number of runs that detected a contradiction.
+    pub conflicts: u64,
+    /// Number of bound updates inferred.
+    pub inferences: u64,
+    /// CPU cycles spent in the propagators of this kind.
+    pub cycles: CycleCount,
+}
+
 #[derive(Clone)]
 pub struct Cp {
     id: ReasonerId,
@@ -120,9 +223,11 @@ pub struct Cp {
     saved: DecLvl,
     /// Propagators that have never been propagated to this point
     pending_propagators: Vec<PropagatorId>,
-    /// Datastructure used in `propagate` to keep track of which propagators should be triggered.
-    /// Not stateful. Present here only to avoid reallocations
-    pending_propagations: IterableRefSet<PropagatorId>,
+    /// Propagators that are scheduled for propagation, with one queue per priority class.
+    /// Not stateful (always empty outside of `propagate`). Present here only to avoid reallocations
+    queues: [VecDeque<PropagatorId>; Priority::NUM_CLASSES],
+    /// Propagators that are in one of the queues
+    scheduled: RefSet<PropagatorId>,
     pub stats: Stats,
 }
 
@@ -135,7 +240,8 @@ impl Cp {
             watches: Default::default(),
             saved: DecLvl::ROOT,
             pending_propagators: Default::default(),
-            pending_propagations: Default::default(),
+            queues: Default::default(),
+            scheduled: Default::default(),
             stats: Default::default(),
         }
     }
@@ -164,15 +270,63 @@ impl Cp {
         self.add_propagator(propagator);
     }
 
+    pub fn add_circuit_constraint(&mut self, circuit: &NFCircuit) {
+        self.add_propagator(CircuitPropagator::new(circuit));
+    }
+
+    /// Adds a regular constraint that is only active when `active` is true.
+    pub fn add_regular_constraint(&mut self, regular: &NFRegular, active: Lit) {
+        self.add_propagator(RegularPropagator::new(regular, active));
+    }
+
     pub fn add_propagator(&mut self, propagator: impl Into<DynPropagator>) {
         // TODO: handle validity scopes
-        let propagator = propagator.into();
+        let mut propagator = propagator.into();
+        propagator.kind = self.stats.kind(propagator.constraint.name());
         let propagator_id = self.constraints.next_key();
         propagator.constraint.setup(propagator_id, &mut self.watches);
         self.constraints.set_next(propagator_id, propagator);
         // mark the constraint as pending for propagation
         self.pending_propagators.push(propagator_id);
     }
+
+    /// Adds the propagator to the queue of its priority class, unless it is already scheduled.
+    fn schedule(&mut self, propagator: PropagatorId) {
+        if !self.scheduled.contains(propagator) {
+            self.scheduled.insert(propagator);
+            let priority = self.constraints[propagator].priority;
+            self.queues[priority as usize].push_back(propagator);
+        }
+    }
+
+    /// Schedules all propagators watching a bound updated since the last call.
+    /// If `skip` is set, the given propagator is not woken up by those events (e.g. for an idempotent propagator
+    /// that produced them).
+    fn schedule_watchers(&mut self, domains: &Domains, skip: Option<PropagatorId>) {
+        while let Some(event) = self.model_events.pop(domains.trail()) {
+            for i in 0..self.watches.get_ub_watches(event.affected_bound).len() {
+                let watcher = self.watches.get_ub_watches(event.affected_bound)[i];
+                if Some(watcher) != skip {
+                    self.schedule(watcher);
+                }
+            }
+        }
+    }
+
+    /// Removes the next propagator from the non-empty queue of the cheapest priority class.
+    fn next_scheduled(&mut self) -> Option<PropagatorId> {
+        let propagator = self.queues.iter_mut().find_map(|q| q.pop_front())?;
+        self.scheduled.remove(propagator);
+        Some(propagator)
+    }
+
+    fn clear_scheduled(&mut self) {
+        for q in &mut self.queues {
+            for propagator in q.drain(..) {
+                self.scheduled.remove(propagator);
+            }
+        }
+    }
 }
 
 impl Theory for Cp {
@@ -181,33 +335,52 @@ impl Theory for Cp {
     }
 
     fn propagate(&mut self, domains: &mut Domains) -> Result<(), Contradiction> {
-        // clean up
-        self.pending_propagations.clear();
+        debug_assert!(self.queues.iter().all(|q| q.is_empty()));
 
         // schedule propagators that have never been triggered
-        for propagator in self.pending_propagators.drain(..) {
+        for propagator in std::mem::take(&mut self.pending_propagators) {
             debug_assert_eq!(
                 domains.current_decision_level(),
                 DecLvl::ROOT,
                 "First propagation should occur at root."
             );
-            self.pending_propagations.insert(propagator)
+            self.schedule(propagator)
         }
 
         // add any propagator that watches a bound updated since last propagation
-        while let Some(event) = self.model_events.pop(domains.trail()) {
-            let watchers = self.watches.get_ub_watches(event.affected_bound);
-            for &watcher in watchers {
-                // note: this could be improved as we may be rescheduling the propagator that triggered the event
-                self.pending_propagations.insert(watcher);
-            }
-        }
-
-        for propagator in self.pending_propagations.iter() {
-            let constraint = self.constraints[propagator].constraint.as_ref();
+        self.schedule_watchers(domains, None);
+
+        // run the propagators until reaching a fixpoint, always selecting one of the cheapest class
+        while let Some(propagator) = self.next_scheduled() {
+            let DynPropagator {
+                constraint,
+                idempotent,
+                kind,
+                ..
+            } = &self.constraints[propagator];
             let cause = self.id.cause(propagator);
+            let num_events = domains.trail().len();
+            let start = StartCycleCount::now();
+            let result = constraint.propagate(domains, cause);
+
             self.stats.num_propagations += 1;
-            constraint.propagate(domains, cause)?;
+            let stats = &mut self.stats.per_propagator[*kind];
+            stats.runs += 1;
+            stats.cycles += start.elapsed();
+            let num_inferences = (domains.trail().len() - num_events) as u64;
+            stats.inferences += num_inferences;
+            if num_inferences > 0 {
+                stats.useful_runs += 1;
+            }
+            if result.is_err() {
+                stats.conflicts += 1;
+                self.clear_scheduled();
+                return result;
+            }
+
+            // wake up the propagators watching the bounds updated by this propagator
+            let skip = idempotent.then_some(propagator);
+            self.schedule_watchers(domains, skip);
         }
 
         Ok(())
@@ -228,6 +401,29 @@ impl Theory for Cp {
     fn print_stats(&self) {
         println!("# constraints: {}", self.constraints.len());
         println!("# propagations: {}", self.stats.num_propagations);
+        for s in self.stats.per_propagator.iter().sorted_by_key(|s| s.name) {
+            println!(
+                "  {:<10} runs: {:<10} useful: {:<10} conflicts: {:<8} inferences: {:<10} cycles: {}",
+                s.name, s.runs, s.useful_runs, s.conflicts, s.inferences, s.cycles
+            );
+        }
+    }
+
+    fn stats(&self) -> TheoryStats {
+        let mut stats = TheoryStats::new();
+        stats.insert("constraints".to_string(), self.constraints.len() as u64);
+        stats.insert("propagations".to_string(), self.stats.num_propagations);
+        for s in &self.stats.per_propagator {
+            let name = s.name;
+            stats.insert(format!("{name}.runs"), s.runs);
+            stats.insert(format!("{name}.useful_runs"), s.useful_runs);
+            stats.insert(format!("{name}.conflicts"), s.conflicts);
+            stats.insert(format!("{name}.inferences"), s.inferences);
+            if let Some(cycles) = s.cycles.count() {
+                stats.insert(format!("{name}.cycles"), cycles);
+            }
+        }
+        stats
     }
 
     fn clone_box(&self) -> Box<dyn Theory> {
@@ -249,3 +445,92 @@ impl Backtrack for Cp {
         self.saved -= 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A propagator that enforces `y >= x + 1` and records when it is run.
+    #[derive(Clone)]
+    struct Successor {
+        x: VarRef,
+        y: VarRef,
+        priority: Priority,
+        log: Arc<Mutex<Vec<&'static str>>>,
+        name: &'static str,
+    }
+
+    impl Propagator for Successor {
+        fn setup(&self, id: PropagatorId, context: &mut Watches) {
+            context.add_lb_watch(self.x, id);
+            context.add_lb_watch(self.y, id);
+        }
+        fn name(&self) -> &'static str {
+            self.name
+        }
+        fn priority(&self) -> Priority {
+            self.priority
+        }
+        fn idempotent(&self) -> bool {
+            true
+        }
+        fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+            self.log.lock().unwrap().push(self.name);
+            domains.set_lb(self.y, domains.lb(self.x) + 1, cause)?;
+            Ok(())
+        }
+        fn explain(&self, _literal: Lit, _state: &DomainsSnapshot, out_explanation: &mut Explanation) {
+            out_explanation.push(self.x.geq(0));
+        }
+        fn clone_box(&self) -> Box<dyn Propagator> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_scheduling() {
+        let mut domains = Domains::new();
+        let vars: Vec<VarRef> = (0..4).map(|_| domains.new_var(0, 10)).collect();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut cp = Cp::new(ReasonerId::Cp);
+        let mut add = |x: usize, name: &'static str, priority: Priority| {
+            cp.add_propagator(Successor {
+                x: vars[x],
+                y: vars[x + 1],
+                priority,
+                log: log.clone(),
+                name,
+            })
+        };
+        // chain  v0 < v1 < v2 < v3, where the expensive propagator is added first
+        add(2, "expensive", Priority::Expensive);
+        add(0, "cheap", Priority::Cheap);
+        add(1, "linear", Priority::Linear);
+
+        cp.propagate(&mut domains).unwrap();
+        assert_eq!(domains.lb(vars[3]), 3);
+        // the expensive propagator is only run once the others reached a fixpoint,
+        // and idempotent propagators are not woken up by their own inferences
+        assert_eq!(*log.lock().unwrap(), vec!["cheap", "linear", "expensive"]);
+        assert_eq!(cp.stats.num_propagations, 3);
+        assert_eq!(cp.stats.of("cheap").unwrap().inferences, 1);
+        assert_eq!(cp.stats.of("expensive").unwrap().useful_runs, 1);
+
+        // an update of v0 wakes up the cheap propagator only, which triggers the others
+        log.lock().unwrap().clear();
+        cp.save_state();
+        domains.save_state();
+        domains.set_lb(vars[0], 5, Cause::Decision).unwrap();
+        cp.propagate(&mut domains).unwrap();
+        assert_eq!(domains.lb(vars[3]), 8);
+        assert_eq!(*log.lock().unwrap(), vec!["cheap", "linear", "expensive"]);
+
+        // contradiction: v3 would exceed its upper bound
+        domains.save_state();
+        domains.set_lb(vars[0], 8, Cause::Decision).unwrap();
+        assert!(cp.propagate(&mut domains).is_err());
+        assert_eq!(cp.stats.of("expensive").unwrap().conflicts, 1);
+        assert!(cp.queues.iter().all(|q| q.is_empty()));
+    }
+}
@@ -33,6 +33,14 @@ impl Propagator for VarEqVarMulLit {
         context.add_watch(self.lit.variable(), id);
     }
 
+    fn name(&self) -> &'static str {
+        "mul"
+    }
+
+    fn priority(&self) -> super::Priority {
+        super::Priority::Cheap
+    }
+
     fn propagate(
         &self,
         domains: &mut crate::core::state::Domains,
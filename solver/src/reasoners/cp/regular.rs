@@ -0,0 +1,197 @@
+use crate::core::state::{Cause, Domains, DomainsSnapshot, Explanation};
+use crate::core::{IntCst, Lit};
+use crate::model::lang::regular::{Dfa, NFRegular};
+use crate::reasoners::cp::{Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Propagator for the `Regular` constraint, reasoning on the layered graph of the automaton unrolled over the variables.
+///
+/// Layer `i` of the graph contains the states of the automaton that may be reached after reading the `i` first
+/// symbols, and there is an edge `(q, v, q')` between layers `i` and `i+1` if the `i`-th variable may take the value `v`
+/// and the automaton goes from `q` to `q'` on `v`.
+/// A value is removed from the domain of a variable if none of its edges lies on a path from the initial state
+/// (layer `0`) to an accepting state (layer `n`).
+///
+/// It assumes that the fact that each variable takes one of the values of the automaton is handled by clauses.
+/// The propagator is only active when its `active` literal (the scope of the constraint) is true.
+#[derive(Clone)]
+pub(crate) struct RegularPropagator {
+    values: Vec<Vec<(IntCst, Lit)>>,
+    dfa: Dfa,
+    active: Lit,
+}
+
+/// For each layer of the graph, the states that are reachable from the initial state (`forward`) and
+/// the states from which an accepting state can be reached (`backward`).
+struct Layers {
+    forward: Vec<Vec<bool>>,
+    backward: Vec<Vec<bool>>,
+}
+
+impl RegularPropagator {
+    pub fn new(regular: &NFRegular, active: Lit) -> Self {
+        RegularPropagator {
+            values: regular.values.clone(),
+            dfa: regular.dfa.clone(),
+            active,
+        }
+    }
+
+    fn layers(&self, possible: impl Fn(Lit) -> bool) -> Layers {
+        let n = self.values.len();
+        let num_states = self.dfa.num_states();
+        let mut forward = vec![vec![false; num_states]; n + 1];
+        forward[0][self.dfa.initial()] = true;
+        for (i, values) in self.values.iter().enumerate() {
+            for q in 0..num_states {
+                if !forward[i][q] {
+                    continue;
+                }
+                for &(v, lit) in values {
+                    if let Some(next) = self.dfa.next(q, v).filter(|_| possible(lit)) {
+                        forward[i + 1][next] = true;
+                    }
+                }
+            }
+        }
+        let mut backward = vec![vec![false; num_states]; n + 1];
+        backward[n] = (0..num_states).map(|q| self.dfa.is_accepting(q)).collect();
+        for (i, values) in self.values.iter().enumerate().rev() {
+            for q in 0..num_states {
+                backward[i][q] = values
+                    .iter()
+                    .any(|&(v, lit)| possible(lit) && self.dfa.next(q, v).is_some_and(|next| backward[i + 1][next]));
+            }
+        }
+        Layers { forward, backward }
+    }
+
+    /// Returns true if an edge for the value `v` at position `i` lies on an accepting path.
+    fn supported(&self, layers: &Layers, i: usize, v: IntCst) -> bool {
+        (0..self.dfa.num_states())
+            .any(|q| layers.forward[i][q] && self.dfa.next(q, v).is_some_and(|next| layers.backward[i + 1][next]))
+    }
+
+    /// Explains why the value `v` is not supported at position `i`.
+    ///
+    /// Each edge `(q, v, q')` from layer `i` is blocked either because `q` is unreachable or because `q'` cannot reach
+    /// an accepting state. Unreachability is explained by following the edges backward: an edge entering an unreachable
+    /// state is either removed (its literal is part of the explanation) or comes from an unreachable state.
+    /// Symmetrically, a dead state is explained by following the edges forward.
+    fn explain_unsupported(&self, layers: &Layers, i: usize, v: IntCst, out_explanation: &mut Explanation) {
+        let n = self.values.len();
+        let num_states = self.dfa.num_states();
+        let mut unreachable = vec![false; num_states];
+        let mut dead = vec![false; num_states];
+        for (q, reachable) in layers.forward[i].iter().enumerate() {
+            if let Some(next) = self.dfa.next(q, v) {
+                if !reachable {
+                    unreachable[q] = true;
+                } else {
+                    debug_assert!(!layers.backward[i + 1][next]);
+                    dead[next] = true;
+                }
+            }
+        }
+
+        // unreachable states, from layer `i` to the initial layer
+        for j in (0..i).rev() {
+            let mut previous = vec![false; num_states];
+            for &(w, lit) in &self.values[j] {
+                let mut removed = false;
+                for (q, &reachable) in layers.forward[j].iter().enumerate() {
+                    if self.dfa.next(q, w).is_some_and(|next| unreachable[next]) {
+                        if reachable {
+                            removed = true;
+                        } else {
+                            previous[q] = true;
+                        }
+                    }
+                }
+                if removed {
+                    out_explanation.push(!lit);
+                }
+            }
+            unreachable = previous;
+        }
+        debug_assert!(!unreachable[self.dfa.initial()]);
+
+        // dead states, from layer `i+1` to the final layer
+        for j in i + 1..n {
+            let mut following = vec![false; num_states];
+            for &(w, lit) in &self.values[j] {
+                let mut removed = false;
+                for q in (0..num_states).filter(|&q| dead[q]) {
+                    if let Some(next) = self.dfa.next(q, w) {
+                        if layers.backward[j + 1][next] {
+                            removed = true;
+                        } else {
+                            following[next] = true;
+                        }
+                    }
+                }
+                if removed {
+                    out_explanation.push(!lit);
+                }
+            }
+            dead = following;
+        }
+        debug_assert!((0..num_states).all(|q| !dead[q] || !self.dfa.is_accepting(q)));
+    }
+}
+
+impl Propagator for RegularPropagator {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_lit_watch(self.active, id);
+        // only the removal of a value may make other values unsupported
+        for &(_, lit) in self.values.iter().flatten() {
+            context.add_lit_watch(!lit, id);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "regular"
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(()); // inactive, skip propagation
+        }
+        let layers = self.layers(|l| !domains.entails(!l));
+        for (i, values) in self.values.iter().enumerate() {
+            for &(v, lit) in values {
+                if !domains.entails(!lit) && !self.supported(&layers, i, v) {
+                    domains.set(!lit, cause)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, state: &DomainsSnapshot, out_explanation: &mut Explanation) {
+        // Note: the literals of several values may be on the same variable, so the literal may be entailed by several of them.
+        // We thus look for any removal that would have entailed the literal.
+        if self.active != Lit::TRUE {
+            // explanation is always conditioned by the activity of the propagator
+            out_explanation.push(self.active);
+        }
+        let layers = self.layers(|l| !state.entails(!l));
+        for (i, values) in self.values.iter().enumerate() {
+            for &(v, lit) in values {
+                if (!lit).entails(literal) && !self.supported(&layers, i, v) {
+                    self.explain_unsupported(&layers, i, v, out_explanation);
+                    return;
+                }
+            }
+        }
+        panic!("Unexpected literal to explain")
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
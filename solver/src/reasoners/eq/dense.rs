@@ -4,7 +4,7 @@ use crate::core::state::{Domains, DomainsSnapshot, Explanation, InvalidUpdate};
 use crate::core::{IntCst, Lit, SignedVar, VarRef, INT_CST_MIN};
 use crate::model::{Label, Model};
 use crate::reasoners::eq::domain;
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
 use crate::reif::ReifExpr;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
@@ -209,6 +209,27 @@ impl Debug for Stats {
     }
 }
 
+impl Stats {
+    pub(crate) fn counters(&self) -> TheoryStats {
+        let counters = [
+            ("edge_propagations", self.num_edge_propagations),
+            ("edge_propagations_pos", self.num_edge_propagations_pos),
+            ("edge_propagations_neg", self.num_edge_propagations_neg),
+            ("edge_propagations1_pos_pos", self.num_edge_propagation1_pos_pos),
+            ("edge_propagations1_pos_neg", self.num_edge_propagation1_pos_neg),
+            ("edge_propagations1_neg_pos", self.num_edge_propagation1_neg_pos),
+            ("edge_propagations1_effective", self.num_edge_propagation1_effective),
+            ("edge_propagations2_pos_pos", self.num_edge_propagation2_pos_pos),
+            ("edge_propagations2_pos_neg", self.num_edge_propagation2_pos_neg),
+            ("edge_propagations2_neg_pos", self.num_edge_propagation2_neg_pos),
+        ];
+        counters
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value as u64))
+            .collect()
+    }
+}
+
 impl std::ops::AddAssign for Stats {
     fn add_assign(&mut self, rhs: Self) {
         self.num_edge_propagations += rhs.num_edge_propagations;
@@ -283,8 +304,7 @@ impl DenseEqTheory {
             .labels
             .insert(DirEdgeId { src, tgt }, DirEdgeLabel { label, active });
         if let (Node::Var(var), Node::Val(val)) = (src, tgt) {
-            let (lb, ub) = model.domain(Node::Var(var));
-            if (lb..=ub).contains(&val) {
+            if model.domains().contains(var, val) {
                 self.graph.domains.add_value(var, val, label);
             }
         }
@@ -298,9 +318,10 @@ impl DenseEqTheory {
         if self.graph.nodes.contains(&v) {
             return;
         }
-        if let Node::Var(_) = v {
-            let (lb, ub) = model.domain(v);
-            for val in lb..=ub {
+        if let Node::Var(var) = v {
+            // the holes of a sparse domain are skipped
+            let values = model.domains().values(var).collect_vec();
+            for val in values {
                 self.add_node(val, model);
             }
         }
@@ -870,6 +891,12 @@ impl Theory for DenseEqTheory {
         println!("num edge props2 -+  {}", self.stats.num_edge_propagation2_neg_pos);
     }
 
+    fn stats(&self) -> TheoryStats {
+        let mut stats = self.stats.counters();
+        stats.insert("nodes".to_string(), self.graph.nodes_ordered.len() as u64);
+        stats
+    }
+
     fn clone_box(&self) -> Box<dyn Theory> {
         Box::new(self.clone())
     }
@@ -2,7 +2,7 @@ use crate::backtrack::{Backtrack, DecLvl, DecisionLevelTracker};
 use crate::core::state::{Domains, DomainsSnapshot, Explanation, InferenceCause};
 use crate::core::{IntCst, Lit, VarRef};
 use crate::reasoners::eq::{DenseEqTheory, Node, ReifyEq};
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -183,6 +183,16 @@ impl Theory for SplitEqTheory {
         println!("{stats:?}");
     }
 
+    fn stats(&self) -> TheoryStats {
+        let mut stats = crate::reasoners::eq::dense::Stats::default();
+        for part in self.parts() {
+            stats += part.stats.clone();
+        }
+        let mut stats = stats.counters();
+        stats.insert("parts".to_string(), self.parts().count() as u64);
+        stats
+    }
+
     fn clone_box(&self) -> Box<dyn Theory> {
         Box::new(self.clone())
     }
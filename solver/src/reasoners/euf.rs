@@ -0,0 +1,766 @@
+//! A reasoner for equality with uninterpreted functions (EUF), based on congruence closure.
+//!
+//! The reasoner maintains a partition of nodes into equivalence classes, where each node is either a variable or a
+//! constant value ([`Node`]). Two nodes are merged when:
+//!
+//!  - a variable is bound to a value `v`: its node is merged with the one of `v`,
+//!  - an equality `a = b` between two present variables is entailed (through an equality literal or the literals of
+//!    both `a <= b` and `b <= a`): the nodes of `a` and `b` are merged,
+//!  - two active applications of the same function have congruent arguments: their results are merged.
+//!
+//! Congruences are detected incrementally: each node has a *use-list* of the applications in which it appears,
+//! and a merge only re-hashes the applications that use a node of the smaller class into a table of signatures
+//! (the function and the classes of the arguments). The table is restored on backtracking.
+//!
+//! In return, the reasoner restricts the domain of each variable to the intersection of the domains in its class
+//! (a class with a constant value thus binds all its variables). A class with two different constants is a conflict.
+//!
+//! All merges are recorded in a *proof forest* whose edges are labeled with the reason of the merge.
+//! Two nodes of the same class are connected by a single path in the forest, which is used to explain their equality
+//! (the explanation of a congruence being the recursive explanation of the equality of the arguments),
+//! following *Nieuwenhuis and Oliveras, "Proof-producing Congruence Closure", RTA 2005*.
+//!
+//! The union-find does not use path compression so that merges can be undone on backtracking.
+//! Explanations are computed eagerly when inferring a new bound and kept until backtracking.
+
+use crate::backtrack::{Backtrack, DecLvl, ObsTrailCursor, Trail};
+use crate::core::state::{Domains, DomainsSnapshot, Event as DomainEvent, Explanation, InferenceCause};
+use crate::core::{IntCst, Lit, VarRef, INT_CST_MAX, INT_CST_MIN};
+use crate::model::lang::euf::FunId;
+use crate::reasoners::eq::Node;
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+type NodeId = usize;
+type AppId = usize;
+type EqId = usize;
+
+/// A function and the representatives of the classes of the arguments of an application.
+type Signature = (FunId, Vec<NodeId>);
+
+/// An application `fun(args) = result`, that is considered only when `active` is true.
+#[derive(Clone, Debug)]
+struct App {
+    fun: FunId,
+    args: Vec<NodeId>,
+    result: NodeId,
+    active: Lit,
+}
+
+/// An equality `a = b` between two variables, that holds when both literals are true.
+#[derive(Copy, Clone, Debug)]
+struct Equality {
+    a: VarRef,
+    b: VarRef,
+    lits: [Lit; 2],
+}
+
+/// Reason for merging two nodes, labeling an edge of the proof forest.
+#[derive(Copy, Clone, Debug)]
+enum Reason {
+    /// The variable is present and bound to the value
+    Bound(VarRef, IntCst),
+    /// The literals of the equality are entailed and both variables are present
+    Equality(EqId),
+    /// The results of two applications with congruent arguments
+    Congruence(AppId, AppId),
+}
+
+#[derive(Clone, Debug)]
+enum Event {
+    /// The `num_moved` nodes of the class of `from` (a root) were appended to the one of `into`,
+    /// through a new edge `(a, b)` of the proof forest.
+    Merge {
+        into: NodeId,
+        from: NodeId,
+        num_moved: usize,
+        a: NodeId,
+        b: NodeId,
+        previous_value: Option<NodeId>,
+    },
+    /// An explanation was recorded for an inference.
+    Explanation,
+    /// The signature was associated to a new application, replacing the previous one.
+    Signature {
+        signature: Signature,
+        previous: Option<AppId>,
+    },
+}
+
+#[derive(Clone, Default)]
+struct EufStats {
+    merges: u64,
+    congruences: u64,
+    propagations: u64,
+    conflicts: u64,
+}
+
+#[derive(Clone)]
+pub struct Euf {
+    nodes: Vec<Node>,
+    node_ids: HashMap<Node, NodeId>,
+    /// Representative of the class of each node
+    root: Vec<NodeId>,
+    /// For a representative, all nodes of its class
+    members: Vec<Vec<NodeId>>,
+    /// For a representative, the node of the constant in its class (if any)
+    value: Vec<Option<NodeId>>,
+    /// Edges of the proof forest, with the reason of each merge
+    proof: Vec<Vec<(NodeId, Reason)>>,
+    apps: Vec<App>,
+    /// Applications indexed by the variable of their activity literal
+    activations: HashMap<VarRef, Vec<AppId>>,
+    /// For each node, the applications in which it appears as an argument
+    uses: Vec<Vec<AppId>>,
+    /// Active applications indexed by their signature. An entry is only valid if the signature of its application
+    /// has not changed since it was inserted.
+    signatures: HashMap<Signature, AppId>,
+    /// Active applications whose signature may have changed since they were last hashed
+    rehash: Vec<AppId>,
+    equalities: Vec<Equality>,
+    /// Literals of the inequalities `a <= b`, indexed by `(a, b)`, to detect equalities posted as two inequalities
+    inequalities: HashMap<(VarRef, VarRef), Vec<Lit>>,
+    /// Equalities indexed by the variables of their literals and of the presence of their variables
+    equality_watches: HashMap<VarRef, Vec<EqId>>,
+    /// Applications and equalities added since the last propagation
+    new_apps: Vec<AppId>,
+    new_equalities: Vec<EqId>,
+    /// Explanations of the bounds inferred, indexed by the payload of the inference cause
+    explanations: Vec<Vec<Lit>>,
+    trail: Trail<Event>,
+    cursor: ObsTrailCursor<DomainEvent>,
+    /// Representatives of the classes whose bounds may need to be updated
+    dirty: Vec<NodeId>,
+    stats: EufStats,
+}
+
+impl Euf {
+    pub fn new() -> Euf {
+        Euf {
+            nodes: Default::default(),
+            node_ids: Default::default(),
+            root: Default::default(),
+            members: Default::default(),
+            value: Default::default(),
+            proof: Default::default(),
+            apps: Default::default(),
+            activations: Default::default(),
+            uses: Default::default(),
+            signatures: Default::default(),
+            rehash: Default::default(),
+            equalities: Default::default(),
+            inequalities: Default::default(),
+            equality_watches: Default::default(),
+            new_apps: Default::default(),
+            new_equalities: Default::default(),
+            explanations: Default::default(),
+            trail: Default::default(),
+            cursor: Default::default(),
+            dirty: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Adds the application `fun(args) = result`, that must hold whenever `active` is true.
+    ///
+    /// The activity literal must imply the presence of all variables.
+    pub fn add_application(&mut self, fun: FunId, args: &[VarRef], result: VarRef, active: Lit) {
+        let app = App {
+            fun,
+            args: args.iter().map(|&a| self.node(Node::Var(a))).collect(),
+            result: self.node(Node::Var(result)),
+            active,
+        };
+        let id = self.apps.len();
+        for &arg in &app.args {
+            self.uses[arg].push(id);
+        }
+        self.apps.push(app);
+        self.activations.entry(active.variable()).or_default().push(id);
+        self.new_apps.push(id);
+    }
+
+    /// Adds the equality `a = b`, that holds whenever `lit` is true and both variables are present.
+    pub fn add_equality(&mut self, a: VarRef, b: VarRef, lit: Lit) {
+        self.add_equality_lits(a, b, [lit, Lit::TRUE]);
+    }
+
+    /// Adds the inequality `a <= b`, that holds whenever `lit` is true and both variables are present.
+    ///
+    /// Only the equalities made of two converse inequalities are of interest to the reasoner.
+    pub fn add_leq(&mut self, a: VarRef, b: VarRef, lit: Lit) {
+        if a == b || a == VarRef::ZERO || b == VarRef::ZERO {
+            return;
+        }
+        let converses = self.inequalities.get(&(b, a)).cloned().unwrap_or_default();
+        for converse in converses {
+            self.add_equality_lits(a, b, [lit, converse]);
+        }
+        self.inequalities.entry((a, b)).or_default().push(lit);
+    }
+
+    fn add_equality_lits(&mut self, a: VarRef, b: VarRef, lits: [Lit; 2]) {
+        let id = self.equalities.len();
+        self.equalities.push(Equality { a, b, lits });
+        for lit in lits {
+            if lit != Lit::TRUE {
+                self.equality_watches.entry(lit.variable()).or_default().push(id);
+            }
+        }
+        self.new_equalities.push(id);
+    }
+
+    fn node(&mut self, node: Node) -> NodeId {
+        match self.node_ids.entry(node) {
+            Entry::Occupied(e) => *e.get(),
+            Entry::Vacant(e) => {
+                let id = self.nodes.len();
+                e.insert(id);
+                self.nodes.push(node);
+                self.root.push(id);
+                self.members.push(vec![id]);
+                self.value.push(matches!(node, Node::Val(_)).then_some(id));
+                self.proof.push(Vec::new());
+                self.uses.push(Vec::new());
+                id
+            }
+        }
+    }
+
+    /// Returns true if the two nodes are in the same class.
+    fn congruent(&self, a: NodeId, b: NodeId) -> bool {
+        self.root[a] == self.root[b]
+    }
+
+    fn merge(&mut self, a: NodeId, b: NodeId, reason: Reason, domains: &Domains) -> Result<(), Contradiction> {
+        let (ra, rb) = (self.root[a], self.root[b]);
+        if ra == rb {
+            return Ok(());
+        }
+        if let (Some(va), Some(vb)) = (self.value[ra], self.value[rb]) {
+            // two different values in the same class
+            self.stats.conflicts += 1;
+            let mut explanation = Vec::new();
+            self.explain_eq(a, va, domains, &mut explanation);
+            self.explain_reason(reason, domains, &mut explanation);
+            self.explain_eq(b, vb, domains, &mut explanation);
+            explanation.retain(|&l| l != Lit::TRUE);
+            return Err(Contradiction::Explanation(explanation.into()));
+        }
+        self.stats.merges += 1;
+        let (into, from) = if self.members[ra].len() >= self.members[rb].len() {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        let moved = std::mem::take(&mut self.members[from]);
+        let num_moved = moved.len();
+        for &m in &moved {
+            self.root[m] = into;
+            // the signature of the applications using the node changed
+            self.rehash.extend(self.uses[m].iter().copied());
+        }
+        self.members[into].extend(moved);
+        let previous_value = self.value[into];
+        self.value[into] = previous_value.or(self.value[from]);
+        self.proof[a].push((b, reason));
+        self.proof[b].push((a, reason));
+        self.trail.push(Event::Merge {
+            into,
+            from,
+            num_moved,
+            a,
+            b,
+            previous_value,
+        });
+        self.dirty.push(into);
+        Ok(())
+    }
+
+    fn undo_merge(&mut self, event: Event) {
+        let Event::Merge {
+            into,
+            from,
+            num_moved,
+            a,
+            b,
+            previous_value,
+        } = event
+        else {
+            return;
+        };
+        self.proof[a].pop().unwrap();
+        self.proof[b].pop().unwrap();
+        self.value[into] = previous_value;
+        // later merges were undone before, so the moved nodes are the last ones of the class
+        let num_kept = self.members[into].len() - num_moved;
+        let moved = self.members[into].split_off(num_kept);
+        for &m in &moved {
+            self.root[m] = from;
+        }
+        self.members[from] = moved;
+    }
+
+    /// Returns the reasons labeling the path between `a` and `b` in the proof forest.
+    fn path(&self, a: NodeId, b: NodeId) -> Vec<Reason> {
+        debug_assert!(self.congruent(a, b));
+        let mut predecessors: HashMap<NodeId, (NodeId, Reason)> = HashMap::new();
+        let mut queue = VecDeque::from([a]);
+        while let Some(n) = queue.pop_front() {
+            if n == b {
+                break;
+            }
+            for &(m, reason) in &self.proof[n] {
+                if m != a && !predecessors.contains_key(&m) {
+                    predecessors.insert(m, (n, reason));
+                    queue.push_back(m);
+                }
+            }
+        }
+        let mut reasons = Vec::new();
+        let mut cur = b;
+        while cur != a {
+            let (prev, reason) = predecessors[&cur];
+            reasons.push(reason);
+            cur = prev;
+        }
+        reasons
+    }
+
+    /// Adds to `out` a set of literals that entail the equality of `a` and `b` (that must be congruent).
+    fn explain_eq(&self, a: NodeId, b: NodeId, domains: &Domains, out: &mut Vec<Lit>) {
+        if a == b {
+            return;
+        }
+        for reason in self.path(a, b) {
+            self.explain_reason(reason, domains, out);
+        }
+    }
+
+    fn explain_reason(&self, reason: Reason, domains: &Domains, out: &mut Vec<Lit>) {
+        match reason {
+            Reason::Bound(var, value) => {
+                out.push(domains.presence(var));
+                out.push(Lit::geq(var, value));
+                out.push(Lit::leq(var, value));
+            }
+            Reason::Equality(id) => {
+                let Equality { a, b, lits } = self.equalities[id];
+                out.extend(lits);
+                out.push(domains.presence(a));
+                out.push(domains.presence(b));
+            }
+            Reason::Congruence(p, q) => {
+                let (p, q) = (&self.apps[p], &self.apps[q]);
+                out.push(p.active);
+                out.push(q.active);
+                for (&x, &y) in p.args.iter().zip(q.args.iter()) {
+                    self.explain_eq(x, y, domains, out);
+                }
+            }
+        }
+    }
+
+    /// Records an explanation and returns the cause of the corresponding inference.
+    fn record_explanation(&mut self, mut explanation: Vec<Lit>) -> u32 {
+        explanation.retain(|&l| l != Lit::TRUE);
+        explanation.sort();
+        explanation.dedup();
+        self.explanations.push(explanation);
+        self.trail.push(Event::Explanation);
+        (self.explanations.len() - 1) as u32
+    }
+
+    /// Merges the node of a present and bound variable with the one of its value.
+    fn on_variable_update(&mut self, var: VarRef, domains: &Domains) -> Result<(), Contradiction> {
+        let Some(&n) = self.node_ids.get(&Node::Var(var)) else {
+            return Ok(());
+        };
+        self.dirty.push(self.root[n]);
+        let (lb, ub) = domains.bounds(var);
+        if lb == ub && domains.entails(domains.presence(var)) {
+            let v = self.node(Node::Val(lb));
+            self.merge(n, v, Reason::Bound(var, lb), domains)?;
+        }
+        Ok(())
+    }
+
+    /// Merges the variables of an equality whose literals are entailed, if both are present.
+    fn on_equality(&mut self, id: EqId, domains: &Domains) -> Result<(), Contradiction> {
+        let Equality { a, b, lits } = self.equalities[id];
+        let [p, q] = [domains.presence(a), domains.presence(b)];
+        if lits.iter().chain([&p, &q]).all(|&l| domains.entails(l)) {
+            let (na, nb) = (self.node(Node::Var(a)), self.node(Node::Var(b)));
+            self.merge(na, nb, Reason::Equality(id), domains)?;
+            // the variables may have been bound before having a node
+            self.on_variable_update(a, domains)?;
+            self.on_variable_update(b, domains)?;
+        }
+        Ok(())
+    }
+
+    fn on_activation(&mut self, app: AppId, domains: &Domains) -> Result<(), Contradiction> {
+        self.rehash.push(app);
+        let vars: Vec<VarRef> = self.apps[app]
+            .args
+            .iter()
+            .chain(std::iter::once(&self.apps[app].result))
+            .filter_map(|&n| match self.nodes[n] {
+                Node::Var(v) => Some(v),
+                Node::Val(_) => None,
+            })
+            .collect();
+        for var in vars {
+            self.on_variable_update(var, domains)?;
+        }
+        Ok(())
+    }
+
+    fn signature(&self, app: AppId) -> Signature {
+        let app = &self.apps[app];
+        (app.fun, app.args.iter().map(|&a| self.root[a]).collect())
+    }
+
+    /// Re-hashes the active applications whose arguments changed class, merging the results of applications
+    /// with the same signature, until reaching a fixpoint.
+    fn propagate_congruences(&mut self, domains: &Domains) -> Result<(), Contradiction> {
+        while let Some(id) = self.rehash.pop() {
+            if !domains.entails(self.apps[id].active) {
+                continue;
+            }
+            let signature = self.signature(id);
+            match self.signatures.get(&signature).copied() {
+                Some(other) if other == id => {}
+                Some(other) if domains.entails(self.apps[other].active) && self.signature(other) == signature => {
+                    let (a, b) = (self.apps[other].result, self.apps[id].result);
+                    if !self.congruent(a, b) {
+                        self.stats.congruences += 1;
+                        self.merge(a, b, Reason::Congruence(other, id), domains)?;
+                    }
+                }
+                previous => {
+                    self.signatures.insert(signature.clone(), id);
+                    self.trail.push(Event::Signature { signature, previous });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Restricts the domain of each variable of the class to the intersection of all domains in the class.
+    fn propagate_bounds(&mut self, root: NodeId, domains: &mut Domains) -> Result<(), Contradiction> {
+        if self.members[root].len() < 2 {
+            return Ok(());
+        }
+        let bounds = |n: NodeId| match self.nodes[n] {
+            Node::Var(v) => domains.bounds(v),
+            Node::Val(c) => (c, c),
+        };
+        let mut lb = (INT_CST_MIN, root);
+        let mut ub = (INT_CST_MAX, root);
+        for &n in &self.members[root] {
+            let (l, u) = bounds(n);
+            if l > lb.0 {
+                lb = (l, n);
+            }
+            if u < ub.0 {
+                ub = (u, n);
+            }
+        }
+        let members = self.members[root].clone();
+        for n in members {
+            let Node::Var(var) = self.nodes[n] else { continue };
+            for (is_lb, (bound, witness)) in [(true, lb), (false, ub)] {
+                let inferred = if is_lb {
+                    Lit::geq(var, bound)
+                } else {
+                    Lit::leq(var, bound)
+                };
+                if domains.entails(inferred) {
+                    continue;
+                }
+                let mut explanation = Vec::new();
+                self.explain_eq(n, witness, domains, &mut explanation);
+                if let Node::Var(w) = self.nodes[witness] {
+                    explanation.push(if is_lb { Lit::geq(w, bound) } else { Lit::leq(w, bound) });
+                }
+                let cause = self.record_explanation(explanation);
+                self.stats.propagations += 1;
+                domains.set(inferred, self.identity().cause(cause))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Euf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backtrack for Euf {
+    fn save_state(&mut self) -> DecLvl {
+        self.trail.save_state()
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.trail.num_saved()
+    }
+
+    fn restore_last(&mut self) {
+        let mut events = Vec::new();
+        self.trail.restore_last_with(|e| events.push(e));
+        for e in events {
+            match e {
+                Event::Merge { .. } => self.undo_merge(e),
+                Event::Explanation => {
+                    self.explanations.pop().unwrap();
+                }
+                Event::Signature { signature, previous } => match previous {
+                    Some(app) => {
+                        self.signatures.insert(signature, app);
+                    }
+                    None => {
+                        self.signatures.remove(&signature);
+                    }
+                },
+            }
+        }
+        self.rehash.clear();
+        self.dirty.clear();
+    }
+}
+
+impl Theory for Euf {
+    fn identity(&self) -> ReasonerId {
+        ReasonerId::Euf
+    }
+
+    fn propagate(&mut self, domains: &mut Domains) -> Result<(), Contradiction> {
+        if self.apps.is_empty() {
+            return Ok(());
+        }
+        while let Some(app) = self.new_apps.pop() {
+            if domains.entails(self.apps[app].active) {
+                self.on_activation(app, domains)?;
+            }
+        }
+        while let Some(id) = self.new_equalities.pop() {
+            let Equality { a, b, .. } = self.equalities[id];
+            for presence in [domains.presence(a), domains.presence(b)] {
+                if presence != Lit::TRUE {
+                    self.equality_watches.entry(presence.variable()).or_default().push(id);
+                }
+            }
+            self.on_equality(id, domains)?;
+        }
+        loop {
+            let mut new_events = false;
+            while let Some(ev) = self.cursor.pop(domains.trail()).copied() {
+                new_events = true;
+                let var = ev.affected_bound.variable();
+                self.on_variable_update(var, domains)?;
+                if let Some(apps) = self.activations.get(&var) {
+                    let activated: Vec<AppId> = apps
+                        .iter()
+                        .copied()
+                        .filter(|&app| domains.entails(self.apps[app].active))
+                        .collect();
+                    for app in activated {
+                        self.on_activation(app, domains)?;
+                    }
+                }
+                if let Some(equalities) = self.equality_watches.get(&var) {
+                    for id in equalities.clone() {
+                        self.on_equality(id, domains)?;
+                    }
+                }
+            }
+            self.propagate_congruences(domains)?;
+            let mut roots: Vec<NodeId> = self.dirty.drain(..).map(|n| self.root[n]).collect();
+            roots.sort();
+            roots.dedup();
+            for root in roots {
+                self.propagate_bounds(root, domains)?;
+            }
+            if !new_events {
+                return Ok(());
+            }
+        }
+    }
+
+    fn explain(&mut self, _: Lit, context: InferenceCause, _: &DomainsSnapshot, out_explanation: &mut Explanation) {
+        for &l in &self.explanations[context.payload as usize] {
+            out_explanation.push(l);
+        }
+    }
+
+    fn print_stats(&self) {
+        println!("# nodes: {}", self.nodes.len());
+        println!("# applications: {}", self.apps.len());
+        println!("# merges: {}", self.stats.merges);
+        println!("# congruences: {}", self.stats.congruences);
+        println!("# propagations: {}", self.stats.propagations);
+        println!("# conflicts: {}", self.stats.conflicts);
+    }
+
+    fn stats(&self) -> TheoryStats {
+        TheoryStats::from([
+            ("nodes".to_string(), self.nodes.len() as u64),
+            ("applications".to_string(), self.apps.len() as u64),
+            ("merges".to_string(), self.stats.merges),
+            ("congruences".to_string(), self.stats.congruences),
+            ("propagations".to_string(), self.stats.propagations),
+            ("conflicts".to_string(), self.stats.conflicts),
+        ])
+    }
+
+    fn clone_box(&self) -> Box<dyn Theory> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::state::Cause;
+
+    /// Checks that the explanation of each inference of the reasoner holds in the current state.
+    fn check_explanations(d: &Domains, euf: &Euf) {
+        for explanation in &euf.explanations {
+            for &l in explanation {
+                assert!(d.entails(l), "{l:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_congruence() {
+        // g(x) = gx, g(y) = gy, f(gx) = r1, f(gy) = r2
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let gx = d.new_var(0, 10);
+        let gy = d.new_var(0, 10);
+        let r1 = d.new_var(0, 10);
+        let r2 = d.new_var(0, 10);
+        let (f, g) = (FunId::new(0), FunId::new(1));
+        let mut euf = Euf::new();
+        euf.add_application(g, &[x], gx, Lit::TRUE);
+        euf.add_application(g, &[y], gy, Lit::TRUE);
+        euf.add_application(f, &[gx], r1, Lit::TRUE);
+        euf.add_application(f, &[gy], r2, Lit::TRUE);
+        assert!(euf.propagate(&mut d).is_ok());
+
+        d.save_state();
+        euf.save_state();
+        d.set_lb(r1, 3, Cause::Decision).unwrap();
+        d.set_ub(r2, 5, Cause::Decision).unwrap();
+        d.set_lb(x, 2, Cause::Decision).unwrap();
+        d.set_ub(x, 2, Cause::Decision).unwrap();
+        d.set_lb(y, 2, Cause::Decision).unwrap();
+        d.set_ub(y, 2, Cause::Decision).unwrap();
+        assert!(euf.propagate(&mut d).is_ok());
+        // x = y  =>  g(x) = g(y)  =>  r1 = r2
+        assert_eq!(d.bounds(r1), (3, 5));
+        assert_eq!(d.bounds(r2), (3, 5));
+        check_explanations(&d, &euf);
+        let explained: Vec<Lit> = euf.explanations.iter().flatten().copied().collect();
+        assert!(explained.contains(&Lit::geq(x, 2)) && explained.contains(&Lit::leq(y, 2)));
+
+        // binding the result of g propagates to the other application
+        d.set_lb(gx, 7, Cause::Decision).unwrap();
+        d.set_ub(gx, 7, Cause::Decision).unwrap();
+        assert!(euf.propagate(&mut d).is_ok());
+        assert_eq!(d.bounds(gy), (7, 7));
+        check_explanations(&d, &euf);
+
+        d.restore_last();
+        euf.restore_last();
+        assert!(euf.explanations.is_empty());
+        assert!((0..euf.nodes.len()).all(|n| euf.root[n] == n && euf.members[n] == [n]));
+        assert_eq!(d.bounds(r1), (0, 10));
+    }
+
+    #[test]
+    fn test_entailed_equality() {
+        // f(x) = r1, f(y) = r2, with a literal for x = y
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let r1 = d.new_var(0, 10);
+        let r2 = d.new_var(0, 10);
+        let eq = d.new_var(0, 1).geq(1);
+        let f = FunId::new(0);
+        let mut euf = Euf::new();
+        euf.add_application(f, &[x], r1, Lit::TRUE);
+        euf.add_application(f, &[y], r2, Lit::TRUE);
+        euf.add_equality(x, y, eq);
+        d.set_lb(r1, 3, Cause::Decision).unwrap();
+        assert!(euf.propagate(&mut d).is_ok());
+        assert_eq!(d.bounds(r2), (0, 10));
+
+        d.save_state();
+        euf.save_state();
+        d.decide(eq).unwrap();
+        assert!(euf.propagate(&mut d).is_ok());
+        // x = y  =>  r1 = r2, although neither x nor y is bound
+        assert_eq!(d.bounds(r2), (3, 10));
+        check_explanations(&d, &euf);
+        assert!(euf.explanations.iter().flatten().any(|&l| l == eq));
+
+        d.restore_last();
+        euf.restore_last();
+        assert!(euf.explanations.is_empty());
+        assert!(euf.propagate(&mut d).is_ok());
+        assert_eq!(d.bounds(r2), (0, 10));
+
+        // the congruence is detected again after backtracking
+        d.save_state();
+        euf.save_state();
+        d.decide(eq).unwrap();
+        assert!(euf.propagate(&mut d).is_ok());
+        assert_eq!(d.bounds(r2), (3, 10));
+    }
+
+    #[test]
+    fn test_conflict() {
+        // f(x) = 1, f(y) = 2, with optional applications
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let one = d.new_var(1, 1);
+        let two = d.new_var(2, 2);
+        let active = d.new_var(0, 1).geq(1);
+        let f = FunId::new(0);
+        let mut euf = Euf::new();
+        euf.add_application(f, &[x], one, Lit::TRUE);
+        euf.add_application(f, &[y], two, active);
+        assert!(euf.propagate(&mut d).is_ok());
+
+        d.save_state();
+        euf.save_state();
+        d.set_lb(x, 4, Cause::Decision).unwrap();
+        d.set_ub(x, 4, Cause::Decision).unwrap();
+        d.set_lb(y, 4, Cause::Decision).unwrap();
+        d.set_ub(y, 4, Cause::Decision).unwrap();
+        // the second application is not active
+        assert!(euf.propagate(&mut d).is_ok());
+
+        d.decide(active).unwrap();
+        match euf.propagate(&mut d) {
+            Err(Contradiction::Explanation(e)) => {
+                assert!(e.literals().contains(&active));
+                for &l in e.literals() {
+                    assert!(d.entails(l), "{l:?}");
+                }
+            }
+            _ => panic!("Expected a conflict"),
+        }
+        d.restore_last();
+        euf.restore_last();
+        assert!(euf.propagate(&mut d).is_ok());
+    }
+}
@@ -4,14 +4,19 @@ use crate::core::state::{Domains, Explanation, InvalidUpdate};
 use crate::core::Lit;
 use crate::reasoners::cp::Cp;
 use crate::reasoners::eq::SplitEqTheory;
+use crate::reasoners::euf::Euf;
 use crate::reasoners::sat::SatSolver;
+use crate::reasoners::simplex::Simplex;
 use crate::reasoners::stn::theory::StnTheory;
 use crate::reasoners::tautologies::Tautologies;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
 pub mod cp;
 pub mod eq;
+pub mod euf;
 pub mod sat;
+pub mod simplex;
 pub mod stn;
 pub mod tautologies;
 
@@ -24,6 +29,8 @@ pub enum ReasonerId {
     Cp,
     Eq(u16),
     Tautologies,
+    Simplex,
+    Euf,
 }
 
 impl ReasonerId {
@@ -44,6 +51,8 @@ impl Display for ReasonerId {
                 Eq(_) => "Equality",
                 Cp => "CP",
                 Tautologies => "Optim",
+                Simplex => "Simplex",
+                Euf => "EUF",
             }
         )
     }
@@ -64,9 +73,32 @@ pub trait Theory: Backtrack + Send + 'static {
 
     fn print_stats(&self);
 
+    /// Returns the named counters of this reasoner, as a machine-readable counterpart of `print_stats`.
+    fn stats(&self) -> TheoryStats;
+
     fn clone_box(&self) -> Box<dyn Theory>;
 }
 
+/// Named counters of a reasoner (e.g. number of propagations or size of its database).
+///
+/// Counters are cumulative counts of events, except for the ones listed in [`SIZE_COUNTERS`].
+pub type TheoryStats = BTreeMap<String, u64>;
+
+/// Names of the reasoner counters that measure the size of a data structure (e.g. the clause database)
+/// rather than counting events. When merging the statistics of several solvers, the largest size is kept
+/// instead of the sum.
+pub const SIZE_COUNTERS: [&str; 9] = [
+    "applications",
+    "constraints",
+    "db_core",
+    "db_learnt",
+    "db_size",
+    "db_tier2",
+    "nodes",
+    "parts",
+    "propagators",
+];
+
 #[derive(Debug)]
 pub enum Contradiction {
     InvalidUpdate(InvalidUpdate),
@@ -87,12 +119,14 @@ impl From<Explanation> for Contradiction {
 ///
 /// SAT should always be first because we should not allow anything to happen between
 /// the moment a clause is learned and the moment it is is propagated.
-pub(crate) const REASONERS: [ReasonerId; 5] = [
+pub(crate) const REASONERS: [ReasonerId; 7] = [
     ReasonerId::Sat,
     ReasonerId::Tautologies,
     ReasonerId::Diff,
     ReasonerId::Eq(0),
+    ReasonerId::Euf,
     ReasonerId::Cp,
+    ReasonerId::Simplex,
 ];
 
 /// A set of inference modules for constraint propagation.
@@ -103,6 +137,8 @@ pub struct Reasoners {
     pub eq: SplitEqTheory,
     pub cp: Cp,
     pub tautologies: Tautologies,
+    pub simplex: Simplex,
+    pub euf: Euf,
 }
 impl Reasoners {
     pub fn new() -> Self {
@@ -112,6 +148,8 @@ impl Reasoners {
             eq: Default::default(),
             cp: Cp::new(ReasonerId::Cp),
             tautologies: Tautologies::default(),
+            simplex: Simplex::new(),
+            euf: Euf::new(),
         }
     }
 
@@ -122,6 +160,8 @@ impl Reasoners {
             ReasonerId::Eq(_) => &self.eq,
             ReasonerId::Cp => &self.cp,
             ReasonerId::Tautologies => &self.tautologies,
+            ReasonerId::Simplex => &self.simplex,
+            ReasonerId::Euf => &self.euf,
         }
     }
 
@@ -132,6 +172,8 @@ impl Reasoners {
             ReasonerId::Eq(_) => &mut self.eq,
             ReasonerId::Cp => &mut self.cp,
             ReasonerId::Tautologies => &mut self.tautologies,
+            ReasonerId::Simplex => &mut self.simplex,
+            ReasonerId::Euf => &mut self.euf,
         }
     }
 
@@ -11,14 +11,28 @@ use std::fmt::{Debug, Display, Error, Formatter};
 use std::ops::{Index, IndexMut};
 
 pub static DEFAULT_LOCKED_LBD_LEVEL: EnvParam<u32> = EnvParam::new("ARIES_SAT_LBD_LOCK_LEVEL", "4");
+pub static DEFAULT_TIER2_LBD_LEVEL: EnvParam<u32> = EnvParam::new("ARIES_SAT_LBD_TIER2_LEVEL", "6");
 
+/// Parameters of the learnt clause database.
+///
+/// Learnt clauses are split into three tiers based on their Literal Block Distance (LBD):
+///  - core: clauses with `lbd <= locked_lbd_level` that are never removed,
+///  - tier-2: clauses with `lbd <= tier2_lbd_level` that are kept as long as they are used
+///    (i.e. involved in a conflict or in an explanation) between two reductions of the database,
+///  - local: all other clauses, half of which (the least active ones) are removed on each reduction.
+///
+/// The LBD of a clause is reevaluated each time it is used for unit propagation, which may promote the clause
+/// to a better tier. A tier-2 clause that was not used since the last reduction is demoted to the local tier.
 #[derive(Clone)]
 pub struct ClausesParams {
     pub cla_inc: f64,
     pub cla_decay: f64,
-    /// All clauses whose Literal Block Distance (LBD) is LEQ than this one will not be removed
-    /// when reducing the DB. Note that the LBD may evolve overtime and is typically reevaluate on unit propagation
+    /// All learnt clauses whose LBD is LEQ than this one are part of the core tier and will not be removed
+    /// when reducing the DB.
     pub locked_lbd_level: u32,
+    /// All learnt clauses whose LBD is LEQ than this one (but not part of the core tier) are part of the tier-2.
+    /// Setting it to `locked_lbd_level` or less disables the tier-2.
+    pub tier2_lbd_level: u32,
 }
 impl Default for ClausesParams {
     fn default() -> Self {
@@ -26,15 +40,43 @@ impl Default for ClausesParams {
             cla_inc: 1_f64,
             cla_decay: 0.999_f64,
             locked_lbd_level: DEFAULT_LOCKED_LBD_LEVEL.get(),
+            tier2_lbd_level: DEFAULT_TIER2_LBD_LEVEL.get(),
+        }
+    }
+}
+
+impl ClausesParams {
+    /// Best tier for a learnt clause with the given LBD.
+    /// A clause with an LBD of 0, i.e., that does not depend on any decision, is always part of the core tier.
+    fn tier_of(&self, lbd: u32) -> Tier {
+        if lbd == 0 || lbd <= self.locked_lbd_level {
+            Tier::Core
+        } else if lbd <= self.tier2_lbd_level {
+            Tier::Tier2
+        } else {
+            Tier::Local
         }
     }
 }
 
+/// Retention tier of a learnt clause (see [`ClausesParams`]).
+/// Tiers are ordered from the most to the least valuable.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Tier {
+    Core,
+    Tier2,
+    Local,
+}
+
 #[derive(Copy, Clone)]
 struct ClauseMetadata {
     pub activity: f64,
     pub lbd: u32,
     pub learnt: bool,
+    /// Tier of the clause, only meaningful for learnt clauses.
+    pub tier: Tier,
+    /// True if the clause was used since the last reduction of the database.
+    pub used: bool,
 }
 
 /// A clause represents a disjunction of literals `a || b || c`. It may also contain a `scope` literal defining
@@ -298,8 +340,10 @@ pub struct ClauseDb {
     num_fixed: usize,
     /// Total number of clauses.
     num_clauses: usize,
-    // Number of learnt clauses that are locked (should not be removed) because of a low LBD indice.
-    num_learnt_lbd_locked: usize,
+    /// Number of learnt clauses in the core tier, that should not be removed because of a low LBD.
+    num_core: usize,
+    /// Number of learnt clauses in the tier-2.
+    num_tier2: usize,
     first_possibly_free: usize,
     /// Associates each clause id to to a clause.
     /// Unassigned clause ids point to a tautological clause in order to always point to valid one.
@@ -322,7 +366,8 @@ impl ClauseDb {
             params,
             num_fixed: 0,
             num_clauses: 0,
-            num_learnt_lbd_locked: 0,
+            num_core: 0,
+            num_tier2: 0,
             first_possibly_free: 0,
             clauses: RefVec::new(),
             metadata: RefMap::default(),
@@ -347,6 +392,9 @@ impl ClauseDb {
             activity: 0f64,
             lbd: 0,
             learnt,
+            // the LBD of a clause is unknown until it is used for propagation
+            tier: Tier::Local,
+            used: false,
         };
 
         // too costly to check when the number of clause grows
@@ -399,13 +447,45 @@ impl ClauseDb {
                 self.all_clauses()
                     .filter(|&cl_id| {
                         let meta = self.metadata[cl_id];
-                        meta.learnt && meta.lbd != 0 && meta.lbd <= self.params.locked_lbd_level
+                        meta.learnt && meta.tier == Tier::Core
                     })
                     .count(),
-                self.num_learnt_lbd_locked
+                self.num_core
             );
         }
-        self.num_learnt() - self.num_learnt_lbd_locked
+        self.num_learnt() - self.num_core
+    }
+
+    /// Number of learnt clauses in the given tier.
+    pub fn num_in_tier(&self, tier: Tier) -> usize {
+        match tier {
+            Tier::Core => self.num_core,
+            Tier::Tier2 => self.num_tier2,
+            Tier::Local => self.num_learnt() - self.num_core - self.num_tier2,
+        }
+    }
+
+    /// Returns the tier of the clause, or `None` if it is not a learnt clause.
+    pub fn tier(&self, clause: ClauseId) -> Option<Tier> {
+        let meta = &self.metadata[clause];
+        meta.learnt.then_some(meta.tier)
+    }
+
+    /// Moves a learnt clause to another tier, maintaining the tier counters.
+    fn set_tier(&mut self, clause: ClauseId, tier: Tier) {
+        let meta = &mut self.metadata[clause];
+        debug_assert!(meta.learnt);
+        match meta.tier {
+            Tier::Core => self.num_core -= 1,
+            Tier::Tier2 => self.num_tier2 -= 1,
+            Tier::Local => {}
+        }
+        match tier {
+            Tier::Core => self.num_core += 1,
+            Tier::Tier2 => self.num_tier2 += 1,
+            Tier::Local => {}
+        }
+        meta.tier = tier;
     }
 
     pub fn all_clauses(&self) -> impl Iterator<Item = ClauseId> + '_ {
@@ -413,27 +493,17 @@ impl ClauseDb {
         self.metadata.keys()
     }
 
-    /// Set the LBD value of the clause
+    /// Set the LBD value of the clause, promoting it to a better tier if its new LBD allows it.
     pub fn set_lbd(&mut self, clause: ClauseId, lbd: u32) {
         debug_assert_ne!(lbd, 0);
         let meta = &mut self.metadata[clause];
+        meta.lbd = lbd;
         if meta.learnt {
-            // we need to keep track of the number of learnt clauses that are locked
-            // for low LBD number
-            let lock_level = self.params.locked_lbd_level;
-            if meta.lbd == 0 || meta.lbd > lock_level {
-                // previously unset or unlocked
-                if lbd <= lock_level {
-                    // is locked, bump counter
-                    self.num_learnt_lbd_locked += 1
-                }
-            } else if meta.lbd <= lock_level && lbd > lock_level {
-                debug_assert_ne!(meta.lbd, 0);
-                // the clause will not be locked anymore
-                self.num_learnt_lbd_locked -= 1;
+            let tier = self.params.tier_of(lbd);
+            if tier < meta.tier {
+                self.set_tier(clause, tier);
             }
         }
-        meta.lbd = lbd;
     }
 
     /// Returns the current LBD value from the clause (updated in unit propagation)
@@ -446,7 +516,9 @@ impl ClauseDb {
         }
     }
 
+    /// Increases the activity of the clause and marks it as recently used.
     pub fn bump_activity(&mut self, cl: ClauseId) {
+        self.metadata[cl].used = true;
         self.metadata[cl].activity += self.params.cla_inc;
         if self.metadata[cl].activity > 1e100_f64 {
             self.rescale_activities()
@@ -465,28 +537,33 @@ impl ClauseDb {
         self.params.cla_inc *= 1e-100_f64;
     }
 
-    /// Reduce the size of database by removing half of the clauses that were:
+    /// Reduce the size of database by removing half of the clauses that are:
     ///  - learnt, and
-    ///  - are not locked, and
-    ///  - have a high LBD value
+    ///  - not locked, and
+    ///  - in the local tier.
+    ///
+    /// Tier-2 clauses that were not used since the last reduction are demoted to the local tier
+    /// (and thus become candidates for removal on the next reduction).
     pub fn reduce_db<F: Fn(ClauseId) -> bool>(&mut self, locked: F, remove_watch: &mut impl FnMut(ClauseId, Lit)) {
+        let mut clauses = Vec::new();
+        let mut demoted = Vec::new();
         #[allow(deprecated)] // ok because we know the table to be dense
-        let mut clauses: Vec<_> = self
-            .metadata
-            .entries()
-            .filter_map(|(id, meta)| {
-                if meta.lbd <= self.params.locked_lbd_level {
-                    // this clause should be kept because of its low LBD value
-                    None
-                } else if meta.learnt && !locked(id) {
-                    // let score = meta.activity / ((meta.lbd) as f64);
-                    let score = meta.activity;
-                    Some((id, score))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        for (id, meta) in self.metadata.entries_mut() {
+            if !meta.learnt {
+                continue;
+            }
+            match meta.tier {
+                Tier::Core => {}
+                Tier::Tier2 if meta.used => {}
+                Tier::Tier2 => demoted.push(id),
+                Tier::Local if locked(id) => {}
+                Tier::Local => clauses.push((id, meta.activity)),
+            }
+            meta.used = false;
+        }
+        for id in demoted {
+            self.set_tier(id, Tier::Local);
+        }
 
         clauses.sort_by(|&a, &b| a.1.partial_cmp(&b.1).unwrap_or(Equal));
         // remove half removable
@@ -5,7 +5,7 @@ use crate::core::state::{Domains, DomainsSnapshot, Event, Explanation, Inference
 use crate::core::*;
 use crate::model::extensions::DisjunctionExt;
 use crate::reasoners::sat::clauses::*;
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
 use itertools::Itertools;
 use smallvec::alloc::collections::VecDeque;
 
@@ -95,6 +95,8 @@ impl Default for SearchState {
 pub struct Stats {
     pub conflicts: u64,
     pub propagations: u64,
+    /// Number of times an out-of-order clause was propagated again after backtracking.
+    pub reassertions: u64,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -103,6 +105,7 @@ impl Default for Stats {
         Stats {
             conflicts: 0,
             propagations: 0,
+            reassertions: 0,
         }
     }
 }
@@ -114,6 +117,15 @@ struct PendingClause {
     clause: ClauseId,
 }
 
+/// A learnt clause whose asserted literal was placed on the trail at a higher decision level than the one
+/// at which the clause is unit. This occurs when the solver backtracks chronologically instead of backjumping.
+#[derive(Copy, Clone)]
+struct OutOfOrderClause {
+    clause: ClauseId,
+    /// Lowest decision level at which the clause is unit.
+    level: DecLvl,
+}
+
 #[derive(Clone)]
 pub struct SatSolver {
     pub clauses: ClauseDb,
@@ -122,6 +134,12 @@ pub struct SatSolver {
     identity: ReasonerId,
     /// Clauses that have been added to the database but not processed and propagated yet
     pending_clauses: VecDeque<PendingClause>,
+    /// Learnt clauses whose asserted literal is out of order on the trail.
+    /// They must be propagated again if we backtrack below the level of the asserted literal, but not below the
+    /// level at which they are unit. These clauses are never removed from the database.
+    out_of_order: Vec<OutOfOrderClause>,
+    /// True if we backtracked since the out-of-order clauses were last checked.
+    reassert_out_of_order: bool,
     /// Clauses that are locked (can't be remove from the database).
     /// A clause is locked if it asserted a literal and thus might be needed for an explanation
     locks: ClauseLocks,
@@ -143,6 +161,8 @@ impl SatSolver {
             events_stream: ObsTrailCursor::new(),
             identity,
             pending_clauses: Default::default(),
+            out_of_order: Vec::new(),
+            reassert_out_of_order: false,
             locks: ClauseLocks::new(),
             trail: Default::default(),
             params: Default::default(),
@@ -178,15 +198,33 @@ impl SatSolver {
         self.add_clause_impl(Clause::new(clause.into()), true);
     }
 
+    /// Returns the literals of all learnt or forgettable clauses currently in the database.
+    pub fn learnt_clauses(&self) -> impl Iterator<Item = Vec<Lit>> + '_ {
+        self.clauses
+            .all_clauses()
+            .filter(|&cl| self.clauses.is_learnt(cl))
+            .map(|cl| self.clauses[cl].literals().collect())
+    }
+
     /// Adds an asserting clause that was learnt as a result of a conflict
     /// On the next propagation, the clause will be propagated should assert a new literal.
     /// We set it to the front of the propagation queue as we know it will be triggered.
-    pub fn add_learnt_clause(&mut self, clause: impl Into<Disjunction>) {
+    ///
+    /// The `asserting_level` is the lowest decision level at which the clause is unit. If it is lower than the current
+    /// decision level (chronological backtracking), the asserted literal will be out of order on the trail and the clause
+    /// will be propagated again when backtracking below the current level.
+    pub fn add_learnt_clause(&mut self, clause: impl Into<Disjunction>, asserting_level: DecLvl) {
         self.stats.conflicts += 1;
         let clause = clause.into();
         let cl_id = self.clauses.add_clause(Clause::new(clause), true);
 
         self.pending_clauses.push_front(PendingClause { clause: cl_id });
+        if asserting_level < self.current_decision_level() {
+            self.out_of_order.push(OutOfOrderClause {
+                clause: cl_id,
+                level: asserting_level,
+            });
+        }
     }
 
     fn add_clause_impl(&mut self, clause: Clause, learnt: bool) -> ClauseId {
@@ -351,6 +389,10 @@ impl SatSolver {
                 return Err(conflict);
             }
         }
+        if self.reassert_out_of_order {
+            self.reassert_out_of_order = false;
+            self.reassert_out_of_order_clauses(model);
+        }
         // grow or shrink database. Placed here to be as close as possible to initial minisat
         // implementation where this appeared in search
         self.scale_database();
@@ -358,6 +400,29 @@ impl SatSolver {
         self.propagate_enqueued(model)
     }
 
+    /// Propagates again the out-of-order clauses whose asserted literal was undone by backtracking.
+    /// Clauses that are no longer unit at the current decision level, or whose asserted literal is now
+    /// at the level where it belongs, are forgotten about.
+    fn reassert_out_of_order_clauses(&mut self, model: &mut Domains) {
+        let current_level = self.current_decision_level();
+        self.out_of_order.retain(|ooo| ooo.level <= current_level);
+        for i in 0..self.out_of_order.len() {
+            let cl_id = self.out_of_order[i].clause;
+            let clause = &self.clauses[cl_id];
+            // Note: the clause might have become satisfied or violated by a previous reassertion,
+            // in which case it will be handled by the regular propagation of the corresponding event.
+            if model.unit_clause(clause) {
+                // remove the current watches, they will be set up again by the unit propagation
+                let (w1, w2) = (clause.watch1, clause.watch2);
+                self.watches.remove_watch(cl_id, !w1);
+                self.watches.remove_watch(cl_id, !w2);
+                self.process_unit_clause(cl_id, model);
+                self.stats.reassertions += 1;
+            }
+        }
+        self.out_of_order.retain(|ooo| ooo.level < current_level);
+    }
+
     /// Returns:
     ///   `Err(cid)`: in case of a conflict where `cid` is the id of the violated clause
     ///   `Ok(())` if no conflict was detected during propagation
@@ -691,11 +756,17 @@ impl SatSolver {
             } else {
                 // reduce the database size
                 let locks = &self.locks;
+                // out-of-order clauses must be kept to be reasserted upon backtracking (in any order),
+                // sort them in place to look them up by id
+                self.out_of_order.sort_unstable_by_key(|ooo| ooo.clause);
+                let out_of_order = &self.out_of_order;
+                let is_out_of_order = |cl: ClauseId| out_of_order.binary_search_by_key(&cl, |ooo| ooo.clause).is_ok();
                 let watches = &mut self.watches;
                 let mut remove_watch = |clause: ClauseId, watched: Lit| {
                     watches.remove_watch(clause, watched);
                 };
-                self.clauses.reduce_db(|cl| locks.contains(cl), &mut remove_watch);
+                self.clauses
+                    .reduce_db(|cl| locks.contains(cl) || is_out_of_order(cl), &mut remove_watch);
             }
         }
     }
@@ -704,6 +775,17 @@ impl SatSolver {
         println!("DB size              : {}", self.clauses.num_clauses());
         println!("Num unit propagations: {}", self.stats.propagations);
     }
+
+    pub fn stats(&self) -> TheoryStats {
+        let mut stats = TheoryStats::new();
+        stats.insert("db_size".to_string(), self.clauses.num_clauses() as u64);
+        stats.insert("db_learnt".to_string(), self.clauses.num_learnt() as u64);
+        stats.insert("db_core".to_string(), self.clauses.num_in_tier(Tier::Core) as u64);
+        stats.insert("db_tier2".to_string(), self.clauses.num_in_tier(Tier::Tier2) as u64);
+        stats.insert("unit_propagations".to_string(), self.stats.propagations);
+        stats.insert("reassertions".to_string(), self.stats.reassertions);
+        stats
+    }
 }
 
 impl Backtrack for SatSolver {
@@ -718,6 +800,7 @@ impl Backtrack for SatSolver {
     fn restore_last(&mut self) {
         let locks = &mut self.locks;
         self.trail.restore_last_with(|SatEvent::Lock(cl)| locks.unlock(cl));
+        self.reassert_out_of_order |= !self.out_of_order.is_empty();
     }
 }
 
@@ -744,6 +827,10 @@ impl Theory for SatSolver {
         self.print_stats()
     }
 
+    fn stats(&self) -> TheoryStats {
+        self.stats()
+    }
+
     fn clone_box(&self) -> Box<dyn Theory> {
         Box::new(self.clone())
     }
@@ -1080,4 +1167,51 @@ mod tests {
 
         assert!(sat.propagate(&mut m.state).is_err());
     }
+
+    #[test]
+    fn test_clause_tiers() {
+        let model = &mut Model::new();
+        let lits = (0..10).map(|_| model.new_bvar("v").true_lit()).collect_vec();
+        let mut db = ClauseDb::new(ClausesParams {
+            locked_lbd_level: 2,
+            tier2_lbd_level: 6,
+            ..Default::default()
+        });
+        let fixed = db.add_clause(Clause::new(Disjunction::new(vec![lits[0], lits[1]])), false);
+        let learnt = |db: &mut ClauseDb, i: usize| {
+            db.add_clause(Clause::new(Disjunction::new(vec![lits[i], lits[i + 1]])), true)
+        };
+        let core = learnt(&mut db, 1);
+        let tier2 = learnt(&mut db, 2);
+        let local = (3..9).map(|i| learnt(&mut db, i)).collect_vec();
+        db.set_lbd(core, 5);
+        db.set_lbd(core, 2);
+        db.set_lbd(tier2, 4);
+        for &cl in &local {
+            db.set_lbd(cl, 10);
+        }
+        assert_eq!(db.tier(fixed), None);
+        assert_eq!(db.tier(core), Some(Tier::Core));
+        assert_eq!(db.tier(tier2), Some(Tier::Tier2));
+        assert_eq!(db.num_in_tier(Tier::Local), 6);
+        // a worse LBD does not demote a clause
+        db.set_lbd(core, 7);
+        assert_eq!(db.tier(core), Some(Tier::Core));
+
+        // the tier-2 clause was not used since it was learnt, it is demoted but not removed
+        db.bump_activity(local[0]);
+        db.reduce_db(|_| false, &mut |_, _| {});
+        assert_eq!(db.num_learnt(), 2 + 3);
+        assert!(db.is_in_db(core) && db.is_in_db(tier2) && db.is_in_db(local[0]));
+        assert_eq!(db.tier(tier2), Some(Tier::Local));
+        assert_eq!(db.num_in_tier(Tier::Tier2), 0);
+
+        // promoted back to tier-2 and kept as long as it is used
+        db.set_lbd(tier2, 3);
+        db.bump_activity(tier2);
+        db.reduce_db(|_| false, &mut |_, _| {});
+        assert_eq!(db.tier(tier2), Some(Tier::Tier2));
+        assert!(db.is_in_db(core) && db.is_in_db(fixed));
+        assert_eq!(db.num_removable(), db.num_learnt() - 1);
+    }
 }
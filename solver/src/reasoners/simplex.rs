@@ -0,0 +1,892 @@
+//! A reasoner for the rational relaxation of linear constraints, based on an incremental simplex.
+//!
+//! The implementation follows the general simplex of *Dutertre and de Moura, "A Fast Linear-Arithmetic Solver
+//! for DPLL(T)", CAV 2006*, which behaves as a dual simplex on the bounds of the variables:
+//!
+//!  - each linear constraint `sum_i a_i * x_i <= ub` introduces a slack variable `s = sum_i a_i * x_i` whose upper bound
+//!    is `ub` when the constraint is active,
+//!  - the bounds of all other (structural) variables are those of their domains in the solver,
+//!  - the tableau and the current assignment are kept from one call to the next, and only the bounds change.
+//!    As a consequence, backtracking does not require any work.
+//!
+//! Propagation is incremental: each call only processes the variables whose bounds changed since the previous one
+//! and repairs the basic variables that these changes pushed out of their bounds.
+//!
+//! When the relaxation is infeasible, the reasoner produces a conflict made of the bounds that appear in the
+//! infeasible row (Farkas lemma): the row is a positive combination of the constraints that cannot be satisfied.
+//! Otherwise, the rows that contain a variable whose bound changed are used to tighten the bounds of their structural
+//! variables, explained by the bounds of the other variables of the row.
+//! When an objective is set (see [`Simplex::set_objective`]), the relaxation is additionally optimized
+//! with a primal simplex and the row of the objective yields its LP bound.
+//!
+//! Since all variables of the solver are integers, the rational relaxation is incomplete (but sound).
+//! Rationals are checked for overflows: if one occurs, the result is unknown and the reasoner gives up,
+//! accepting all states without any further inference.
+
+use crate::backtrack::{Backtrack, DecLvl, ObsTrailCursor};
+use crate::core::state::{Domains, DomainsSnapshot, Event, Explanation, InferenceCause};
+use crate::core::{IntCst, Lit, VarRef};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Signed, Zero};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Rationals used in the tableau. Coefficients are kept exact, and the large integer type limits the risk of overflows.
+type Rat = Ratio<i128>;
+
+/// Index of a variable in the tableau.
+type TVar = usize;
+
+/// Maximum number of pivots of the primal simplex that optimizes the objective in a single propagation.
+const MAX_OBJECTIVE_PIVOTS: usize = 100;
+
+/// Overflow of the rationals of the tableau.
+#[derive(Debug)]
+struct Overflow;
+
+fn add(a: Rat, b: Rat) -> Result<Rat, Overflow> {
+    a.checked_add(&b).ok_or(Overflow)
+}
+
+fn sub(a: Rat, b: Rat) -> Result<Rat, Overflow> {
+    a.checked_sub(&b).ok_or(Overflow)
+}
+
+fn mul(a: Rat, b: Rat) -> Result<Rat, Overflow> {
+    a.checked_mul(&b).ok_or(Overflow)
+}
+
+fn div(a: Rat, b: Rat) -> Result<Rat, Overflow> {
+    a.checked_div(&b).ok_or(Overflow)
+}
+
+/// Reason for which a propagation was interrupted.
+enum Failure {
+    Contradiction(Contradiction),
+    Overflow,
+}
+
+impl From<Overflow> for Failure {
+    fn from(_: Overflow) -> Self {
+        Failure::Overflow
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum VarKind {
+    /// A variable of the solver
+    Structural(VarRef),
+    /// The slack variable of the constraint at the given index
+    Slack(usize),
+}
+
+#[derive(Clone, Debug)]
+struct LinearConstraint {
+    /// The constraint only holds when this literal is true.
+    active: Lit,
+    ub: IntCst,
+}
+
+/// A row of the tableau, defining a basic variable as a linear combination of non-basic ones.
+#[derive(Clone, Debug)]
+struct Row {
+    basic: TVar,
+    coefs: BTreeMap<TVar, Rat>,
+}
+
+#[derive(Clone, Default)]
+struct SimplexStats {
+    checks: u64,
+    pivots: u64,
+    conflicts: u64,
+    bound_updates: u64,
+    overflows: u64,
+}
+
+#[derive(Clone)]
+pub struct Simplex {
+    kinds: Vec<VarKind>,
+    /// Current value of each variable of the tableau
+    values: Vec<Rat>,
+    /// For each variable, index of the row in which it is basic (if any)
+    row_of: Vec<Option<usize>>,
+    /// For each non-basic variable, indices of the rows in which it appears
+    cols: Vec<BTreeSet<usize>>,
+    rows: Vec<Row>,
+    constraints: Vec<LinearConstraint>,
+    structurals: HashMap<VarRef, TVar>,
+    /// For each variable of the solver, the variables of the tableau whose bounds depend on it.
+    /// A structural variable depends on its own bounds and on its presence, a slack variable on its activity literal.
+    watches: HashMap<VarRef, Vec<TVar>>,
+    /// Number of variables of the tableau that have been added to the watches.
+    num_watched: usize,
+    /// Cursor on the events of the domains, to find the variables whose bounds changed since the last propagation.
+    events: ObsTrailCursor<Event>,
+    /// Variables whose bounds may have changed since the last propagation.
+    touched: BTreeSet<TVar>,
+    /// Basic variables that may be out of their bounds. All other basic variables are within their bounds.
+    violated: BTreeSet<TVar>,
+    /// Variable whose value must be bounded by the relaxation, and whether it is minimized (or maximized).
+    objective: Option<(TVar, bool)>,
+    /// Explanations of the bounds inferred by the reasoner, indexed by the payload of their cause.
+    explanations: Vec<Vec<Lit>>,
+    /// Number of explanations at the start of each saved decision level.
+    saved: Vec<usize>,
+    /// Set when the rationals of the tableau overflowed, after which the reasoner makes no inference.
+    overflowed: bool,
+    stats: SimplexStats,
+}
+
+impl Simplex {
+    pub fn new() -> Simplex {
+        Simplex {
+            kinds: Default::default(),
+            values: Default::default(),
+            row_of: Default::default(),
+            cols: Default::default(),
+            rows: Default::default(),
+            constraints: Default::default(),
+            structurals: Default::default(),
+            watches: Default::default(),
+            num_watched: 0,
+            events: ObsTrailCursor::new(),
+            touched: Default::default(),
+            violated: Default::default(),
+            objective: None,
+            explanations: Default::default(),
+            saved: Default::default(),
+            overflowed: false,
+            stats: Default::default(),
+        }
+    }
+
+    /// Adds the constraint `sum_i factor_i * var_i <= ub` that must hold whenever `active` is true.
+    pub fn add_constraint(&mut self, sum: impl IntoIterator<Item = (VarRef, IntCst)>, ub: IntCst, active: Lit) {
+        if self.try_add_constraint(sum, ub, active).is_err() {
+            self.give_up();
+        }
+    }
+
+    fn try_add_constraint(
+        &mut self,
+        sum: impl IntoIterator<Item = (VarRef, IntCst)>,
+        ub: IntCst,
+        active: Lit,
+    ) -> Result<(), Overflow> {
+        // definition of the slack variable, over non-basic variables only
+        let mut coefs: BTreeMap<TVar, Rat> = BTreeMap::new();
+        for (var, factor) in sum {
+            if var == VarRef::ZERO || factor == 0 {
+                continue;
+            }
+            let x = self.structural(var);
+            let factor = Rat::from(factor as i128);
+            match self.row_of[x] {
+                Some(r) => {
+                    for (&y, &a) in &self.rows[r].coefs {
+                        add_coef(&mut coefs, y, mul(factor, a)?)?;
+                    }
+                }
+                None => add_coef(&mut coefs, x, factor)?,
+            }
+        }
+        let mut value = Rat::zero();
+        for (&y, &a) in &coefs {
+            value = add(value, mul(a, self.values[y])?)?;
+        }
+        let constraint = self.constraints.len();
+        self.constraints.push(LinearConstraint { active, ub });
+        let slack = self.new_var(VarKind::Slack(constraint));
+        let r = self.rows.len();
+        for &y in coefs.keys() {
+            self.cols[y].insert(r);
+        }
+        self.values[slack] = value;
+        self.row_of[slack] = Some(r);
+        self.rows.push(Row { basic: slack, coefs });
+        self.violated.insert(slack);
+        Ok(())
+    }
+
+    /// Sets the variable whose bound is inferred from an optimization of the relaxation, when searching for a solution
+    /// that minimizes (or maximizes) it.
+    pub fn set_objective(&mut self, var: VarRef, minimize: bool) {
+        let x = self.structural(var);
+        self.objective = Some((x, minimize));
+    }
+
+    fn new_var(&mut self, kind: VarKind) -> TVar {
+        self.kinds.push(kind);
+        self.values.push(Rat::zero());
+        self.row_of.push(None);
+        self.cols.push(BTreeSet::new());
+        self.kinds.len() - 1
+    }
+
+    fn structural(&mut self, var: VarRef) -> TVar {
+        if let Some(&x) = self.structurals.get(&var) {
+            x
+        } else {
+            let x = self.new_var(VarKind::Structural(var));
+            self.structurals.insert(var, x);
+            x
+        }
+    }
+
+    /// Stops all reasoning after an overflow, since the tableau may be left in an inconsistent state.
+    fn give_up(&mut self) {
+        self.overflowed = true;
+        self.stats.overflows += 1;
+    }
+
+    /// Records the dependencies of the variables added to the tableau since the last call,
+    /// and marks them as touched so that their bounds are taken into account.
+    fn watch_new_vars(&mut self, domains: &Domains) {
+        for x in self.num_watched..self.kinds.len() {
+            let deps = match self.kinds[x] {
+                VarKind::Structural(v) => [v, domains.presence(v).variable()],
+                VarKind::Slack(c) => [self.constraints[c].active.variable(), VarRef::ZERO],
+            };
+            for dep in deps {
+                if dep != VarRef::ZERO {
+                    self.watches.entry(dep).or_default().push(x);
+                }
+            }
+            self.touched.insert(x);
+        }
+        self.num_watched = self.kinds.len();
+    }
+
+    /// Returns the lower bound of the variable, if it has one.
+    fn lower(&self, x: TVar, domains: &Domains) -> Option<Rat> {
+        match self.kinds[x] {
+            VarKind::Structural(v) if domains.entails(domains.presence(v)) => Some(Rat::from(domains.lb(v) as i128)),
+            _ => None,
+        }
+    }
+
+    /// Returns the upper bound of the variable, if it has one.
+    fn upper(&self, x: TVar, domains: &Domains) -> Option<Rat> {
+        match self.kinds[x] {
+            VarKind::Structural(v) if domains.entails(domains.presence(v)) => Some(Rat::from(domains.ub(v) as i128)),
+            VarKind::Slack(c) if domains.entails(self.constraints[c].active) => {
+                Some(Rat::from(self.constraints[c].ub as i128))
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds to the explanation the literals that entail the current lower bound of the variable.
+    fn explain_lower(&self, x: TVar, domains: &Domains, out: &mut Explanation) {
+        match self.kinds[x] {
+            VarKind::Structural(v) => {
+                push_non_trivial(out, domains.presence(v));
+                push_non_trivial(out, Lit::geq(v, domains.lb(v)));
+            }
+            VarKind::Slack(_) => unreachable!("Slack variables have no lower bound"),
+        }
+    }
+
+    /// Adds to the explanation the literals that entail the current upper bound of the variable.
+    fn explain_upper(&self, x: TVar, domains: &Domains, out: &mut Explanation) {
+        match self.kinds[x] {
+            VarKind::Structural(v) => {
+                push_non_trivial(out, domains.presence(v));
+                push_non_trivial(out, Lit::leq(v, domains.ub(v)));
+            }
+            VarKind::Slack(c) => push_non_trivial(out, self.constraints[c].active),
+        }
+    }
+
+    fn can_increase(&self, x: TVar, domains: &Domains) -> bool {
+        match self.upper(x, domains) {
+            Some(ub) => self.values[x] < ub,
+            None => true,
+        }
+    }
+
+    fn can_decrease(&self, x: TVar, domains: &Domains) -> bool {
+        match self.lower(x, domains) {
+            Some(lb) => self.values[x] > lb,
+            None => true,
+        }
+    }
+
+    /// Sets the value of a non-basic variable, updating the value of all basic variables that depend on it.
+    fn update(&mut self, x: TVar, value: Rat) -> Result<(), Overflow> {
+        debug_assert!(self.row_of[x].is_none());
+        let delta = sub(value, self.values[x])?;
+        for &k in &self.cols[x] {
+            let row = &self.rows[k];
+            self.values[row.basic] = add(self.values[row.basic], mul(row.coefs[&x], delta)?)?;
+            self.violated.insert(row.basic);
+        }
+        self.values[x] = value;
+        Ok(())
+    }
+
+    /// Swaps the basic variable `basic` with the non-basic variable `non_basic`, and sets the value of `basic` to `value`.
+    fn pivot_and_update(&mut self, basic: TVar, non_basic: TVar, value: Rat) -> Result<(), Overflow> {
+        let r = self.row_of[basic].unwrap();
+        let a = self.rows[r].coefs[&non_basic];
+        let theta = div(sub(value, self.values[basic])?, a)?;
+        self.values[basic] = value;
+        self.values[non_basic] = add(self.values[non_basic], theta)?;
+        for &k in &self.cols[non_basic] {
+            if k != r {
+                let row = &self.rows[k];
+                self.values[row.basic] = add(self.values[row.basic], mul(row.coefs[&non_basic], theta)?)?;
+                self.violated.insert(row.basic);
+            }
+        }
+        self.violated.insert(non_basic);
+        self.pivot(r, non_basic)
+    }
+
+    fn pivot(&mut self, r: usize, non_basic: TVar) -> Result<(), Overflow> {
+        self.stats.pivots += 1;
+        let basic = self.rows[r].basic;
+        // basic = a * non_basic + sum_k c_k * x_k    =>    non_basic = (basic - sum_k c_k * x_k) / a
+        let mut coefs = std::mem::take(&mut self.rows[r].coefs);
+        let a = coefs.remove(&non_basic).unwrap();
+        for c in coefs.values_mut() {
+            *c = div(-*c, a)?;
+        }
+        coefs.insert(basic, div(Rat::from(1), a)?);
+        self.cols[basic].insert(r);
+
+        // substitute the non-basic variable in all other rows in which it appears
+        for k in std::mem::take(&mut self.cols[non_basic]) {
+            if k == r {
+                continue;
+            }
+            let row = &mut self.rows[k].coefs;
+            let c = row.remove(&non_basic).unwrap();
+            for (&y, &d) in &coefs {
+                add_coef(row, y, mul(c, d)?)?;
+                if row.contains_key(&y) {
+                    self.cols[y].insert(k);
+                } else {
+                    self.cols[y].remove(&k);
+                }
+            }
+        }
+        self.rows[r] = Row {
+            basic: non_basic,
+            coefs,
+        };
+        self.row_of[basic] = None;
+        self.row_of[non_basic] = Some(r);
+        Ok(())
+    }
+
+    /// Processes the bound changes since the last propagation, checks the feasibility of the relaxation and
+    /// tightens the bounds of the variables that appear in the affected rows.
+    fn propagate_bounds(&mut self, domains: &mut Domains) -> Result<(), Failure> {
+        self.watch_new_vars(domains);
+        while let Some(event) = self.events.pop(domains.trail()) {
+            if let Some(xs) = self.watches.get(&event.affected_bound.variable()) {
+                self.touched.extend(xs.iter().copied());
+            }
+        }
+        let touched = std::mem::take(&mut self.touched);
+        for &x in &touched {
+            if self.row_of[x].is_some() {
+                self.violated.insert(x);
+            } else {
+                // make sure the non-basic variable is within its bounds
+                match (self.lower(x, domains), self.upper(x, domains)) {
+                    (Some(lb), _) if self.values[x] < lb => self.update(x, lb)?,
+                    (_, Some(ub)) if self.values[x] > ub => self.update(x, ub)?,
+                    _ => {}
+                }
+            }
+        }
+        if let Err(failure) = self.check(domains) {
+            // the touched variables will be reconsidered for bound tightening in the next propagation
+            self.touched.extend(touched);
+            return Err(failure);
+        }
+
+        // rows in which a variable with new bounds appears
+        let mut rows = BTreeSet::new();
+        for &x in &touched {
+            match self.row_of[x] {
+                Some(r) => {
+                    rows.insert(r);
+                }
+                None => rows.extend(self.cols[x].iter().copied()),
+            }
+        }
+        if let Some((objective, minimize)) = self.objective {
+            self.optimize(objective, minimize, domains)?;
+            if let Some(r) = self.row_of[objective] {
+                rows.insert(r);
+            }
+        }
+        for r in rows {
+            self.tighten(r, domains)?;
+        }
+        Ok(())
+    }
+
+    /// Looks for an assignment of the tableau that respects the bounds of all variables,
+    /// returning the Farkas explanation of the infeasibility if there is none.
+    fn check(&mut self, domains: &Domains) -> Result<(), Failure> {
+        self.stats.checks += 1;
+        // select the basic variable with the smallest index that violates its bounds (Bland's rule)
+        while let Some(x) = self.violated.pop_first() {
+            let Some(r) = self.row_of[x] else {
+                continue;
+            };
+            let value = self.values[x];
+            let (bound, below) = match (self.lower(x, domains), self.upper(x, domains)) {
+                (Some(lb), _) if value < lb => (lb, true),
+                (_, Some(ub)) if value > ub => (ub, false),
+                _ => continue,
+            };
+            let row = &self.rows[r];
+            // a non-basic variable that can be changed to bring `x` towards its bound
+            let candidate = row
+                .coefs
+                .iter()
+                .find(|&(&y, &a)| {
+                    if a.is_positive() == below {
+                        self.can_increase(y, domains)
+                    } else {
+                        self.can_decrease(y, domains)
+                    }
+                })
+                .map(|(&y, _)| y);
+
+            match candidate {
+                Some(y) => self.pivot_and_update(x, y, bound)?,
+                None => {
+                    // the row cannot be repaired: all non-basic variables are at the bound that restricts `x`
+                    self.stats.conflicts += 1;
+                    let mut explanation = Explanation::with_capacity(row.coefs.len() * 2 + 2);
+                    if below {
+                        self.explain_lower(x, domains, &mut explanation);
+                    } else {
+                        self.explain_upper(x, domains, &mut explanation);
+                    }
+                    for (&y, &a) in &row.coefs {
+                        if a.is_positive() == below {
+                            self.explain_upper(y, domains, &mut explanation);
+                        } else {
+                            self.explain_lower(y, domains, &mut explanation);
+                        }
+                    }
+                    self.violated.insert(x);
+                    return Err(Failure::Contradiction(Contradiction::Explanation(explanation)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Starting from a feasible assignment, moves towards the minimum (or maximum) of the objective
+    /// with the primal simplex, using Bland's rule for the entering and leaving variables.
+    fn optimize(&mut self, objective: TVar, minimize: bool, domains: &Domains) -> Result<(), Overflow> {
+        let sign = Rat::from(if minimize { 1 } else { -1 });
+        for _ in 0..MAX_OBJECTIVE_PIVOTS {
+            // the objective as a combination of non-basic variables: sign * objective = sum_j d_j * x_j
+            let terms: Vec<(TVar, Rat)> = match self.row_of[objective] {
+                Some(r) => self.rows[r].coefs.iter().map(|(&y, &d)| (y, d * sign)).collect(),
+                None => vec![(objective, sign)],
+            };
+            // a variable whose change decreases the objective
+            let entering = terms.iter().find_map(|&(y, d)| {
+                if d.is_positive() && self.can_decrease(y, domains) {
+                    Some((y, Rat::from(-1)))
+                } else if d.is_negative() && self.can_increase(y, domains) {
+                    Some((y, Rat::from(1)))
+                } else {
+                    None
+                }
+            });
+            let Some((entering, direction)) = entering else {
+                return Ok(()); // optimal
+            };
+
+            // ratio test: the largest step of the entering variable that keeps all variables within their bounds
+            let own_bound = if direction.is_positive() {
+                self.upper(entering, domains)
+            } else {
+                self.lower(entering, domains)
+            };
+            // (step, leaving variable and its bound)
+            let mut best: Option<(Rat, Option<(TVar, Rat)>)> = match own_bound {
+                Some(bound) => Some((mul(sub(bound, self.values[entering])?, direction)?, None)),
+                None => None,
+            };
+            for &k in &self.cols[entering] {
+                let row = &self.rows[k];
+                let b = row.basic;
+                let rate = mul(row.coefs[&entering], direction)?;
+                let bound = if rate.is_positive() {
+                    self.upper(b, domains)
+                } else {
+                    self.lower(b, domains)
+                };
+                let Some(bound) = bound else {
+                    continue;
+                };
+                let step = div(sub(bound, self.values[b])?, rate)?;
+                let better = match best {
+                    None => true,
+                    Some((s, leaving)) => step < s || step == s && leaving.is_some_and(|(l, _)| b < l),
+                };
+                if better {
+                    best = Some((step, Some((b, bound))));
+                }
+            }
+            match best {
+                None => return Ok(()), // unbounded
+                Some((step, None)) => self.update(entering, add(self.values[entering], mul(step, direction)?)?)?,
+                Some((_, Some((leaving, bound)))) => self.pivot_and_update(leaving, entering, bound)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Uses the row `r` to tighten the bounds of its structural variables, from the bounds of the other variables.
+    fn tighten(&mut self, r: usize, domains: &mut Domains) -> Result<(), Failure> {
+        // the row as `sum_k c_k * x_k = 0`
+        let row = &self.rows[r];
+        let terms: Vec<(TVar, Rat)> = row
+            .coefs
+            .iter()
+            .map(|(&y, &c)| (y, c))
+            .chain(std::iter::once((row.basic, Rat::from(-1))))
+            .collect();
+        // bounds of each term `c_k * x_k`, and of their sum with the number of unbounded terms
+        let mut mins = Vec::with_capacity(terms.len());
+        let mut maxs = Vec::with_capacity(terms.len());
+        let (mut min_sum, mut min_unbounded) = (Rat::zero(), 0);
+        let (mut max_sum, mut max_unbounded) = (Rat::zero(), 0);
+        for &(y, c) in &terms {
+            let (lo, hi) = if c.is_positive() {
+                (self.lower(y, domains), self.upper(y, domains))
+            } else {
+                (self.upper(y, domains), self.lower(y, domains))
+            };
+            let lo = lo.map(|b| mul(c, b)).transpose()?;
+            let hi = hi.map(|b| mul(c, b)).transpose()?;
+            match lo {
+                Some(v) => min_sum = add(min_sum, v)?,
+                None => min_unbounded += 1,
+            }
+            match hi {
+                Some(v) => max_sum = add(max_sum, v)?,
+                None => max_unbounded += 1,
+            }
+            mins.push(lo);
+            maxs.push(hi);
+        }
+        // bound of the sum of all terms but the i-th one
+        let rest = |sum: Rat, unbounded: usize, own: Option<Rat>| -> Result<Option<Rat>, Overflow> {
+            match own {
+                Some(own) if unbounded == 0 => Ok(Some(sub(sum, own)?)),
+                None if unbounded == 1 => Ok(Some(sum)),
+                _ => Ok(None),
+            }
+        };
+
+        for (i, &(y, c)) in terms.iter().enumerate() {
+            let VarKind::Structural(v) = self.kinds[y] else {
+                continue;
+            };
+            if !domains.entails(domains.presence(v)) {
+                continue;
+            }
+            // c * y = - sum_{k != i} c_k * x_k
+            for from_min in [true, false] {
+                let bound = if from_min {
+                    rest(min_sum, min_unbounded, mins[i])?
+                } else {
+                    rest(max_sum, max_unbounded, maxs[i])?
+                };
+                let Some(bound) = bound else {
+                    continue;
+                };
+                let bound = div(-bound, c)?;
+                // the minimum of the other terms gives an upper bound of `c * y`, their maximum a lower bound
+                let lit = if from_min == c.is_positive() {
+                    match IntCst::try_from(bound.floor().to_integer()) {
+                        Ok(ub) if ub < domains.ub(v) => Lit::leq(v, ub),
+                        _ => continue,
+                    }
+                } else {
+                    match IntCst::try_from(bound.ceil().to_integer()) {
+                        Ok(lb) if lb > domains.lb(v) => Lit::geq(v, lb),
+                        _ => continue,
+                    }
+                };
+                let mut explanation = Explanation::with_capacity(terms.len() * 2);
+                push_non_trivial(&mut explanation, domains.presence(v));
+                for (k, &(x, d)) in terms.iter().enumerate() {
+                    if k != i {
+                        if d.is_positive() == from_min {
+                            self.explain_lower(x, domains, &mut explanation);
+                        } else {
+                            self.explain_upper(x, domains, &mut explanation);
+                        }
+                    }
+                }
+                let cause = self.identity().cause(self.explanations.len() as u32);
+                self.explanations.push(explanation.lits);
+                self.stats.bound_updates += 1;
+                domains
+                    .set(lit, cause)
+                    .map_err(|e| Failure::Contradiction(Contradiction::InvalidUpdate(e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the value of the variable in the current solution of the relaxation (if it appears in the tableau).
+    pub fn relaxed_value(&self, var: VarRef) -> Option<Ratio<i128>> {
+        self.structurals.get(&var).map(|&x| self.values[x])
+    }
+}
+
+impl Default for Simplex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn add_coef(coefs: &mut BTreeMap<TVar, Rat>, x: TVar, a: Rat) -> Result<(), Overflow> {
+    let c = coefs.entry(x).or_insert(Rat::zero());
+    *c = add(*c, a)?;
+    if c.is_zero() {
+        coefs.remove(&x);
+    }
+    Ok(())
+}
+
+fn push_non_trivial(out: &mut Explanation, lit: Lit) {
+    if lit != Lit::TRUE {
+        out.push(lit)
+    }
+}
+
+impl Backtrack for Simplex {
+    fn save_state(&mut self) -> DecLvl {
+        self.saved.push(self.explanations.len());
+        DecLvl::new(self.saved.len() as u32)
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.saved.len() as u32
+    }
+
+    fn restore_last(&mut self) {
+        // the current assignment satisfies the tableau and remains valid, only the bounds will change
+        let num_explanations = self.saved.pop().expect("No saved state");
+        self.explanations.truncate(num_explanations);
+    }
+}
+
+impl Theory for Simplex {
+    fn identity(&self) -> ReasonerId {
+        ReasonerId::Simplex
+    }
+
+    fn propagate(&mut self, model: &mut Domains) -> Result<(), Contradiction> {
+        if self.rows.is_empty() || self.overflowed {
+            return Ok(());
+        }
+        match self.propagate_bounds(model) {
+            Ok(()) => Ok(()),
+            Err(Failure::Contradiction(contradiction)) => Err(contradiction),
+            Err(Failure::Overflow) => {
+                self.give_up();
+                Ok(())
+            }
+        }
+    }
+
+    fn explain(&mut self, _: Lit, context: InferenceCause, _: &DomainsSnapshot, out: &mut Explanation) {
+        out.extend(self.explanations[context.payload as usize].iter().copied())
+    }
+
+    fn print_stats(&self) {
+        println!("# constraints: {}", self.constraints.len());
+        println!("# checks: {}", self.stats.checks);
+        println!("# pivots: {}", self.stats.pivots);
+        println!("# conflicts: {}", self.stats.conflicts);
+        println!("# bound updates: {}", self.stats.bound_updates);
+        println!("# overflows: {}", self.stats.overflows);
+    }
+
+    fn stats(&self) -> TheoryStats {
+        TheoryStats::from([
+            ("constraints".to_string(), self.constraints.len() as u64),
+            ("checks".to_string(), self.stats.checks),
+            ("pivots".to_string(), self.stats.pivots),
+            ("conflicts".to_string(), self.stats.conflicts),
+            ("bound_updates".to_string(), self.stats.bound_updates),
+            ("overflows".to_string(), self.stats.overflows),
+        ])
+    }
+
+    fn clone_box(&self) -> Box<dyn Theory> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::state::Cause;
+
+    /// Checks that all literals of the conflict hold in the current state.
+    fn check_conflict(d: &Domains, explanation: &Explanation) {
+        assert!(!explanation.literals().is_empty());
+        for &l in explanation.literals() {
+            assert!(d.entails(l), "{l:?}");
+        }
+    }
+
+    #[test]
+    fn test_infeasible_sum() {
+        let mut d = Domains::new();
+        let x = d.new_var(2, 10);
+        let y = d.new_var(2, 10);
+        let z = d.new_var(0, 10);
+        let active = d.new_var(0, 1).geq(1);
+        let mut s = Simplex::new();
+        // x + y + z <= 5
+        s.add_constraint([(x, 1), (y, 1), (z, 1)], 5, Lit::TRUE);
+        // x - y - 2z >= 3   <=>  -x + y + 2z <= -3
+        s.add_constraint([(x, -1), (y, 1), (z, 2)], -3, active);
+        assert!(s.propagate(&mut d).is_ok());
+
+        d.save_state();
+        d.decide(active).unwrap();
+        // x + y <= 5 with y >= 2   =>  x <= 3,  and  x >= 3 + y >= 5
+        match s.propagate(&mut d) {
+            Err(Contradiction::Explanation(e)) => {
+                check_conflict(&d, &e);
+                assert!(e.literals().contains(&active));
+                assert!(e.literals().contains(&Lit::geq(y, 2)));
+            }
+            _ => panic!("Expected a conflict"),
+        }
+        d.restore_last();
+        assert!(s.propagate(&mut d).is_ok());
+        // x + y >= 4 leaves at most 1 for z
+        assert_eq!(d.ub(z), 1);
+
+        d.save_state();
+        d.set_lb(z, 1, Cause::Decision).unwrap();
+        assert!(s.propagate(&mut d).is_ok());
+        // 4 + z <= 5 is satisfiable with x = y = 2 and z = 1
+        assert_eq!((d.ub(x), d.ub(y)), (2, 2));
+    }
+
+    #[test]
+    fn test_bound_tightening() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let active = d.new_var(0, 1).geq(1);
+        let mut s = Simplex::new();
+        // x + 2y <= 8
+        s.add_constraint([(x, 1), (y, 2)], 8, active);
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!((d.ub(x), d.ub(y)), (10, 10));
+
+        d.save_state();
+        s.save_state();
+        d.decide(active).unwrap();
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!((d.ub(x), d.ub(y)), (8, 4));
+        d.set_lb(y, 3, Cause::Decision).unwrap();
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!(d.ub(x), 2);
+        // x <= 2 is explained by the activity of the constraint and the lower bound of y
+        let mut explanation = s.explanations.last().unwrap().clone();
+        explanation.sort();
+        let mut expected = vec![active, Lit::geq(y, 3)];
+        expected.sort();
+        assert_eq!(explanation, expected);
+
+        d.restore_last();
+        s.restore_last();
+        assert!(s.explanations.is_empty());
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!((d.ub(x), d.ub(y)), (10, 10));
+    }
+
+    #[test]
+    fn test_objective_bound() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let z = d.new_var(0, 20);
+        let mut s = Simplex::new();
+        // 2x + 2y <= 3
+        s.add_constraint([(x, 2), (y, 2)], 3, Lit::TRUE);
+        // z <= x + y
+        s.add_constraint([(x, -1), (y, -1), (z, 1)], 0, Lit::TRUE);
+        s.set_objective(z, false);
+        assert!(s.propagate(&mut d).is_ok());
+        // the bounds x <= 1 and y <= 1 only give z <= 2, while the relaxation has a maximum of 3/2 for z
+        assert_eq!(s.relaxed_value(z), Some(Ratio::new(3, 2)));
+        assert_eq!(d.ub(z), 1);
+        for explanation in &s.explanations {
+            assert!(explanation.iter().all(|&l| d.entails(l)));
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let mut s = Simplex::new();
+        s.add_constraint([(x, 1), (y, 1)], 5, Lit::TRUE);
+        assert!(s.propagate(&mut d).is_ok());
+        // a value at the limit of the rationals, that overflows on the next update of the slack variable
+        let slack = s.rows[0].basic;
+        s.values[slack] = Rat::from(i128::MAX);
+        d.set_lb(x, 1, Cause::Decision).unwrap();
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!(s.stats()["overflows"], 1);
+
+        // the reasoner gave up and neither tightens x <= 0 nor detects the infeasibility anymore
+        d.set_lb(y, 5, Cause::Decision).unwrap();
+        assert!(s.propagate(&mut d).is_ok());
+        assert_eq!(d.ub(x), 5);
+    }
+
+    #[test]
+    fn test_rational_relaxation() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let mut s = Simplex::new();
+        // 2x + 2y = 3  has a rational solution but no integer one
+        s.add_constraint([(x, 2), (y, 2)], 3, Lit::TRUE);
+        s.add_constraint([(x, -2), (y, -2)], -3, Lit::TRUE);
+        assert!(s.propagate(&mut d).is_ok());
+        let sum = s.relaxed_value(x).unwrap() + s.relaxed_value(y).unwrap();
+        assert_eq!(sum, Ratio::new(3, 2));
+
+        // x >= 1 and y >= 1 make it infeasible
+        d.set_lb(x, 1, Cause::Decision).unwrap();
+        d.set_lb(y, 1, Cause::Decision).unwrap();
+        let Err(Contradiction::Explanation(e)) = s.propagate(&mut d) else {
+            panic!("Expected a conflict")
+        };
+        check_conflict(&d, &e);
+        let mut lits = e.literals().to_vec();
+        lits.sort();
+        let mut expected = vec![Lit::geq(x, 1), Lit::geq(y, 1)];
+        expected.sort();
+        assert_eq!(lits, expected);
+    }
+}
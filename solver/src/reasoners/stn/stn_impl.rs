@@ -55,6 +55,13 @@ impl Stn {
         active_edge
     }
 
+    pub fn add_contingent_link(&mut self, source: Timepoint, target: Timepoint, lb: W, ub: W) {
+        let valid_link = self.get_conjunctive_scope(source, target);
+        let active_link = self.model.get_tautology_of_scope(valid_link);
+        self.stn
+            .add_contingent_link(active_link, source, target, lb, ub, &self.model.state)
+    }
+
     // add delay between optional variables
     pub fn add_delay(&mut self, a: impl Into<Timepoint>, b: impl Into<Timepoint>, delay: W) {
         self.add_edge(b.into(), a.into(), -delay);
@@ -1,5 +1,7 @@
 mod bound_propagation;
 mod contraint_db;
+mod controllability;
+mod dispatch;
 mod distances;
 mod edges;
 
@@ -9,7 +11,7 @@ use crate::collections::ref_store::{RefMap, RefVec};
 use crate::core::state::*;
 use crate::core::*;
 use crate::reasoners::stn::theory::Event::EdgeActivated;
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
 use contraint_db::*;
 use distances::{Graph, StnGraph};
 use edges::*;
@@ -19,6 +21,9 @@ use std::convert::*;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
+pub use controllability::Stnu;
+pub use dispatch::DispatchableStn;
+
 type ModelEvent = crate::core::state::Event;
 
 /// A temporal reference in an STN, i.e., reference to an absolute time.
@@ -30,6 +35,7 @@ pub type W = IntCst;
 pub static STN_THEORY_PROPAGATION: EnvParam<TheoryPropagationLevel> =
     EnvParam::new("ARIES_STN_THEORY_PROPAGATION", "bounds");
 pub static STN_EXTENSIVE_TESTS: EnvParam<bool> = EnvParam::new("ARIES_STN_EXTENSIVE_TESTS", "false");
+pub static STN_DYNAMIC_CONTROLLABILITY: EnvParam<bool> = EnvParam::new("ARIES_STN_DYNAMIC_CONTROLLABILITY", "false");
 
 /// Describes which part of theory propagation should be enabled.
 #[derive(Copy, Clone, Debug)]
@@ -87,6 +93,9 @@ pub struct StnConfig {
     pub theory_propagation: TheoryPropagationLevel,
     /// If true, extensive and very expensive tests will be made in debug mode.
     pub extensive_tests: bool,
+    /// If true, propagation will fail whenever the active edges and contingent links do not form a dynamically
+    /// controllable network.
+    pub dynamic_controllability: bool,
 }
 
 impl Default for StnConfig {
@@ -94,6 +103,7 @@ impl Default for StnConfig {
         StnConfig {
             theory_propagation: STN_THEORY_PROPAGATION.get(),
             extensive_tests: STN_EXTENSIVE_TESTS.get(),
+            dynamic_controllability: STN_DYNAMIC_CONTROLLABILITY.get(),
         }
     }
 }
@@ -184,6 +194,11 @@ pub struct StnTheory {
     /// A set of edges whose upper bound is dynamic (i.e. depends on the variable)
     /// The map is indexed on the variable from which the variable is computed.
     dyn_edges: hashbrown::HashMap<SignedVar, Vec<DynamicEdge>>,
+    /// Contingent links, whose duration is not under the control of the executive.
+    contingent_links: Vec<ContingentLink>,
+    /// Signature of the network (see [`StnTheory::controllability_signature`]) when it was last found to be
+    /// dynamically controllable. Cleared on backtrack.
+    last_controllability_check: Option<ControllabilitySignature>,
 }
 
 #[derive(Copy, Clone)]
@@ -265,6 +280,52 @@ struct DynamicEdge {
     ub_factor: IntCst,
 }
 
+/// A contingent link stating that `tgt - src`, in `[lb, ub]`, is chosen by nature (when `literal` is true).
+#[derive(Clone)]
+struct ContingentLink {
+    literal: Lit,
+    src: Timepoint,
+    tgt: Timepoint,
+    lb: W,
+    ub: W,
+}
+
+impl ContingentLink {
+    /// Returns an entailed literal that makes the link inactive, or `None` if the link may still be active.
+    fn inactivity_reason(&self, doms: &Domains) -> Option<Lit> {
+        [!self.literal, !doms.presence(self.src), !doms.presence(self.tgt)]
+            .into_iter()
+            .find(|&l| doms.entails(l))
+    }
+}
+
+/// Summary of the parts of the state that the dynamic controllability check depends on: the number of edge
+/// activations in the STN trail, the number of contingent links known to be active or inactive and the sum of
+/// the upper bounds of the edges whose upper bound is dynamic.
+///
+/// As all of them evolve monotonically between two backtracks, the network is unchanged as long as its signature is.
+type ControllabilitySignature = (EventIndex, usize, i64);
+
+/// Reason for a constraint of the STNU built when checking the dynamic controllability of the network.
+#[derive(Copy, Clone, Debug)]
+enum ControllabilityReason {
+    /// The constraint holds as long as all literals are true.
+    Literals([Lit; 4]),
+    /// The constraint is a root bound of the timepoint, that only holds because all contingent links targeting the
+    /// timepoint are inactive.
+    RootBound(Timepoint),
+}
+
+/// A difference constraint `target - source <= weight` that is active in a given state.
+#[derive(Copy, Clone, Debug)]
+struct ActiveConstraint {
+    source: Timepoint,
+    target: Timepoint,
+    weight: W,
+    /// Literals that entail the activity of the constraint (some of them might be tautologies).
+    reasons: [Lit; 4],
+}
+
 impl StnTheory {
     /// Creates a new STN. Initially, the STN contains a single timepoint
     /// representing the origin whose domain is `[0,0]`. The id of this timepoint can
@@ -284,6 +345,8 @@ impl StnTheory {
             last_disabling_timestamp: Default::default(),
             pending_bound_changes: Default::default(),
             dyn_edges: Default::default(),
+            contingent_links: Default::default(),
+            last_controllability_check: None,
         }
     }
     pub fn num_nodes(&self) -> u32 {
@@ -404,6 +467,196 @@ impl StnTheory {
         self.dyn_edges.entry(ub_var).or_default().push(dyn_edge);
     }
 
+    /// Adds a contingent link, representing the fact that, when `literal` is true, the duration `tgt - src` is in
+    /// `[lb, ub]` but chosen by nature rather than by the solver.
+    ///
+    /// For the purpose of propagation, the link is handled as the two edges `tgt - src <= ub` and `src - tgt <= -lb`
+    /// reified by `literal`, which should thus typically be a tautology in the scope of the two timepoints.
+    /// The contingent nature of the link is only exploited when checking the dynamic controllability of the network.
+    pub fn add_contingent_link(
+        &mut self,
+        literal: Lit,
+        src: impl Into<Timepoint>,
+        tgt: impl Into<Timepoint>,
+        lb: W,
+        ub: W,
+        domains: &Domains,
+    ) {
+        let src = src.into();
+        let tgt = tgt.into();
+        assert!(
+            0 <= lb && lb <= ub,
+            "Invalid bounds for a contingent link: [{lb}, {ub}]"
+        );
+        self.add_reified_edge(literal, src, tgt, ub, domains);
+        self.add_reified_edge(literal, tgt, src, -lb, domains);
+        self.contingent_links.push(ContingentLink {
+            literal,
+            src,
+            tgt,
+            lb,
+            ub,
+        });
+    }
+
+    /// Returns all difference constraints that are active in the given state and whose timepoints are both present.
+    fn active_constraints(&self, doms: &Domains) -> Vec<ActiveConstraint> {
+        let present = |tp: Timepoint| doms.present(tp) == Some(true);
+        let mut constraints = Vec::new();
+        for (_, prop) in self.constraints.propagators() {
+            // each constraint has a single forward view, from `plus(source)` to `plus(target)`
+            if !prop.source.is_plus() || !prop.target.is_plus() {
+                continue;
+            }
+            let (source, target) = (prop.source.variable(), prop.target.variable());
+            if !present(source) || !present(target) {
+                continue;
+            }
+            let enabler = prop
+                .enablers
+                .iter()
+                .find(|e| doms.entails(e.active) && doms.entails(e.valid));
+            if let Some(enabler) = enabler {
+                constraints.push(ActiveConstraint {
+                    source,
+                    target,
+                    weight: prop.weight,
+                    reasons: [
+                        enabler.active,
+                        enabler.valid,
+                        doms.presence(source),
+                        doms.presence(target),
+                    ],
+                });
+            }
+        }
+        // edges whose upper bound depends on a variable, only the edge for the initial upper bound is in the constraint database
+        for dyn_edge in self.dyn_edges.values().flatten() {
+            let (src, tgt) = (dyn_edge.src, dyn_edge.tgt);
+            if !present(src) || !present(tgt) || !present(dyn_edge.ub_var.variable()) {
+                continue;
+            }
+            let var_ub = doms.ub(dyn_edge.ub_var);
+            let ub = var_ub.saturating_mul(dyn_edge.ub_factor);
+            if ub < INT_CST_MAX {
+                constraints.push(ActiveConstraint {
+                    source: src,
+                    target: tgt,
+                    weight: ub,
+                    reasons: [
+                        dyn_edge.ub_var.leq(var_ub),
+                        doms.presence(dyn_edge.ub_var),
+                        doms.presence(src),
+                        doms.presence(tgt),
+                    ],
+                });
+            }
+        }
+        constraints
+    }
+
+    /// Checks whether the edges and contingent links that are active in the given state form a dynamically
+    /// controllable network, i.e., whether all timepoints that are not the target of a contingent link can be
+    /// scheduled at execution time, based only on the observation of the contingent timepoints that already occurred.
+    ///
+    /// Timepoints are constrained by their root domains, thus ignoring any bound that depends on a decision.
+    /// The domain of the target of an active contingent link is ignored, as its value is determined by nature.
+    /// The domain of the target of a contingent link that is not yet known to be active or inactive is ignored as
+    /// well, which is a relaxation of the network in both cases.
+    ///
+    /// If the network is not dynamically controllable, returns a set of entailed literals that imply
+    /// that it is not.
+    pub fn check_dynamic_controllability(&self, doms: &Domains) -> Result<(), Vec<Lit>> {
+        use ControllabilityReason::*;
+        let mut stnu = Stnu::new();
+        let present = |tp: Timepoint| doms.present(tp) == Some(true);
+        let mut timepoints = std::collections::BTreeSet::new();
+        for c in self.active_constraints(doms) {
+            stnu.add_requirement(c.source, c.target, c.weight, Literals(c.reasons));
+            timepoints.insert(c.source);
+            timepoints.insert(c.target);
+        }
+        for link in &self.contingent_links {
+            if doms.entails(link.literal) && present(link.src) && present(link.tgt) {
+                let reasons = [
+                    link.literal,
+                    doms.presence(link.src),
+                    doms.presence(link.tgt),
+                    Lit::TRUE,
+                ];
+                stnu.add_contingent_link(link.src, link.tgt, link.lb, link.ub, Literals(reasons));
+                timepoints.remove(&link.tgt);
+            } else if link.inactivity_reason(doms).is_none() {
+                // the link might still become active, do not constrain its target yet
+                timepoints.remove(&link.tgt);
+            }
+        }
+        for tp in timepoints {
+            let (lb, ub) = doms.root_bounds(tp);
+            if ub < INT_CST_MAX {
+                stnu.add_requirement(VarRef::ZERO, tp, ub, RootBound(tp));
+            }
+            if lb > INT_CST_MIN {
+                stnu.add_requirement(tp, VarRef::ZERO, -lb, RootBound(tp));
+            }
+        }
+        stnu.check_dynamic_controllability().map_err(|culprits| {
+            let mut explanation = Vec::new();
+            for culprit in culprits {
+                match culprit {
+                    Literals(reasons) => explanation.extend(reasons),
+                    RootBound(tp) => explanation.extend(
+                        self.contingent_links
+                            .iter()
+                            .filter(|link| link.tgt == tp)
+                            .filter_map(|link| link.inactivity_reason(doms)),
+                    ),
+                }
+            }
+            explanation.retain(|&l| l != Lit::TRUE);
+            explanation.sort();
+            explanation.dedup();
+            explanation
+        })
+    }
+
+    /// Returns the signature of the network whose dynamic controllability would be checked in the given state.
+    fn controllability_signature(&self, doms: &Domains) -> ControllabilitySignature {
+        let decided_links = self
+            .contingent_links
+            .iter()
+            .filter(|link| doms.entails(link.literal) || link.inactivity_reason(doms).is_some())
+            .count();
+        let dynamic_bounds = self
+            .dyn_edges
+            .values()
+            .flatten()
+            .map(|edge| doms.ub(edge.ub_var) as i64)
+            .sum();
+        (self.trail.next_event(), decided_links, dynamic_bounds)
+    }
+
+    /// If enabled, checks that the current network is dynamically controllable, unless no edge was activated and
+    /// no contingent link was decided since the last successful check.
+    fn propagate_controllability(&mut self, doms: &Domains) -> Result<(), Contradiction> {
+        if !self.config.dynamic_controllability || self.contingent_links.is_empty() {
+            return Ok(());
+        }
+        let state = self.controllability_signature(doms);
+        if self.last_controllability_check == Some(state) {
+            return Ok(());
+        }
+        self.check_dynamic_controllability(doms).map_err(|explanation| {
+            let mut expl = Explanation::with_capacity(explanation.len());
+            for l in explanation {
+                expl.push(l);
+            }
+            Contradiction::Explanation(expl)
+        })?;
+        self.last_controllability_check = Some(state);
+        Ok(())
+    }
+
     /// Creates and record a new propagator associated with the given [DirEdge], making sure
     /// to set up the watches to enable it when it becomes active and valid.
     fn record_propagator(&mut self, prop: Propagator, domains: &Domains) {
@@ -717,6 +970,7 @@ impl StnTheory {
             }
         });
         self.constraints.restore_last();
+        self.last_controllability_check = None;
 
         None
     }
@@ -813,6 +1067,22 @@ impl StnTheory {
         println!("# theory deactivations: {}", self.stats.num_theory_deactivations);
     }
 
+    pub fn stats(&self) -> TheoryStats {
+        let counters = [
+            ("nodes", self.num_nodes() as u64),
+            ("propagators", self.constraints.num_propagator_groups() as u64),
+            ("propagations", self.stats.num_propagations),
+            ("domain_updates", self.stats.bound_updates),
+            ("bound_deactivations", self.stats.num_bound_edge_deactivation),
+            ("theory_propagations", self.stats.num_theory_propagations),
+            ("theory_deactivations", self.stats.num_theory_deactivations),
+        ];
+        counters
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
     /// Perform the theory propagation that follows from the addition of the given edge.
     ///
     /// In essence, we find all shortest paths A -> B that contain the new edge.
@@ -886,7 +1156,8 @@ impl Theory for StnTheory {
     }
 
     fn propagate(&mut self, model: &mut Domains) -> Result<(), Contradiction> {
-        self.propagate_all(model)
+        self.propagate_all(model)?;
+        self.propagate_controllability(model)
     }
 
     fn explain(
@@ -944,6 +1215,10 @@ impl Theory for StnTheory {
         self.print_stats()
     }
 
+    fn stats(&self) -> TheoryStats {
+        self.stats()
+    }
+
     fn clone_box(&self) -> Box<dyn Theory> {
         Box::new(self.clone())
     }
@@ -1368,4 +1643,100 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_dispatchable_network() {
+        let s = &mut Stn::new();
+        let a = s.add_timepoint(0, 10);
+        let b = s.add_timepoint(0, 10);
+        let c = s.add_timepoint(0, 20);
+        let d = s.add_timepoint(0, 20);
+        // b in [a+2, a+5] and c in [b+1, b+4]
+        s.add_edge(a, b, 5);
+        s.add_delay(a, b, 2);
+        s.add_edge(b, c, 4);
+        s.add_delay(b, c, 1);
+        let ad = s.add_inactive_edge(a, d, 3);
+        s.assert_consistent();
+
+        // solution in which `d - a <= 3` does not hold
+        s.set_backtrack_point();
+        s.model.state.decide(!ad).unwrap();
+        for (tp, value) in [(a, 1), (b, 4), (c, 6), (d, 8)] {
+            s.set_lb(tp, value);
+            s.set_ub(tp, value);
+        }
+        s.assert_consistent();
+
+        let network = s.stn.dispatchable_network(&s.model.state);
+        assert_eq!(network.timepoints().len(), 5);
+        assert_eq!(network.window(a), Some((0, 8)));
+        assert_eq!(network.window(b), Some((2, 10)));
+        assert_eq!(network.window(c), Some((3, 14)));
+        assert_eq!(network.window(d), Some((4, 20)));
+        assert_eq!(network.distance(a, c), Some(9));
+        assert_eq!(network.distance(c, a), Some(-3));
+        assert_eq!(network.distance(d, a), Some(-4));
+        assert_eq!(network.distance(a, d), Some(20));
+    }
+
+    #[test]
+    fn test_dynamic_controllability() {
+        let s = &mut Stn::new_with_config(StnConfig {
+            dynamic_controllability: true,
+            ..Default::default()
+        });
+        let a = s.add_timepoint(0, 100);
+        let b = s.add_timepoint(0, 200);
+        let c = s.add_timepoint(0, 100);
+        // duration of `c - a` in [2, 10] is chosen by nature, `b` must wait for `c`
+        s.add_contingent_link(a, c, 2, 10);
+        s.add_delay(c, b, 0);
+        let deadline = s.add_inactive_edge(a, b, 5);
+        s.assert_consistent();
+        assert!(s.stn.check_dynamic_controllability(&s.model.state).is_ok());
+
+        // `b` must be executed at most 5 units after `a` which is not controllable, even though it is consistent
+        s.set_backtrack_point();
+        s.mark_active(deadline);
+        s.assert_consistent();
+        let culprits = s.stn.check_dynamic_controllability(&s.model.state).unwrap_err();
+        assert!(culprits.contains(&deadline));
+        match s.stn.propagate(&mut s.model.state) {
+            Err(Contradiction::Explanation(expl)) => assert!(expl.lits.contains(&deadline)),
+            _ => panic!("Expected a conflict"),
+        }
+
+        // relaxing the deadline makes the network controllable
+        s.undo_to_last_backtrack_point();
+        s.add_edge(a, b, 12);
+        assert!(s.stn.propagate(&mut s.model.state).is_ok());
+        assert!(s.stn.check_dynamic_controllability(&s.model.state).is_ok());
+    }
+
+    #[test]
+    fn test_dynamic_controllability_undecided_link() {
+        let s = &mut Stn::new_with_config(StnConfig {
+            dynamic_controllability: true,
+            ..Default::default()
+        });
+        let a = s.add_timepoint(0, 0);
+        let b = s.add_timepoint(0, 100);
+        let c = s.add_timepoint(0, 5);
+        // duration of `b - a` in [2, 10] is chosen by nature and `c` must not occur before `b`
+        s.add_contingent_link(a, b, 2, 10);
+        s.add_delay(b, c, 0);
+        // when active, the duration of `c - b` in [1, 2] is chosen by nature
+        let link = s.model.new_bvar("link").true_lit();
+        s.stn.add_contingent_link(link, b, c, 1, 2, &s.model.state);
+        s.assert_consistent();
+        assert_eq!(s.model.state.value(link), None);
+
+        // `c` might be contingent, so its deadline should not be considered until the link is decided
+        assert!(s.stn.check_dynamic_controllability(&s.model.state).is_ok());
+
+        s.mark_active(link);
+        s.assert_consistent();
+        assert!(s.stn.check_dynamic_controllability(&s.model.state).is_ok());
+    }
 }
@@ -0,0 +1,429 @@
+use crate::reasoners::stn::theory::{Timepoint, W};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A simple temporal network with uncertainty (STNU).
+///
+/// In addition to the requirement constraints of an STN (whose satisfaction is under the responsibility of the
+/// executive), the network contains contingent links: the duration between the two timepoints of a contingent link is
+/// chosen by nature within the bounds of the link, and only observed by the executive once the target timepoint occurs.
+///
+/// Each constraint is associated with a `Tag` that is used to report the constraints responsible for the
+/// network not being dynamically controllable.
+#[derive(Clone)]
+pub struct Stnu<Tag> {
+    nodes: HashMap<Timepoint, usize>,
+    num_nodes: usize,
+    /// Edges of the distance graph of the network in normal form (where all contingent links have a lower bound of 0).
+    edges: Vec<DistEdge>,
+    /// Tag of each constraint, indexed by the constraint id.
+    tags: Vec<Tag>,
+    /// Number of contingent links that were added.
+    num_links: usize,
+    /// Associates each contingent timepoint to the id of its contingent link.
+    contingent: HashMap<usize, usize>,
+}
+
+/// Kind of an edge in the distance graph of an STNU.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum EdgeKind {
+    Ordinary,
+    /// Lower-case edge from the activation timepoint to the contingent timepoint of the given link.
+    LowerCase(usize),
+    /// Upper-case edge from the contingent timepoint to the activation timepoint of the given link.
+    UpperCase(usize),
+}
+
+/// Edge of the distance graph, stating that `tgt - src <= weight`.
+#[derive(Clone, Debug)]
+struct DistEdge {
+    src: usize,
+    tgt: usize,
+    weight: i64,
+    kind: EdgeKind,
+    /// Ids of the constraints from which this edge was derived (sorted and without duplicates).
+    support: Vec<usize>,
+}
+
+impl<Tag: Clone> Default for Stnu<Tag> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Tag: Clone> Stnu<Tag> {
+    pub fn new() -> Self {
+        Stnu {
+            nodes: Default::default(),
+            num_nodes: 0,
+            edges: Vec::new(),
+            tags: Vec::new(),
+            num_links: 0,
+            contingent: Default::default(),
+        }
+    }
+
+    fn node(&mut self, tp: Timepoint) -> usize {
+        let next = self.num_nodes;
+        let node = *self.nodes.entry(tp).or_insert(next);
+        if node == next {
+            self.num_nodes += 1;
+        }
+        node
+    }
+
+    fn new_constraint(&mut self, tag: Tag) -> usize {
+        self.tags.push(tag);
+        self.tags.len() - 1
+    }
+
+    fn add_dist_edge(&mut self, src: usize, tgt: usize, weight: W, kind: EdgeKind, constraint: usize) {
+        self.edges.push(DistEdge {
+            src,
+            tgt,
+            weight: weight as i64,
+            kind,
+            support: vec![constraint],
+        })
+    }
+
+    /// Adds the requirement constraint `target - source <= weight`.
+    pub fn add_requirement(&mut self, source: Timepoint, target: Timepoint, weight: W, tag: Tag) {
+        let c = self.new_constraint(tag);
+        let (source, target) = (self.node(source), self.node(target));
+        self.add_dist_edge(source, target, weight, EdgeKind::Ordinary, c);
+    }
+
+    /// Adds a contingent link, stating that `target - source` is chosen by nature in `[lb, ub]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds are not such that `0 <= lb <= ub`, or if `target` is already the target of another
+    /// contingent link.
+    pub fn add_contingent_link(&mut self, source: Timepoint, target: Timepoint, lb: W, ub: W, tag: Tag) {
+        assert!(
+            0 <= lb && lb <= ub,
+            "Invalid bounds for a contingent link: [{lb}, {ub}]"
+        );
+        let c = self.new_constraint(tag);
+        let source = self.node(source);
+        let target = self.node(target);
+        assert!(
+            !self.contingent.contains_key(&target),
+            "Timepoint is already the target of a contingent link"
+        );
+        let link = self.num_links;
+        self.num_links += 1;
+        self.contingent.insert(target, link);
+
+        // put the link in normal form, i.e., with a null lower bound, by introducing an intermediate timepoint
+        // that is rigidly bound to the source
+        let activation = if lb > 0 {
+            let activation = self.num_nodes;
+            self.num_nodes += 1;
+            self.add_dist_edge(source, activation, lb, EdgeKind::Ordinary, c);
+            self.add_dist_edge(activation, source, -lb, EdgeKind::Ordinary, c);
+            activation
+        } else {
+            source
+        };
+        let ub = ub - lb;
+        self.add_dist_edge(activation, target, ub, EdgeKind::Ordinary, c);
+        self.add_dist_edge(target, activation, 0, EdgeKind::Ordinary, c);
+        self.add_dist_edge(activation, target, 0, EdgeKind::LowerCase(link), c);
+        self.add_dist_edge(target, activation, -ub, EdgeKind::UpperCase(link), c);
+    }
+
+    /// Checks whether the network is dynamically controllable, i.e., whether there exists a strategy to schedule the
+    /// timepoints that satisfies all requirements, based on the past observations of the contingent timepoints.
+    ///
+    /// If it is not, returns the tags of a subset of the constraints that is not dynamically controllable.
+    ///
+    /// This is the O(n^3) algorithm of Morris (2014), Dynamic Controllability and Dispatchability Relationships.
+    pub fn check_dynamic_controllability(&self) -> Result<(), Vec<Tag>> {
+        let mut checker = Checker::new(self.num_nodes, self.edges.clone());
+        for node in 0..self.num_nodes {
+            if checker.negative[node] {
+                if let Err(cycle) = checker.backpropagate(node) {
+                    let mut culprits = cycle.support;
+                    culprits.sort_unstable();
+                    culprits.dedup();
+                    return Err(culprits.into_iter().map(|c| self.tags[c].clone()).collect());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Status {
+    NotStarted,
+    InProgress,
+    Done,
+}
+
+/// A cycle of the distance graph that proves that the network is not dynamically controllable.
+struct Cycle {
+    /// Node at which the cycle was detected.
+    start: usize,
+    /// Constraints on the part of the cycle that was built so far.
+    support: Vec<usize>,
+    /// True if the whole cycle was built.
+    closed: bool,
+}
+
+struct Checker {
+    edges: Vec<DistEdge>,
+    incoming: Vec<Vec<usize>>,
+    /// For each node, true if it has an incoming negative edge.
+    negative: Vec<bool>,
+    status: Vec<Status>,
+}
+
+impl Checker {
+    fn new(num_nodes: usize, edges: Vec<DistEdge>) -> Self {
+        let mut incoming = vec![Vec::new(); num_nodes];
+        let mut negative = vec![false; num_nodes];
+        for (id, e) in edges.iter().enumerate() {
+            incoming[e.tgt].push(id);
+            if e.weight < 0 {
+                negative[e.tgt] = true;
+            }
+        }
+        Checker {
+            edges,
+            incoming,
+            negative,
+            status: vec![Status::NotStarted; num_nodes],
+        }
+    }
+
+    /// Propagates backward the negative edges incoming into `source`, adding the non-negative edges derived in the
+    /// process (Morris 2014, DCbackprop).
+    fn backpropagate(&mut self, source: usize) -> Result<(), Cycle> {
+        match self.status[source] {
+            Status::InProgress => {
+                return Err(Cycle {
+                    start: source,
+                    support: Vec::new(),
+                    closed: false,
+                })
+            }
+            Status::Done => return Ok(()),
+            Status::NotStarted => {}
+        }
+        self.status[source] = Status::InProgress;
+
+        let n = self.incoming.len();
+        let mut dist = vec![i64::MAX; n];
+        // edge through which each node reaches the source
+        let mut preds: Vec<Option<usize>> = vec![None; n];
+        // contingent link whose upper-case edge starts the path of each node
+        let mut origin: Vec<Option<usize>> = vec![None; n];
+        let mut done = vec![false; n];
+        let mut queue = BinaryHeap::new();
+
+        dist[source] = 0;
+        for &e in &self.incoming[source] {
+            let edge = &self.edges[e];
+            if edge.weight < 0 && edge.weight < dist[edge.src] {
+                dist[edge.src] = edge.weight;
+                preds[edge.src] = Some(e);
+                origin[edge.src] = match edge.kind {
+                    EdgeKind::UpperCase(link) => Some(link),
+                    _ => None,
+                };
+                queue.push(Reverse((edge.weight, edge.src)));
+            }
+        }
+
+        while let Some(Reverse((d, u))) = queue.pop() {
+            if done[u] || d != dist[u] {
+                continue;
+            }
+            done[u] = true;
+            if d >= 0 {
+                let mut support = Vec::new();
+                self.support_of(u, source, &preds, &mut support);
+                self.add_edge(u, source, d, support);
+                continue;
+            }
+            if self.negative[u] {
+                if let Err(mut cycle) = self.backpropagate(u) {
+                    if !cycle.closed {
+                        self.support_of(u, source, &preds, &mut cycle.support);
+                        cycle.closed = cycle.start == source;
+                    }
+                    return Err(cycle);
+                }
+            }
+            // note that the recursive call may have added new edges into `u`
+            for i in 0..self.incoming[u].len() {
+                let e = self.incoming[u][i];
+                let edge = &self.edges[e];
+                if edge.weight < 0 {
+                    continue;
+                }
+                if let EdgeKind::LowerCase(link) = edge.kind {
+                    if origin[u] == Some(link) {
+                        // lower-case edge that is unsuitable to extend a path starting with the upper-case edge of
+                        // the same contingent link
+                        continue;
+                    }
+                }
+                let v = edge.src;
+                let new = d + edge.weight;
+                if !done[v] && new < dist[v] {
+                    dist[v] = new;
+                    preds[v] = Some(e);
+                    origin[v] = origin[u];
+                    queue.push(Reverse((new, v)));
+                }
+            }
+        }
+        self.status[source] = Status::Done;
+        Ok(())
+    }
+
+    /// Collects the constraints supporting the path from `node` to `source` (possibly looping back to `source`).
+    fn support_of(&self, node: usize, source: usize, preds: &[Option<usize>], out: &mut Vec<usize>) {
+        let mut curr = node;
+        loop {
+            let e = preds[curr].expect("No path to the source");
+            out.extend_from_slice(&self.edges[e].support);
+            curr = self.edges[e].tgt;
+            if curr == source {
+                break;
+            }
+        }
+    }
+
+    fn add_edge(&mut self, src: usize, tgt: usize, weight: i64, mut support: Vec<usize>) {
+        support.sort_unstable();
+        support.dedup();
+        let id = self.edges.len();
+        self.edges.push(DistEdge {
+            src,
+            tgt,
+            weight,
+            kind: EdgeKind::Ordinary,
+            support,
+        });
+        self.incoming[tgt].push(id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::VarRef;
+
+    fn tp(i: u32) -> Timepoint {
+        VarRef::from(i)
+    }
+
+    #[test]
+    fn test_negative_cycle() {
+        let (a, b, c) = (tp(1), tp(2), tp(3));
+        let mut stnu = Stnu::new();
+        stnu.add_requirement(a, b, 1, "ab");
+        stnu.add_requirement(b, a, -2, "ba");
+        stnu.add_requirement(a, c, 5, "ac");
+        assert_eq!(stnu.check_dynamic_controllability(), Err(vec!["ab", "ba"]));
+    }
+
+    #[test]
+    fn test_waiting() {
+        // `b` must occur at most one unit before `c`, which is observed between 1 and 3 units after `a`
+        let (a, b, c) = (tp(1), tp(2), tp(3));
+        let mut stnu = Stnu::new();
+        stnu.add_contingent_link(a, c, 1, 3, "link");
+        stnu.add_requirement(c, b, 0, "b <= c");
+        stnu.add_requirement(b, c, 1, "c <= b + 1");
+        assert_eq!(stnu.check_dynamic_controllability(), Ok(()));
+
+        // `b` must occur exactly one unit before `c`
+        let mut stnu = Stnu::new();
+        stnu.add_contingent_link(a, c, 1, 3, "link");
+        stnu.add_requirement(c, b, -1, "b <= c - 1");
+        stnu.add_requirement(b, c, 1, "c <= b + 1");
+        stnu.add_requirement(a, b, 10, "other");
+        let mut culprits = stnu.check_dynamic_controllability().unwrap_err();
+        culprits.sort();
+        assert_eq!(culprits, vec!["b <= c - 1", "c <= b + 1", "link"]);
+
+        // same, but without uncertainty
+        let mut stnu = Stnu::new();
+        stnu.add_contingent_link(a, c, 3, 3, "link");
+        stnu.add_requirement(c, b, -1, "b <= c - 1");
+        stnu.add_requirement(b, c, 1, "c <= b + 1");
+        assert_eq!(stnu.check_dynamic_controllability(), Ok(()));
+    }
+
+    #[test]
+    fn test_deadline() {
+        // `c` must occur before 10 and `a` cannot start before 4
+        let (z, a, c) = (tp(0), tp(1), tp(2));
+        let mut stnu = Stnu::new();
+        stnu.add_contingent_link(a, c, 2, 5, "link");
+        stnu.add_requirement(z, c, 10, "deadline");
+        stnu.add_requirement(a, z, -4, "release");
+        assert_eq!(stnu.check_dynamic_controllability(), Ok(()));
+
+        // with a later release date, the deadline may be missed if the duration is too long
+        let mut stnu = Stnu::new();
+        stnu.add_contingent_link(a, c, 2, 5, "link");
+        stnu.add_requirement(z, c, 10, "deadline");
+        stnu.add_requirement(a, z, -6, "release");
+        let mut culprits = stnu.check_dynamic_controllability().unwrap_err();
+        culprits.sort();
+        assert_eq!(culprits, vec!["deadline", "link", "release"]);
+    }
+
+    /// Without contingent links, a network is dynamically controllable iff it has no negative cycle.
+    #[test]
+    fn test_consistency() {
+        use rand::prelude::*;
+        for seed in 0..500 {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let num_nodes = rng.gen_range(2..8u32);
+            let edges: Vec<(u32, u32, W)> = (0..rng.gen_range(1..15))
+                .map(|_| {
+                    let src = rng.gen_range(0..num_nodes);
+                    let tgt = rng.gen_range(0..num_nodes);
+                    (src, tgt, rng.gen_range(-10..=10))
+                })
+                .collect();
+            let mut stnu = Stnu::new();
+            for (i, &(src, tgt, w)) in edges.iter().enumerate() {
+                stnu.add_requirement(tp(src), tp(tgt), w, i);
+            }
+
+            // Bellman-Ford from a virtual source connected to all nodes
+            let mut dist = vec![0; num_nodes as usize];
+            for _ in 0..num_nodes {
+                for &(src, tgt, w) in &edges {
+                    dist[tgt as usize] = dist[tgt as usize].min(dist[src as usize] + w);
+                }
+            }
+            let consistent = edges
+                .iter()
+                .all(|&(src, tgt, w)| dist[src as usize] + w >= dist[tgt as usize]);
+
+            match stnu.check_dynamic_controllability() {
+                Ok(()) => assert!(consistent),
+                Err(culprits) => {
+                    assert!(!consistent);
+                    // the culprits alone must form an inconsistent network
+                    let mut sub = Stnu::new();
+                    for i in culprits {
+                        let (src, tgt, w) = edges[i];
+                        sub.add_requirement(tp(src), tp(tgt), w, i);
+                    }
+                    assert!(sub.check_dynamic_controllability().is_err());
+                }
+            }
+        }
+    }
+}
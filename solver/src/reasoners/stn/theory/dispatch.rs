@@ -0,0 +1,163 @@
+use crate::core::state::Domains;
+use crate::core::*;
+use crate::reasoners::stn::theory::distances::{Edge, Graph};
+use crate::reasoners::stn::theory::{StnTheory, Timepoint, W};
+use std::collections::{BTreeSet, HashMap};
+
+/// Minimal network of the simple temporal network (STN) that is satisfied by a solution.
+///
+/// For each pair of timepoints `(a, b)`, it gives the tightest upper bound on `b - a` that is implied by the
+/// difference constraints active in the solution. Because it is the all-pairs shortest path network of the STN,
+/// it is dispatchable: an executive can schedule the timepoints one after the other, only propagating to the
+/// neighbours of each scheduled timepoint, without ever needing to backtrack.
+///
+/// Timepoint bounds are represented as constraints relative to the origin ([VarRef::ZERO]).
+#[derive(Clone, Debug)]
+pub struct DispatchableStn {
+    /// All timepoints of the network, the first one being the origin.
+    timepoints: Vec<Timepoint>,
+    indices: HashMap<Timepoint, usize>,
+    /// `distances[i][j]` is the upper bound of `timepoints[j] - timepoints[i]`, if any.
+    distances: Vec<Vec<Option<W>>>,
+}
+
+impl DispatchableStn {
+    /// All timepoints of the network, starting with the origin.
+    pub fn timepoints(&self) -> &[Timepoint] {
+        &self.timepoints
+    }
+
+    /// Returns true if the timepoint is part of the network.
+    pub fn contains(&self, tp: Timepoint) -> bool {
+        self.indices.contains_key(&tp)
+    }
+
+    /// Returns the tightest upper bound on `to - from`, or `None` if the difference is unconstrained
+    /// or if one of the timepoints is not part of the network.
+    pub fn distance(&self, from: Timepoint, to: Timepoint) -> Option<W> {
+        let from = *self.indices.get(&from)?;
+        let to = *self.indices.get(&to)?;
+        self.distances[from][to]
+    }
+
+    /// Returns the `[min, max]` window in which the timepoint can be executed, with respect to the origin.
+    /// Returns `None` if the timepoint is not part of the network.
+    pub fn window(&self, tp: Timepoint) -> Option<(W, W)> {
+        if !self.contains(tp) {
+            return None;
+        }
+        let min = self.distance(tp, VarRef::ZERO).map_or(INT_CST_MIN, |d| -d);
+        let max = self.distance(VarRef::ZERO, tp).unwrap_or(INT_CST_MAX);
+        Some((min, max))
+    }
+
+    /// Iterates over all constraints `(from, to, d)` of the network, each stating that `to - from <= d`.
+    pub fn edges(&self) -> impl Iterator<Item = (Timepoint, Timepoint, W)> + '_ {
+        self.distances.iter().enumerate().flat_map(move |(i, row)| {
+            row.iter().enumerate().filter_map(move |(j, d)| match d {
+                Some(d) if i != j => Some((self.timepoints[i], self.timepoints[j], *d)),
+                _ => None,
+            })
+        })
+    }
+}
+
+/// Difference constraints extracted from the STN, with the values of a solution as the potential function.
+struct SolutionGraph<'a> {
+    solution: &'a Domains,
+    /// Edges `(source, target, weight)` stating that `target - source <= weight`
+    edges: Vec<(Timepoint, Timepoint, W)>,
+    outgoing: HashMap<Timepoint, Vec<usize>>,
+    incoming: HashMap<Timepoint, Vec<usize>>,
+}
+
+impl<'a> SolutionGraph<'a> {
+    fn new(solution: &'a Domains) -> Self {
+        SolutionGraph {
+            solution,
+            edges: Vec::new(),
+            outgoing: Default::default(),
+            incoming: Default::default(),
+        }
+    }
+
+    fn add_edge(&mut self, source: Timepoint, target: Timepoint, weight: W) {
+        debug_assert!(self.solution.lb(target) - self.solution.lb(source) <= weight);
+        let id = self.edges.len();
+        self.edges.push((source, target, weight));
+        self.outgoing.entry(source).or_default().push(id);
+        self.incoming.entry(target).or_default().push(id);
+    }
+}
+
+impl<'a> Graph<Timepoint, usize> for SolutionGraph<'a> {
+    fn vertices(&self) -> impl Iterator<Item = Timepoint> + '_ {
+        let vertices: BTreeSet<Timepoint> = self.edges.iter().flat_map(|&(s, t, _)| [s, t]).collect();
+        vertices.into_iter()
+    }
+
+    fn outgoing(&self, v: Timepoint) -> impl Iterator<Item = Edge<Timepoint, usize>> + '_ {
+        self.outgoing.get(&v).into_iter().flatten().map(|&e| self.edge(e))
+    }
+
+    fn incoming(&self, v: Timepoint) -> impl Iterator<Item = Edge<Timepoint, usize>> + '_ {
+        self.incoming.get(&v).into_iter().flatten().map(|&e| self.edge(e))
+    }
+
+    fn potential(&self, v: Timepoint) -> IntCst {
+        // all constraints are satisfied by the solution, which makes its values a valid potential function
+        self.solution.lb(v)
+    }
+
+    fn edge(&self, e: usize) -> Edge<Timepoint, usize> {
+        let (source, target, weight) = self.edges[e];
+        Edge::new(source, target, weight, e)
+    }
+}
+
+impl StnTheory {
+    /// Extracts the network of difference constraints that are active in the given solution
+    /// and returns its minimal (dispatchable) network.
+    ///
+    /// The network contains all present timepoints that appear in an active edge, together with the origin.
+    /// The bounds of each timepoint are those of the root level of the solution, so that the resulting network
+    /// only depends on the choices made in the solution and not on the timepoints values.
+    ///
+    /// Only the constraints handled by the STN are considered: timepoints that are involved in other constraints
+    /// (e.g. linear constraints) may need to be fixed to their value in the solution.
+    ///
+    /// The `solution` is assumed to be a complete assignment where all present variables are bound.
+    pub fn dispatchable_network(&self, solution: &Domains) -> DispatchableStn {
+        let mut graph = SolutionGraph::new(solution);
+        for c in self.active_constraints(solution) {
+            graph.add_edge(c.source, c.target, c.weight);
+        }
+
+        // constrain all timepoints with their bounds at the root
+        let timepoints: BTreeSet<Timepoint> = graph.vertices().filter(|&tp| tp != VarRef::ZERO).collect();
+        for &tp in &timepoints {
+            let (lb, ub) = solution.root_bounds(tp);
+            if ub < INT_CST_MAX {
+                graph.add_edge(VarRef::ZERO, tp, ub);
+            }
+            if lb > INT_CST_MIN {
+                graph.add_edge(tp, VarRef::ZERO, -lb);
+            }
+        }
+
+        let timepoints: Vec<Timepoint> = std::iter::once(VarRef::ZERO).chain(timepoints).collect();
+        let indices: HashMap<Timepoint, usize> = timepoints.iter().enumerate().map(|(i, &tp)| (tp, i)).collect();
+        let distances = timepoints
+            .iter()
+            .map(|&from| {
+                let from_distances = graph.distances_from(from);
+                timepoints.iter().map(|to| from_distances.get(to).copied()).collect()
+            })
+            .collect();
+        DispatchableStn {
+            timepoints,
+            indices,
+            distances,
+        }
+    }
+}
@@ -165,6 +165,33 @@ pub(super) trait Graph<V: Copy, E: Copy> {
         self.ssp(src, tgt).map(|(dist, _preds)| dist)
     }
 
+    /// Returns the distance through the shortest path from `src` to all vertices reachable from it.
+    fn distances_from(&self, src: V) -> HashMap<V, IntCst>
+    where
+        V: Ord + Hash,
+    {
+        let mut distances = HashMap::new();
+        // this is a max heap, so we will store the negation of computed (reduced) distances
+        let mut heap = BinaryHeap::new();
+
+        heap.push((-0, src));
+
+        while let Some((neg_dist, curr)) = heap.pop() {
+            if distances.contains_key(&curr) {
+                // already treated, ignore
+                continue;
+            }
+            let reduced_dist = -neg_dist;
+            distances.insert(curr, reduced_dist - self.potential(src) + self.potential(curr));
+            for out in self.outgoing(curr) {
+                let reduced_cost = self.potential(out.src) + out.weight - self.potential(out.tgt);
+                debug_assert!(reduced_cost >= 0);
+                heap.push((neg_dist - reduced_cost, out.tgt));
+            }
+        }
+        distances
+    }
+
     /// Returns the (unordered!) set of vertices on a shortest path between the two vertices.
     /// Returns `None` if there is no path.
     fn shortest_path(&self, src: V, tgt: V) -> Option<Vec<E>>
@@ -895,11 +922,26 @@ mod test {
 
             if let Some(dist) = dist {
                 let path = path.unwrap();
-                let path_dist = path.into_iter().map(|e| graph.edge(e).weight).sum();
+                let path_dist: IntCst = path.into_iter().map(|e| graph.edge(e).weight).sum();
                 assert_eq!(dist, path_dist);
             } else {
                 assert!(path.is_none());
             }
         }
     }
+
+    /// Tests that the single-source distances are the same as the ones of individual shortest paths
+    #[test]
+    fn test_distances_from() {
+        let graphs = (0..1000).map(gen_graph).collect_vec();
+
+        for graph in graphs {
+            for orig in graph.vertices() {
+                let distances = graph.distances_from(orig);
+                for dest in graph.vertices() {
+                    assert_eq!(distances.get(&dest).copied(), graph.shortest_distance(orig, dest));
+                }
+            }
+        }
+    }
 }
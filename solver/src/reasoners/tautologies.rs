@@ -1,7 +1,7 @@
 use crate::backtrack::{Backtrack, DecLvl};
 use crate::core::state::{Cause, Domains, DomainsSnapshot, Explanation, InferenceCause};
 use crate::core::Lit;
-use crate::reasoners::{Contradiction, ReasonerId, Theory};
+use crate::reasoners::{Contradiction, ReasonerId, Theory, TheoryStats};
 
 /// A reasoner that holds a set of tautologies (single literals that are known to be true)
 /// and propagates them at every decision level.
@@ -73,6 +73,10 @@ impl Theory for Tautologies {
 
     fn print_stats(&self) {}
 
+    fn stats(&self) -> TheoryStats {
+        TheoryStats::new()
+    }
+
     fn clone_box(&self) -> Box<dyn Theory> {
         Box::new(self.clone())
     }
@@ -2,11 +2,15 @@ use crate::core::literals::Disjunction;
 use crate::core::state::{Domains, OptDomain};
 use crate::core::{IntCst, Lit, SignedVar, VarRef};
 use crate::model::lang::alternative::NFAlternative;
+use crate::model::lang::circuit::NFCircuit;
+use crate::model::lang::euf::Apply;
 use crate::model::lang::linear::NFLinearLeq;
 use crate::model::lang::max::NFEqMax;
 use crate::model::lang::mul::NFEqVarMulLit;
+use crate::model::lang::regular::NFRegular;
 use crate::model::lang::ValidityScope;
 use crate::model::{Label, Model};
+use itertools::Itertools;
 use std::fmt::{Debug, Formatter};
 use std::ops::Not;
 
@@ -34,6 +38,9 @@ pub enum ReifExpr {
     Alternative(NFAlternative),
     EqMax(NFEqMax),
     EqVarMulLit(NFEqVarMulLit),
+    Circuit(NFCircuit),
+    Regular(NFRegular),
+    Apply(Apply),
 }
 
 impl std::fmt::Display for ReifExpr {
@@ -51,6 +58,9 @@ impl std::fmt::Display for ReifExpr {
             ReifExpr::EqMax(em) => write!(f, "{em:?}"),
             ReifExpr::Alternative(alt) => write!(f, "{alt:?}"),
             ReifExpr::EqVarMulLit(em) => write!(f, "{em:?}"),
+            ReifExpr::Circuit(c) => write!(f, "{c:?}"),
+            ReifExpr::Regular(r) => write!(f, "{r:?}"),
+            ReifExpr::Apply(app) => write!(f, "{app:?}"),
         }
     }
 }
@@ -79,6 +89,9 @@ impl ReifExpr {
             ReifExpr::Alternative(alt) => ValidityScope::new([presence(alt.main)], []),
             ReifExpr::EqMax(eq_max) => ValidityScope::new([presence(eq_max.lhs.variable())], []),
             ReifExpr::EqVarMulLit(em) => ValidityScope::new([presence(em.lhs)], []),
+            ReifExpr::Circuit(c) => ValidityScope::new(c.variables().map(presence), []),
+            ReifExpr::Regular(r) => ValidityScope::new(r.variables().map(presence), []),
+            ReifExpr::Apply(app) => ValidityScope::new(app.variables().map(presence), []),
         }
     }
 
@@ -86,7 +99,12 @@ impl ReifExpr {
     pub fn negatable(&self) -> bool {
         !matches!(
             self,
-            ReifExpr::Alternative(_) | ReifExpr::EqMax(_) | ReifExpr::EqVarMulLit(_)
+            ReifExpr::Alternative(_)
+                | ReifExpr::EqMax(_)
+                | ReifExpr::EqVarMulLit(_)
+                | ReifExpr::Circuit(_)
+                | ReifExpr::Regular(_)
+                | ReifExpr::Apply(_)
         )
     }
 
@@ -220,6 +238,27 @@ impl ReifExpr {
                     Some(value(*lhs) == lit_value * value(*rhs))
                 }
             }
+            ReifExpr::Circuit(c) => {
+                let succ = |i: usize| c.arcs[i].iter().positions(|&l| lvalue(l)).exactly_one().ok();
+                Some(c.is_valid(succ))
+            }
+            ReifExpr::Regular(r) => {
+                let word = r
+                    .values
+                    .iter()
+                    .map(|values| {
+                        values
+                            .iter()
+                            .filter(|&&(_, l)| lvalue(l))
+                            .map(|&(v, _)| v)
+                            .exactly_one()
+                            .ok()
+                    })
+                    .collect::<Option<Vec<_>>>();
+                Some(word.is_some_and(|word| r.dfa.accepts(word)))
+            }
+            // functional consistency relates several applications and cannot be checked on a single one
+            ReifExpr::Apply(_) => Some(true),
         }
     }
 }
@@ -273,6 +312,9 @@ impl Not for ReifExpr {
             ReifExpr::Alternative(_) => panic!("Alternative is a constraint and cannot be negated"),
             ReifExpr::EqMax(_) => panic!("EqMax is a constraint and cannot be negated"),
             ReifExpr::EqVarMulLit(_) => panic!("EqVarMulLit is a constraint and cannot be negated"),
+            ReifExpr::Circuit(_) => panic!("Circuit is a constraint and cannot be negated"),
+            ReifExpr::Regular(_) => panic!("Regular is a constraint and cannot be negated"),
+            ReifExpr::Apply(_) => panic!("Apply is a constraint and cannot be negated"),
         }
     }
 }
@@ -291,6 +333,17 @@ impl DifferenceExpression {
         assert_ne!(a, VarRef::ZERO);
         DifferenceExpression { b, a, ub }
     }
+
+    /// Builds the difference expression `b - a <= ub` where `a` and `b` are signed variables with the same sign.
+    pub fn signed(b: SignedVar, a: SignedVar, ub: IntCst) -> Self {
+        assert_eq!(b.is_plus(), a.is_plus());
+        if b.is_plus() {
+            DifferenceExpression::new(b.variable(), a.variable(), ub)
+        } else {
+            // (-x) - (-y) <= ub    <=>   y - x <= ub
+            DifferenceExpression::new(a.variable(), b.variable(), ub)
+        }
+    }
 }
 
 impl Debug for DifferenceExpression {
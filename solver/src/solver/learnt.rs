@@ -0,0 +1,185 @@
+//! Export and import of learnt clauses, to reuse the work of a solver on a similar problem.
+//!
+//! Clauses are exported with `Solver::export_learnt_clauses`, where each variable is identified by its label
+//! rather than by its `VarRef`, so that they can be imported with `Solver::import_learnt_clauses` into a solver built
+//! independently from another model whose variables have the same labels.
+//!
+//! A learnt clause is a logical consequence of the constraints of the model it was learnt from.
+//! It thus remains valid in any model that contains the same constraints on the labeled variables, possibly with
+//! additional ones (e.g. more goals or new orders). Clauses should not be imported in a model where some constraints
+//! were relaxed, or after a search that added non-removable clauses to the model
+//! (as done by `Solver::enumerate` and `Solver::pareto_front`).
+//!
+//! Clauses learnt after breaking symmetries (see `Solver::symmetry_breaking`) may be consequences of the lex-leader
+//! constraints rather than of the model and would remove solutions from another solver.
+//! `Solver::export_learnt_clauses` thus exports no clause from a solver whose symmetries were broken.
+//!
+//! With the `serde` feature, clauses can be saved to and loaded from JSON documents.
+
+use crate::core::{IntCst, Lit, Relation};
+use crate::model::{Label, Model};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "serde")]
+use std::io::{Read, Write};
+
+/// A literal `var <relation> value` where the variable is identified by its label.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabeledLit<Lbl> {
+    pub var: Lbl,
+    pub relation: Relation,
+    pub value: IntCst,
+}
+
+/// A set of learnt clauses (disjunctions of literals) whose variables are identified by their labels.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LearntClauses<Lbl> {
+    pub clauses: Vec<Vec<LabeledLit<Lbl>>>,
+}
+
+impl<Lbl: Label> LearntClauses<Lbl> {
+    /// Builds the labeled version of the clauses.
+    /// Clauses with a literal on a variable without a unique label are dropped.
+    pub fn from_clauses(clauses: impl IntoIterator<Item = Vec<Lit>>, model: &Model<Lbl>) -> Self {
+        let clauses = clauses
+            .into_iter()
+            .filter_map(|clause| clause.into_iter().map(|l| labeled(l, model)).collect())
+            .collect();
+        LearntClauses { clauses }
+    }
+
+    /// Returns the clauses on the variables of the model.
+    /// Clauses with a literal on a label that does not identify a single variable of the model are dropped.
+    pub fn to_clauses<'a>(&'a self, model: &'a Model<Lbl>) -> impl Iterator<Item = Vec<Lit>> + 'a {
+        self.clauses
+            .iter()
+            .filter_map(|clause| clause.iter().map(|l| unlabeled(l, model)).collect())
+    }
+
+    pub fn num_clauses(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Writes the clauses as a JSON document.
+    #[cfg(feature = "serde")]
+    pub fn write_json(&self, out: impl Write) -> anyhow::Result<()>
+    where
+        Lbl: Serialize,
+    {
+        serde_json::to_writer(out, self)?;
+        Ok(())
+    }
+
+    /// Reads clauses from a JSON document, as written by [`LearntClauses::write_json`].
+    #[cfg(feature = "serde")]
+    pub fn read_json(input: impl Read) -> anyhow::Result<Self>
+    where
+        Lbl: DeserializeOwned,
+    {
+        Ok(serde_json::from_reader(input)?)
+    }
+}
+
+fn labeled<Lbl: Label>(lit: Lit, model: &Model<Lbl>) -> Option<LabeledLit<Lbl>> {
+    let (var, relation, value) = lit.unpack();
+    let label = model.shape.labels.get(var)?;
+    if model.shape.labels.variables_with_label(label).len() != 1 {
+        return None; // the label is ambiguous
+    }
+    Some(LabeledLit {
+        var: label.clone(),
+        relation,
+        value,
+    })
+}
+
+fn unlabeled<Lbl: Label>(lit: &LabeledLit<Lbl>, model: &Model<Lbl>) -> Option<Lit> {
+    let &[var] = model.shape.labels.variables_with_label(&lit.var) else {
+        return None; // unknown or ambiguous label
+    };
+    Some(match lit.relation {
+        Relation::Leq => Lit::leq(var, lit.value),
+        Relation::Gt => Lit::gt(var, lit.value),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::lang::expr::or;
+    use crate::solver::Solver;
+    use itertools::Itertools;
+
+    type Model = crate::model::Model<String>;
+
+    /// Pigeon-hole problem with `n` pigeons and `n - 1` holes (unsatisfiable), where the variable stating that
+    /// the i-th pigeon is in the j-th hole is labeled `{prefix}{i}-{j}` for the first pigeon and `p{i}-{j}` for the others.
+    fn pigeons(n: usize, prefix: &str) -> Model {
+        let mut model = Model::new();
+        let vars = (0..n)
+            .map(|i| {
+                let name = if i == 0 { prefix } else { "p" };
+                (0..n - 1)
+                    .map(|j| model.new_bvar(format!("{name}{i}-{j}")).true_lit())
+                    .collect_vec()
+            })
+            .collect_vec();
+        for pigeon in &vars {
+            model.enforce(or(pigeon.clone()), []);
+        }
+        for j in 0..n - 1 {
+            for (a, b) in vars.iter().tuple_combinations() {
+                model.enforce(or([!a[j], !b[j]]), []);
+            }
+        }
+        model
+    }
+
+    #[test]
+    fn test_export_import() {
+        let mut solver = Solver::new(pigeons(6, "p"));
+        assert!(solver.solve().unwrap().is_none());
+        let learnt = solver.export_learnt_clauses();
+        assert!(learnt.num_clauses() > 0);
+
+        // round trip through JSON
+        #[cfg(feature = "serde")]
+        let learnt = {
+            use super::LearntClauses;
+            let mut json = Vec::new();
+            learnt.write_json(&mut json).unwrap();
+            LearntClauses::<String>::read_json(json.as_slice()).unwrap()
+        };
+        assert_eq!(learnt, solver.export_learnt_clauses());
+
+        // same labels, all clauses are imported as forgettable clauses
+        let mut solver = Solver::new(pigeons(6, "p"));
+        assert_eq!(solver.import_learnt_clauses(&learnt), learnt.num_clauses());
+        assert_eq!(solver.reasoners.sat.clauses.num_learnt(), learnt.num_clauses());
+        assert_eq!(solver.reasoners.sat.clauses.num_removable(), learnt.num_clauses());
+        assert!(solver.solve().unwrap().is_none());
+
+        // the first pigeon is relabeled, clauses on it are dropped
+        let num_on_first = learnt
+            .clauses
+            .iter()
+            .filter(|clause| clause.iter().any(|l| l.var.starts_with("p0-")))
+            .count();
+        let mut solver = Solver::new(pigeons(6, "q"));
+        assert_eq!(
+            solver.import_learnt_clauses(&learnt),
+            learnt.num_clauses() - num_on_first
+        );
+        assert!(solver.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_no_export_with_broken_symmetries() {
+        let mut solver = Solver::new(pigeons(6, "p"));
+        solver.symmetry_breaking = true;
+        assert!(solver.solve().unwrap().is_none());
+        assert!(solver.reasoners.sat.clauses.num_learnt() > 0);
+        assert_eq!(solver.export_learnt_clauses().num_clauses(), 0);
+    }
+}
@@ -1,6 +1,8 @@
+pub mod learnt;
 pub mod parallel;
 pub mod search;
 pub mod stats;
+pub mod trace;
 
 mod solver_impl;
 pub use solver_impl::*;
@@ -1,4 +1,6 @@
 mod parallel_solver;
+#[cfg(feature = "serde")]
+pub mod portfolio;
 pub mod signals;
 
 pub use parallel_solver::*;
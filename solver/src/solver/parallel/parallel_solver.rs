@@ -2,8 +2,10 @@ use crate::model::extensions::{AssignmentExt, SavedAssignment, Shaped};
 use crate::model::lang::IAtom;
 use crate::model::{Label, ModelShape};
 use crate::solver::parallel::signals::{InputSignal, InputStream, OutputSignal, SolverOutput, ThreadID};
-use crate::solver::{Exit, Solver};
+use crate::solver::stats::StatsReport;
+use crate::solver::{Exit, ParetoPoint, Solver};
 use crossbeam_channel::{select, Receiver, Sender};
+use std::convert::Infallible;
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -11,6 +13,8 @@ use std::time::{Duration, Instant};
 pub struct ParSolver<Lbl> {
     base_model: ModelShape<Lbl>,
     solvers: Vec<Worker<Lbl>>,
+    /// Index of the worker that provided the final result of the last run, if any.
+    winner: Option<usize>,
 }
 
 pub type Solution = Arc<SavedAssignment>;
@@ -83,20 +87,39 @@ impl<Lbl: Label> ParSolver<Lbl> {
     ///
     /// All solvers will be based on a clone of `base_solver`, on which the provided `adapt` function
     /// will be called to allow its customisation.
-    pub fn new(mut base_solver: Box<Solver<Lbl>>, num_workers: usize, adapt: impl Fn(usize, &mut Solver<Lbl>)) -> Self {
+    pub fn new(base_solver: Box<Solver<Lbl>>, num_workers: usize, adapt: impl Fn(usize, &mut Solver<Lbl>)) -> Self {
+        let res = Self::try_new(base_solver, num_workers, |i, s| {
+            adapt(i, s);
+            Ok::<(), Infallible>(())
+        });
+        match res {
+            Ok(solver) => solver,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Creates a new parallel solver, where the customisation of each solver may fail.
+    ///
+    /// Returns the first error raised by the `adapt` function, if any.
+    pub fn try_new<E>(
+        mut base_solver: Box<Solver<Lbl>>,
+        num_workers: usize,
+        adapt: impl Fn(usize, &mut Solver<Lbl>) -> Result<(), E>,
+    ) -> Result<Self, E> {
         let mut solver = ParSolver {
             base_model: base_solver.model.shape.clone(),
             solvers: Vec::with_capacity(num_workers),
+            winner: None,
         };
         for i in 0..(num_workers - 1) {
             let mut s = base_solver.clone();
-            adapt(i, &mut s);
+            adapt(i, &mut s)?;
             solver.solvers.push(Worker::Idle(s));
         }
-        adapt(num_workers - 1, &mut base_solver);
+        adapt(num_workers - 1, &mut base_solver)?;
         solver.solvers.push(Worker::Idle(base_solver));
 
-        solver
+        Ok(solver)
     }
 
     /// Sets the output of all solvers to a particular channel and return its receiving end.
@@ -116,7 +139,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
 
     /// Solve the problem that was given on initialization using all available solvers.
     pub fn solve(&mut self, deadline: Option<Instant>) -> SolverResult<Solution> {
-        self.race_solvers(|s| s.solve(), |_| {}, deadline)
+        self.race_solvers(|s| s.solve(), Some, deadline)
     }
 
     /// Minimize the value of the given expression.
@@ -128,7 +151,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
                 Ok(None) => Ok(None),
                 Err(x) => Err(x),
             },
-            |_| {},
+            Some,
             deadline,
         )
     }
@@ -155,9 +178,10 @@ impl<Lbl: Label> ParSolver<Lbl> {
                 None => true,
             };
             if is_improvement {
-                on_improved_solution(ass);
+                on_improved_solution(ass.clone());
                 previous_best = Some(obj_value)
             }
+            Some(ass)
         };
         self.race_solvers(
             move |s| match s.minimize(objective) {
@@ -170,15 +194,62 @@ impl<Lbl: Label> ParSolver<Lbl> {
         )
     }
 
+    /// Enumerates the Pareto front for the given objectives, that are all minimized (see [`Solver::pareto_front`]).
+    ///
+    /// Each solution found by a worker is passed to `on_point` with a `false` flag, unless it is dominated by a
+    /// previous one. Once a worker has completed the front, all its points are passed to `on_point` with a `true` flag.
+    /// On timeout, the non-dominated solutions found so far are returned (without any proof of optimality).
+    pub fn pareto_front(
+        &mut self,
+        objectives: &[IAtom],
+        mut on_point: impl FnMut(&ParetoPoint, bool),
+        deadline: Option<Instant>,
+    ) -> SolverResult<Vec<ParetoPoint>> {
+        let objectives = objectives.to_vec();
+        // non-dominated solutions found so far
+        let mut found: Vec<ParetoPoint> = Vec::new();
+        let on_new_sol = |ass: Solution| {
+            let point = ParetoPoint::new(&objectives, ass);
+            if !found.iter().any(|p| p.weakly_dominates(&point)) {
+                found.retain(|p| !point.weakly_dominates(p));
+                on_point(&point, false);
+                found.push(point);
+            }
+            Some(found.clone())
+        };
+        let result = self.race_solvers(
+            {
+                let objectives = objectives.clone();
+                move |s| match s.pareto_front(&objectives, |_, _| {}) {
+                    Ok(front) if front.is_empty() => Ok(None),
+                    Ok(front) => Ok(Some(front)),
+                    Err(x) => Err(x),
+                }
+            },
+            on_new_sol,
+            deadline,
+        );
+        if let SolverResult::Sol(front) = &result {
+            for point in front {
+                on_point(point, true);
+            }
+        }
+        result
+    }
+
     /// Generic function to run a lambda in parallel on all available solvers and return the result of the
     /// first finishing one.
     ///
+    /// Each solution found by a worker is passed to `on_new_sol`, that may return an intermediate result
+    /// to be returned in case of a timeout.
+    ///
     /// This function also setups inter-solver communication to enable clause/solution sharing.
     /// Once a first result is found, it sends an interruption message to all other workers and wait for them to yield.
-    fn race_solvers<F, G>(&mut self, run: F, mut on_new_sol: G, deadline: Option<Instant>) -> SolverResult<Solution>
+    fn race_solvers<O, F, G>(&mut self, run: F, mut on_new_sol: G, deadline: Option<Instant>) -> SolverResult<O>
     where
-        F: Fn(&mut Solver<Lbl>) -> Result<Option<Solution>, Exit> + Send + 'static + Copy,
-        G: FnMut(Solution),
+        O: Send + 'static,
+        F: Fn(&mut Solver<Lbl>) -> Result<Option<O>, Exit> + Send + 'static + Clone,
+        G: FnMut(Solution) -> Option<O>,
     {
         // a receiver that will collect all intermediates results (incumbent solution and learned clauses)
         // from the solvers
@@ -188,15 +259,15 @@ impl<Lbl: Label> ParSolver<Lbl> {
         let (result_snd, result_rcv) = crossbeam_channel::unbounded();
 
         // lambda used to start a thread and run a solver on it.
-        let spawn =
-            |id: usize, mut solver: Box<Solver<Lbl>>, result_snd: Sender<WorkerResult<Option<Solution>, Lbl>>| {
-                thread::spawn(move || {
-                    let output = run(&mut solver);
-                    let answer = WorkerResult { id, output, solver };
-                    // ignore message delivery failures (on another solver might have found the solution earlier)
-                    let _ = result_snd.send(answer);
-                });
-            };
+        let spawn = |id: usize, mut solver: Box<Solver<Lbl>>, result_snd: Sender<WorkerResult<Option<O>, Lbl>>| {
+            let run = run.clone();
+            thread::spawn(move || {
+                let output = run(&mut solver);
+                let answer = WorkerResult { id, output, solver };
+                // ignore message delivery failures (on another solver might have found the solution earlier)
+                let _ = result_snd.send(answer);
+            });
+        };
 
         let mut solvers_inputs = Vec::with_capacity(self.solvers.len());
 
@@ -208,6 +279,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
         }
 
         let mut status = SolverStatus::Pending;
+        self.winner = None;
 
         while self.is_worker_running() {
             let time_left = if let Some(deadline) = deadline {
@@ -235,6 +307,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
                             }
                         };
                         status = SolverStatus::Final(result);
+                        self.winner = Some(worker_id);
                         for s in &mut self.solvers {
                             s.interrupt()
                         }
@@ -245,8 +318,9 @@ impl<Lbl: Label> ParSolver<Lbl> {
                         self.share_among_solvers(&msg);
                         if !matches!(status, SolverStatus::Final(_)) {
                             if let OutputSignal::SolutionFound(assignment) = msg.msg {
-                                on_new_sol(assignment.clone());
-                                status = SolverStatus::Intermediate(assignment);
+                                if let Some(intermediate) = on_new_sol(assignment) {
+                                    status = SolverStatus::Intermediate(intermediate);
+                                }
                             }
                         }
                     }
@@ -297,6 +371,29 @@ impl<Lbl: Label> ParSolver<Lbl> {
         }
     }
 
+    /// Returns the statistics of all workers, in the order in which they were created.
+    /// Workers that are still running are ignored.
+    pub fn stats_reports(&self) -> Vec<StatsReport> {
+        self.solvers
+            .iter()
+            .filter_map(|solver| match solver {
+                Worker::Idle(solver) => Some(solver.stats_report()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the solver of the worker that provided the final result of the last run, e.g., to inspect its
+    /// reasoners after solving.
+    ///
+    /// Returns `None` if the last run was not concluded by a worker (e.g. on a timeout).
+    pub fn winning_solver(&self) -> Option<&Solver<Lbl>> {
+        match self.solvers.get(self.winner?)? {
+            Worker::Idle(solver) => Some(solver.as_ref()),
+            _ => None,
+        }
+    }
+
     /// Prints the statistics of all solvers.
     pub fn print_stats(&self) {
         for (id, solver) in self.solvers.iter().enumerate() {
@@ -323,3 +420,55 @@ enum SolverStatus<Sol> {
     /// A final result was provided by at least one solver.
     Final(SolverResult<Sol>),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::lang::linear::LinearSum;
+    use crate::model::Model;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_winning_solver() {
+        let mut model: Model<String> = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 10, "y");
+        model.enforce((LinearSum::from(x) + y).geq(8), []);
+        let mut solver = ParSolver::new(Box::new(Solver::new(model)), 2, |_, _| {});
+        assert!(solver.winning_solver().is_none());
+
+        let SolverResult::Sol(sol) = solver.minimize(x, None) else {
+            panic!("No solution")
+        };
+        let winner = solver.winning_solver().expect("No winning solver");
+        assert_eq!(sol.lb(x), 0);
+        assert_eq!(winner.model.shape.labels.get(x.into()), Some(&"x".to_string()));
+    }
+
+    #[test]
+    fn test_parallel_pareto_front() {
+        let mut model: Model<String> = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 10, "y");
+        model.enforce((LinearSum::from(x) + y).geq(8), []);
+        let mut solver = ParSolver::new(Box::new(Solver::new(model)), 2, |_, _| {});
+
+        let proven = Mutex::new(Vec::new());
+        let result = solver.pareto_front(
+            &[x.into(), y.into()],
+            |p, is_proven| {
+                if is_proven {
+                    proven.lock().unwrap().push(p.objectives.clone())
+                }
+            },
+            None,
+        );
+        let SolverResult::Sol(front) = result else {
+            panic!("No front")
+        };
+        let mut points = front.iter().map(|p| p.objectives.clone()).collect::<Vec<_>>();
+        points.sort();
+        assert_eq!(points, (0..=8).map(|i| vec![i, 8 - i]).collect::<Vec<_>>());
+        assert_eq!(proven.lock().unwrap().len(), 9);
+    }
+}
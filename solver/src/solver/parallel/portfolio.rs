@@ -0,0 +1,473 @@
+//! Declarative description of the strategies run by the workers of a `ParSolver`.
+//!
+//! This module is only available with the `serde` feature (enabled by default).
+//!
+//! A portfolio is typically loaded from a TOML file, where each `[[worker]]` table describes
+//! the configuration of one solver:
+//!
+//! ```toml
+//! [[worker]]
+//! brancher = { type = "activity" }
+//! restarts = { policy = "geometric", period = 100, factor = 1.5 }
+//! stn-propagation = "bounds"
+//!
+//! [[worker]]
+//! brancher = { type = "conflict", options = "+lrb:+sol" }
+//! phase-saving = true
+//! clauses = { locked-lbd-level = 2, tier2-lbd-level = 6 }
+//! chronological-backtracking = 100
+//!
+//! [[worker]]
+//! brancher = { type = "random", seed = 3 }
+//! symmetry-breaking = true
+//!
+//! [[worker]]
+//! brancher = { type = "failure-directed" }
+//! domain-splitting = true
+//! ```
+//!
+//! Binaries that have domain specific strategies can expose them through the `custom` brancher,
+//! (e.g. `brancher = { type = "custom", name = "causal" }`) whose name is resolved by the caller
+//! of [`Portfolio::build_solver`].
+
+use crate::core::{Lit, VarRef};
+use crate::model::extensions::AssignmentExt;
+use crate::model::{Label, Model};
+use crate::reasoners::stn::theory::TheoryPropagationLevel;
+use crate::solver::parallel::ParSolver;
+use crate::solver::search::activity::ActivityBrancher;
+use crate::solver::search::combinators::CombinatorExt;
+use crate::solver::search::conflicts::{ConflictBasedBrancher, Params};
+use crate::solver::search::fds::FailureDirectedSearch;
+use crate::solver::search::lexical::Lexical;
+use crate::solver::search::random::RandomChoice;
+use crate::solver::search::{Brancher, SearchControl};
+use crate::solver::Solver;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A set of solver configurations, one for each worker of a parallel solver.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Portfolio {
+    #[serde(rename = "worker")]
+    pub workers: Vec<WorkerConfig>,
+}
+
+/// Configuration of a single solver in a portfolio.
+///
+/// All fields are optional: an absent field leaves the corresponding parameter of the solver untouched.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WorkerConfig {
+    /// Search strategy of the worker.
+    #[serde(default)]
+    pub brancher: BrancherConfig,
+    /// Restart policy. If absent, the default policy of the brancher is used.
+    pub restarts: Option<RestartPolicy>,
+    /// Level of theory propagation of the difference logic reasoner.
+    #[serde(default, deserialize_with = "from_str_opt")]
+    pub stn_propagation: Option<TheoryPropagationLevel>,
+    /// Parameters of the learnt clause database.
+    #[serde(default)]
+    pub clauses: ClausesConfig,
+    /// Whether the brancher should prefer the value a variable last had during search.
+    /// Only supported by the conflict-based brancher.
+    pub phase_saving: Option<bool>,
+    /// Whether decisions should split the domain of integer variables in two halves instead of fixing their value.
+    pub domain_splitting: Option<bool>,
+    /// If set, the worker backtracks chronologically when a backjump would undo more than this number of decision levels.
+    pub chronological_backtracking: Option<u32>,
+    /// Whether the worker breaks the symmetries of the model with lex-leader constraints before the search.
+    pub symmetry_breaking: Option<bool>,
+}
+
+/// Branching strategies that can be selected in a portfolio.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, tag = "type", rename_all = "kebab-case")]
+pub enum BrancherConfig {
+    /// Activity-based search (default brancher of the solver).
+    #[default]
+    Activity,
+    /// Conflict-based search on all boolean variables, followed by a lexical assignment of the remaining ones.
+    Conflict {
+        /// Options of the brancher, in the syntax accepted by `conflicts::Params::configure` (e.g. `"+lrb:+sol"`).
+        #[serde(default)]
+        options: String,
+    },
+    /// Assigns all variables in lexical order.
+    Lexical {
+        #[serde(default)]
+        prefer: LexicalValue,
+    },
+    /// Selects variables and values randomly.
+    Random {
+        #[serde(default)]
+        seed: u64,
+    },
+    /// Failure-directed search on the labeled variables, intended for proving infeasibility or optimality.
+    FailureDirected,
+    /// A domain-specific strategy, whose name is interpreted by the application.
+    Custom { name: String },
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LexicalValue {
+    #[default]
+    Min,
+    Max,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields, tag = "policy", rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Never restart.
+    None,
+    /// Restarts after `period` conflicts, the period being multiplied by `factor` after each restart.
+    Geometric { period: u64, factor: f32 },
+}
+
+/// Overrides for the `ClausesParams` of the SAT reasoner.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ClausesConfig {
+    pub cla_inc: Option<f64>,
+    pub cla_decay: Option<f64>,
+    pub locked_lbd_level: Option<u32>,
+    pub tier2_lbd_level: Option<u32>,
+}
+
+/// Deserializes an optional field from its string representation.
+fn from_str_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = String::deserialize(deserializer)?;
+    T::from_str(&s).map(Some).map_err(serde::de::Error::custom)
+}
+
+impl Portfolio {
+    /// Reads a portfolio from a TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Could not read portfolio {}", path.display()))?;
+        Portfolio::from_str(&content).with_context(|| format!("Invalid portfolio {}", path.display()))
+    }
+
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Builds a parallel solver with one worker for each configuration of the portfolio.
+    ///
+    /// The `custom` function is called on each worker with a `custom` brancher and is responsible for
+    /// setting up the strategy with the given name. It should return an error if the name is unknown.
+    pub fn build_solver<Lbl: Label>(
+        &self,
+        base_solver: Box<Solver<Lbl>>,
+        custom: impl Fn(&str, &mut Solver<Lbl>) -> Result<()>,
+    ) -> Result<ParSolver<Lbl>> {
+        ParSolver::try_new(base_solver, self.num_workers(), |id, s| {
+            self.workers[id]
+                .apply(s, &custom)
+                .with_context(|| format!("Invalid configuration for worker {id}"))
+        })
+    }
+}
+
+impl FromStr for Portfolio {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let portfolio: Portfolio = toml::from_str(s)?;
+        if portfolio.workers.is_empty() {
+            bail!("The portfolio has no worker");
+        }
+        for (id, worker) in portfolio.workers.iter().enumerate() {
+            worker
+                .validate()
+                .with_context(|| format!("Invalid configuration for worker {id}"))?;
+        }
+        Ok(portfolio)
+    }
+}
+
+impl WorkerConfig {
+    /// Checks that the options of the worker are compatible with each other.
+    fn validate(&self) -> Result<()> {
+        if let BrancherConfig::Conflict { options } = &self.brancher {
+            let mut params = Params::default();
+            for opt in options.split(':') {
+                if !params.configure(opt) {
+                    bail!("Unsupported option for the conflict-based brancher: {opt}");
+                }
+            }
+        } else if self.phase_saving.is_some() {
+            bail!("Phase saving is only supported by the conflict-based brancher");
+        }
+        if let Some(RestartPolicy::Geometric { factor, .. }) = self.restarts {
+            if factor < 1.0 {
+                bail!("The restart factor must be greater or equal to 1 (got {factor})");
+            }
+        }
+        Ok(())
+    }
+
+    /// Configures the solver according to this worker configuration.
+    pub fn apply<Lbl: Label>(
+        &self,
+        solver: &mut Solver<Lbl>,
+        custom: impl Fn(&str, &mut Solver<Lbl>) -> Result<()>,
+    ) -> Result<()> {
+        self.validate()?;
+        match &self.brancher {
+            BrancherConfig::Activity => {
+                let mut brancher = ActivityBrancher::new();
+                // the activity brancher has a built-in geometric restart policy that we directly configure
+                match self.restarts {
+                    Some(RestartPolicy::None) => brancher.params.allowed_conflicts = u64::MAX,
+                    Some(RestartPolicy::Geometric { period, factor }) => {
+                        brancher.params.allowed_conflicts = period;
+                        brancher.params.increase_ratio_for_allowed_conflicts = factor;
+                    }
+                    None => {}
+                }
+                solver.set_brancher(brancher);
+            }
+            BrancherConfig::Conflict { options } => {
+                let mut params = Params::default();
+                for opt in options.split(':') {
+                    params.configure(opt);
+                }
+                if let Some(phase_saving) = self.phase_saving {
+                    params.value_selection.phase_saving = phase_saving;
+                }
+                let decision_lits = boolean_decisions(&solver.model);
+                let brancher: Brancher<Lbl> = Box::new(ConflictBasedBrancher::with(decision_lits, params));
+                // conflict-based search only takes care of boolean variables, bind the others afterward
+                let brancher = brancher.and_then(Lexical::with_min().clone_to_box());
+                solver.set_brancher_boxed(self.with_restarts(brancher));
+            }
+            BrancherConfig::Lexical { prefer } => {
+                let brancher = match prefer {
+                    LexicalValue::Min => Lexical::with_min(),
+                    LexicalValue::Max => Lexical::with_max(),
+                };
+                solver.set_brancher_boxed(self.with_restarts(brancher.clone_to_box()));
+            }
+            BrancherConfig::Random { seed } => {
+                let brancher = RandomChoice::new(*seed).clone_to_box();
+                solver.set_brancher_boxed(self.with_restarts(brancher));
+            }
+            BrancherConfig::FailureDirected => {
+                let brancher: Brancher<Lbl> = Box::new(FailureDirectedSearch::new(labeled_variables(&solver.model)));
+                // failure-directed search only takes care of the variables of the user, bind the others afterward
+                let brancher = brancher.and_then(Lexical::with_min().clone_to_box());
+                solver.set_brancher_boxed(self.with_restarts(brancher));
+            }
+            BrancherConfig::Custom { name } => {
+                custom(name, solver)?;
+                if self.restarts.is_some() {
+                    let brancher = solver.brancher.clone_to_box();
+                    solver.set_brancher_boxed(self.with_restarts(brancher));
+                }
+            }
+        }
+
+        if self.domain_splitting == Some(true) {
+            let brancher = solver.brancher.clone_to_box();
+            solver.set_brancher_boxed(brancher.with_domain_splitting());
+        }
+
+        if let Some(threshold) = self.chronological_backtracking {
+            solver.chronological_backtracking = Some(threshold);
+        }
+
+        if let Some(symmetry_breaking) = self.symmetry_breaking {
+            solver.symmetry_breaking = symmetry_breaking;
+        }
+
+        // reasoners parameters are set last so that they take precedence over the ones of a custom strategy
+        if let Some(level) = self.stn_propagation {
+            solver.reasoners.diff.config.theory_propagation = level;
+        }
+        let clause_params = &mut solver.reasoners.sat.clauses.params;
+        if let Some(cla_inc) = self.clauses.cla_inc {
+            clause_params.cla_inc = cla_inc;
+        }
+        if let Some(cla_decay) = self.clauses.cla_decay {
+            clause_params.cla_decay = cla_decay;
+        }
+        if let Some(lvl) = self.clauses.locked_lbd_level {
+            clause_params.locked_lbd_level = lvl;
+        }
+        if let Some(lvl) = self.clauses.tier2_lbd_level {
+            clause_params.tier2_lbd_level = lvl;
+        }
+        Ok(())
+    }
+
+    /// Wraps the brancher with the restart policy of the worker, if any.
+    fn with_restarts<Lbl: 'static>(&self, brancher: Brancher<Lbl>) -> Brancher<Lbl> {
+        match self.restarts {
+            Some(RestartPolicy::Geometric { period, factor }) => brancher.with_restarts(period, factor),
+            Some(RestartPolicy::None) | None => brancher,
+        }
+    }
+}
+
+/// Returns all variables of the model that have a label, i.e., that were introduced by the user.
+fn labeled_variables<Lbl: Label>(model: &Model<Lbl>) -> Vec<VarRef> {
+    model
+        .state
+        .variables()
+        .filter(|&v| model.shape.labels.get(v).is_some())
+        .collect()
+}
+
+/// Returns a decision literal `v >= 1` for each boolean variable of the model.
+fn boolean_decisions<Lbl: Label>(model: &Model<Lbl>) -> Vec<Lit> {
+    model
+        .state
+        .variables()
+        .filter(|&v| {
+            let dom = model.var_domain(v);
+            dom.lb == 0 && dom.ub == 1
+        })
+        .map(|v| v.geq(1))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::lang::expr::{leq, or};
+
+    type Model = crate::model::Model<&'static str>;
+
+    #[test]
+    fn test_parse_portfolio() {
+        let portfolio = Portfolio::from_str(
+            r#"
+            [[worker]]
+            brancher = { type = "activity" }
+            restarts = { policy = "geometric", period = 100, factor = 1.5 }
+            stn-propagation = "edges"
+
+            [[worker]]
+            brancher = { type = "conflict", options = "+lrb:+sol" }
+            phase-saving = false
+            clauses = { locked-lbd-level = 2, tier2-lbd-level = 8 }
+            chronological-backtracking = 100
+
+            [[worker]]
+            brancher = { type = "random", seed = 3 }
+            restarts = { policy = "none" }
+            symmetry-breaking = true
+
+            [[worker]]
+            brancher = { type = "custom", name = "my-strategy" }
+
+            [[worker]]
+            brancher = { type = "failure-directed" }
+            domain-splitting = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(portfolio.num_workers(), 5);
+        assert!(matches!(
+            portfolio.workers[0].stn_propagation,
+            Some(TheoryPropagationLevel::Edges)
+        ));
+        assert!(matches!(portfolio.workers[1].brancher, BrancherConfig::Conflict { .. }));
+        assert_eq!(portfolio.workers[1].clauses.locked_lbd_level, Some(2));
+        assert_eq!(portfolio.workers[1].clauses.tier2_lbd_level, Some(8));
+        assert_eq!(portfolio.workers[1].chronological_backtracking, Some(100));
+        assert!(matches!(
+            portfolio.workers[2].brancher,
+            BrancherConfig::Random { seed: 3 }
+        ));
+        assert!(matches!(portfolio.workers[2].restarts, Some(RestartPolicy::None)));
+        assert_eq!(portfolio.workers[2].symmetry_breaking, Some(true));
+        assert!(matches!(portfolio.workers[4].brancher, BrancherConfig::FailureDirected));
+        assert_eq!(portfolio.workers[4].domain_splitting, Some(true));
+
+        // errors
+        assert!(Portfolio::from_str("").is_err());
+        assert!(Portfolio::from_str("[[worker]]\nbrancher = { type = \"unknown\" }").is_err());
+        assert!(Portfolio::from_str("[[worker]]\nbrancher = { type = \"conflict\", options = \"+xx\" }").is_err());
+        assert!(Portfolio::from_str("[[worker]]\nphase-saving = true").is_err());
+        assert!(Portfolio::from_str("[[worker]]\nstn-propagation = \"some\"").is_err());
+    }
+
+    #[test]
+    fn test_portfolio_solver() {
+        let mut model = Model::new();
+        let a = model.new_bvar("a").true_lit();
+        let b = model.new_bvar("b").true_lit();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 10, "y");
+        model.enforce(or([a, b]), []);
+        model.enforce(leq(x, y), []);
+        model.enforce(leq(y, 7), [a]);
+
+        let portfolio = Portfolio::from_str(
+            r#"
+            [[worker]]
+            brancher = { type = "activity" }
+            [[worker]]
+            brancher = { type = "conflict" }
+            [[worker]]
+            brancher = { type = "lexical", prefer = "max" }
+            [[worker]]
+            brancher = { type = "random", seed = 1 }
+            restarts = { policy = "geometric", period = 10, factor = 1.2 }
+            [[worker]]
+            brancher = { type = "custom", name = "lexical" }
+            [[worker]]
+            brancher = { type = "failure-directed" }
+            [[worker]]
+            brancher = { type = "lexical" }
+            domain-splitting = true
+            [[worker]]
+            brancher = { type = "activity" }
+            chronological-backtracking = 0
+            [[worker]]
+            brancher = { type = "conflict" }
+            symmetry-breaking = true
+            "#,
+        )
+        .unwrap();
+
+        let custom = |name: &str, s: &mut Solver<&'static str>| match name {
+            "lexical" => {
+                s.set_brancher(Lexical::with_min());
+                Ok(())
+            }
+            _ => bail!("Unknown strategy {name}"),
+        };
+        for id in 0..portfolio.num_workers() {
+            // run each worker in isolation to check that its configuration is complete
+            let worker = Portfolio {
+                workers: vec![portfolio.workers[id].clone()],
+            };
+            let solver = Box::new(Solver::new(model.clone()));
+            let mut par = worker.build_solver(solver, custom).unwrap();
+            let result = par.minimize(y, None);
+            match result {
+                crate::solver::parallel::SolverResult::Sol(sol) => assert_eq!(sol.var_domain(y).lb, 0),
+                _ => panic!(),
+            }
+        }
+
+        let bad = Portfolio::from_str("[[worker]]\nbrancher = { type = \"custom\", name = \"unknown\" }").unwrap();
+        assert!(bad.build_solver(Box::new(Solver::new(model)), custom).is_err());
+    }
+}
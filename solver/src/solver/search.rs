@@ -1,6 +1,7 @@
 pub mod activity;
 pub mod combinators;
 pub mod conflicts;
+pub mod fds;
 pub mod lexical;
 pub mod random;
 
@@ -38,6 +39,10 @@ pub trait SearchControl<Lbl>: Backtrack {
     /// Notifies the search control that a new assignment has been found (either by itself or by an other solver running in parallel).
     fn new_assignment_found(&mut self, objective_value: IntCst, assignment: std::sync::Arc<SavedAssignment>) {}
 
+    /// Provides a hint that the variable should take the given value, e.g., because it had it in a previous solution.
+    /// The search control may use it as a preferred value when branching on the variable.
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {}
+
     /// Invoked by search immediately before saving the state
     fn pre_save_state(&mut self, _model: &Model<Lbl>) {}
 
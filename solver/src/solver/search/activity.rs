@@ -189,6 +189,12 @@ impl<Lbl: Label> ActivityBrancher<Lbl> {
                     .get(v)
                     .copied()
                     .unwrap_or(if self.params.prefer_min_value { lb } else { ub });
+                // a preferred value in a hole of a sparse domain is replaced by the closest value of the domain
+                let value = if lb < value && value < ub {
+                    model.state.closest_value(v, value)
+                } else {
+                    value
+                };
 
                 let literal = if value < lb || value > ub {
                     if self.params.prefer_min_value {
@@ -482,6 +488,10 @@ impl<Lbl: Label> SearchControl<Lbl> for ActivityBrancher<Lbl> {
         }
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.set_default_value(var, value)
+    }
+
     fn conflict(
         &mut self,
         clause: &Conflict,
@@ -1,6 +1,6 @@
 use crate::backtrack::{Backtrack, DecLvl};
 use crate::core::state::{Conflict, Explainer};
-use crate::core::IntCst;
+use crate::core::{IntCst, Lit, VarRef};
 use crate::model::extensions::SavedAssignment;
 use crate::model::Model;
 use crate::solver::search::{Brancher, Decision, SearchControl};
@@ -16,6 +16,9 @@ pub trait CombinatorExt<L> {
 
     /// Creates a brancher that extends `self` to have geometric restarts.
     fn with_restarts(self, allowed_conflicts: u64, increase_ratio: f32) -> Brancher<L>;
+
+    /// Creates a brancher that replaces the decisions of `self` by a dichotomic split of the domain of the variable.
+    fn with_domain_splitting(self) -> Brancher<L>;
 }
 
 impl<L: 'static> CombinatorExt<L> for Brancher<L> {
@@ -26,6 +29,10 @@ impl<L: 'static> CombinatorExt<L> for Brancher<L> {
     fn with_restarts(self, allowed_conflicts: u64, increase_ratio: f32) -> Brancher<L> {
         Box::new(WithGeomRestart::new(allowed_conflicts, increase_ratio, self))
     }
+
+    fn with_domain_splitting(self) -> Brancher<L> {
+        Box::new(DomainSplitting::new(self))
+    }
 }
 
 /// A brancher that will systematically ask the `first` brancher for a decision.
@@ -74,6 +81,11 @@ impl<L: 'static> SearchControl<L> for AndThen<L> {
         self.second.new_assignment_found(objective_value, assignment);
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.first.add_hint(var, value);
+        self.second.add_hint(var, value);
+    }
+
     fn conflict(
         &mut self,
         clause: &Conflict,
@@ -150,6 +162,10 @@ impl<L: 'static> SearchControl<L> for UntilFirstConflict<L> {
         }
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.brancher.add_hint(var, value)
+    }
+
     fn conflict(
         &mut self,
         _clause: &Conflict,
@@ -234,6 +250,10 @@ impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
         self.brancher.new_assignment_found(objective_value, assignment)
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.brancher.add_hint(var, value)
+    }
+
     fn conflict(
         &mut self,
         clause: &Conflict,
@@ -262,6 +282,98 @@ impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
     }
 }
 
+/// Returns a literal that splits the current domain of the variable of `lit` in two halves,
+/// selecting the lower half if `lit` is an upper bound of the variable and the upper half otherwise.
+///
+/// For instance, with a variable in `[0, 10]`, both `x <= 0` and `x <= 8` are split into `x <= 5` while `x >= 10` is split into `x >= 6`.
+pub fn dichotomic_split<L>(lit: Lit, model: &Model<L>) -> Lit {
+    let var = lit.variable();
+    let (lb, ub) = model.state.bounds(var);
+    debug_assert!(lb < ub);
+    let mid = lb + (ub - lb) / 2;
+    if lit.svar().is_plus() {
+        Lit::leq(var, mid)
+    } else {
+        Lit::gt(var, mid)
+    }
+}
+
+/// A brancher that replaces each decision of the `brancher` by a dichotomic split of the domain of the variable
+/// (see [`dichotomic_split`]), in the direction of the original decision.
+///
+/// Instead of fixing a variable to its preferred value, the search thus successively halves its domain,
+/// which is typically beneficial for variables with large domains whose value is mostly determined by propagation.
+pub struct DomainSplitting<L> {
+    brancher: Brancher<L>,
+}
+
+impl<L> DomainSplitting<L> {
+    pub fn new(brancher: Brancher<L>) -> Self {
+        DomainSplitting { brancher }
+    }
+}
+
+impl<L> Backtrack for DomainSplitting<L> {
+    fn save_state(&mut self) -> DecLvl {
+        self.brancher.save_state()
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.brancher.num_saved()
+    }
+
+    fn restore_last(&mut self) {
+        self.brancher.restore_last()
+    }
+}
+
+impl<L: 'static> SearchControl<L> for DomainSplitting<L> {
+    fn next_decision(&mut self, stats: &Stats, model: &Model<L>) -> Option<Decision> {
+        match self.brancher.next_decision(stats, model) {
+            Some(Decision::SetLiteral(lit)) if !model.state.is_bound(lit.variable()) => {
+                Some(Decision::SetLiteral(dichotomic_split(lit, model)))
+            }
+            decision => decision,
+        }
+    }
+
+    fn import_vars(&mut self, model: &Model<L>) {
+        self.brancher.import_vars(model)
+    }
+
+    fn new_assignment_found(&mut self, objective_value: IntCst, assignment: Arc<SavedAssignment>) {
+        self.brancher.new_assignment_found(objective_value, assignment)
+    }
+
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.brancher.add_hint(var, value)
+    }
+
+    fn conflict(
+        &mut self,
+        clause: &Conflict,
+        model: &Model<L>,
+        explainer: &mut dyn Explainer,
+        backtrack_level: DecLvl,
+    ) {
+        self.brancher.conflict(clause, model, explainer, backtrack_level)
+    }
+
+    fn pre_save_state(&mut self, model: &Model<L>) {
+        self.brancher.pre_save_state(model);
+    }
+
+    fn pre_conflict_analysis(&mut self, model: &Model<L>) {
+        self.brancher.pre_conflict_analysis(model);
+    }
+
+    fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
+        Box::new(DomainSplitting {
+            brancher: self.brancher.clone_to_box(),
+        })
+    }
+}
+
 /// A solver that alternates between the given strategies in a round-robin fashion.
 pub struct RoundRobin<L> {
     /// Number of conflicts before switching to the next
@@ -326,6 +438,12 @@ impl<L: 'static> SearchControl<L> for RoundRobin<L> {
         self.current_mut().new_assignment_found(objective_value, assignment)
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        for brancher in &mut self.branchers {
+            brancher.add_hint(var, value)
+        }
+    }
+
     fn pre_save_state(&mut self, _model: &Model<L>) {
         self.current_mut().pre_save_state(_model);
     }
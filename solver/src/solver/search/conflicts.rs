@@ -59,6 +59,8 @@ struct PreferredValueOrigin(u32);
 
 impl PreferredValueOrigin {
     const PHASE: PreferredValueOrigin = PreferredValueOrigin(0);
+    /// Value provided by the user as a hint, that is only overridden by the values of a solution.
+    const HINT: PreferredValueOrigin = PreferredValueOrigin(u32::MAX);
     pub fn from_solution(solution_id: u32) -> Self {
         debug_assert!(solution_id > 0);
         PreferredValueOrigin(solution_id)
@@ -86,6 +88,11 @@ impl PreferredValues {
             .insert(var, (value, PreferredValueOrigin::from_solution(self.last_solution_id)));
     }
 
+    /// Set the value as preferred, as a hint that is not erased by phase saving (but is by solutions).
+    pub fn set_from_hint(&mut self, var: VarRef, value: IntCst) {
+        self.values.insert(var, (value, PreferredValueOrigin::HINT));
+    }
+
     /// Returns the preferred value vor the variable or None is none is recorded
     pub fn get(&self, var: VarRef) -> Option<IntCst> {
         self.values.get(var).map(|(val, _)| *val)
@@ -328,6 +335,12 @@ impl ConflictBasedBrancher {
             debug_assert!(lb < ub);
 
             let value = self.default_assignment.get(v).unwrap_or(lb);
+            // a preferred value in a hole of a sparse domain is replaced by the closest value of the domain
+            let value = if lb < value && value < ub {
+                model.state.closest_value(v, value)
+            } else {
+                value
+            };
 
             let literal = if value <= lb {
                 Lit::leq(v, lb)
@@ -607,6 +620,10 @@ impl<Var> SearchControl<Var> for ConflictBasedBrancher {
         }
     }
 
+    fn add_hint(&mut self, var: VarRef, value: IntCst) {
+        self.default_assignment.set_from_hint(var, value)
+    }
+
     fn pre_save_state(&mut self, _model: &Model<Var>) {
         self.process_events(_model);
     }
@@ -0,0 +1,302 @@
+//! Failure-directed search, a search strategy focused on proving infeasibility (or optimality) rather than on finding solutions.
+//!
+//! Reference: Vilím, Laborie and Shaw. Failure-directed Search for Constraint-based Scheduling. CPAIOR 2015.
+//!
+//! Each unbound decision variable offers two *choices*: the lower and the upper half of its domain (see [`dichotomic_split`]).
+//! Each choice is associated to a *rating* that estimates how likely it is to fail: after a decision, its rating is
+//! updated with `0` if the propagation of the decision failed, or with `1 + r` otherwise, where `r` is the ratio
+//! between the size of the search space after and before the decision.
+//! The brancher selects the variable whose two choices have the lowest ratings, i.e., whose two branches are expected to
+//! fail quickly, and first explores the choice with the lowest rating.
+
+use crate::backtrack::{Backtrack, DecLvl, Trail};
+use crate::collections::heap::IdxHeap;
+use crate::collections::ref_store::RefMap;
+use crate::core::state::{Conflict, Explainer};
+use crate::core::{Lit, SignedVar, VarRef};
+use crate::model::Model;
+use crate::solver::search::combinators::dichotomic_split;
+use crate::solver::search::{Decision, SearchControl};
+use crate::solver::stats::Stats;
+
+#[derive(Copy, Clone, Debug)]
+pub struct FdsParams {
+    /// Weight of the latest observation when updating the rating of a choice, in `(0, 1]`.
+    pub decay: f64,
+    /// Rating of a choice that was never made.
+    pub initial_rating: f64,
+}
+
+impl Default for FdsParams {
+    fn default() -> Self {
+        FdsParams {
+            decay: 0.1,
+            initial_rating: 1.0,
+        }
+    }
+}
+
+/// A brancher implementing failure-directed search on a set of decision variables.
+#[derive(Clone)]
+pub struct FailureDirectedSearch {
+    pub params: FdsParams,
+    /// Rating of the choices, indexed by the bound they constrain: `x <= mid` is associated to `+x` and `x > mid` to `-x`.
+    ratings: RefMap<SignedVar, f64>,
+    /// Decision variables, whose priority is the opposite of the sum of the ratings of their two choices.
+    /// Only variables that are bound or absent are removed from the heap, which allows reinserting them on backtrack.
+    heap: IdxHeap<VarRef, f64>,
+    /// Variables removed from the heap at each decision level.
+    removed: Trail<VarRef>,
+    /// Last decision taken, with the logarithm of the size of the search space before it was propagated.
+    pending: Option<(Lit, f64)>,
+}
+
+impl FailureDirectedSearch {
+    pub fn new(decision_vars: impl IntoIterator<Item = VarRef>) -> Self {
+        Self::with_params(decision_vars, FdsParams::default())
+    }
+
+    pub fn with_params(decision_vars: impl IntoIterator<Item = VarRef>, params: FdsParams) -> Self {
+        let mut heap = IdxHeap::new();
+        for var in decision_vars {
+            if !heap.is_declared(var) {
+                heap.declare_element(var, -2.0 * params.initial_rating);
+                heap.enqueue(var);
+            }
+        }
+        FailureDirectedSearch {
+            params,
+            ratings: Default::default(),
+            heap,
+            removed: Default::default(),
+            pending: None,
+        }
+    }
+
+    /// Returns the current rating of the choice.
+    pub fn rating(&self, choice: Lit) -> f64 {
+        self.ratings
+            .get(choice.svar())
+            .copied()
+            .unwrap_or(self.params.initial_rating)
+    }
+
+    fn update_rating(&mut self, choice: Lit, local_rating: f64) {
+        let updated = (1.0 - self.params.decay) * self.rating(choice) + self.params.decay * local_rating;
+        self.ratings.insert(choice.svar(), updated);
+        let var = choice.variable();
+        if self.heap.is_declared(var) {
+            let score = self.rating(var.leq(0)) + self.rating(var.geq(0));
+            self.heap.set_priority(var, -score);
+        }
+    }
+
+    /// Decision variables that are present and not bound yet.
+    fn decidable_variables<'a, L>(&'a self, model: &'a Model<L>) -> impl Iterator<Item = VarRef> + 'a {
+        self.heap
+            .keys()
+            .filter(|&v| model.state.present(v) == Some(true) && !model.state.is_bound(v))
+    }
+
+    /// Logarithm of the size of the search space, i.e., of the product of the sizes of the domains of all decision
+    /// variables.
+    fn log_search_space<L>(&self, model: &Model<L>) -> f64 {
+        self.decidable_variables(model)
+            .map(|v| {
+                let (lb, ub) = model.state.bounds(v);
+                ((ub - lb) as f64 + 1.0).ln()
+            })
+            .sum()
+    }
+
+    /// Returns the decidable variable whose choices are the most likely to fail.
+    ///
+    /// Bound and absent variables are removed from the heap until backtracking. Variables whose presence is not
+    /// known yet are only skipped.
+    fn select_variable<L>(&mut self, model: &Model<L>) -> Option<VarRef> {
+        let mut skipped = Vec::new();
+        let selected = loop {
+            let Some(&var) = self.heap.peek() else {
+                break None;
+            };
+            match model.state.present(var) {
+                Some(true) if !model.state.is_bound(var) => break Some(var),
+                None => skipped.push(var),
+                Some(_) => {
+                    self.removed.push(var);
+                }
+            }
+            self.heap.pop();
+        };
+        for var in skipped {
+            self.heap.enqueue(var);
+        }
+        selected
+    }
+}
+
+impl Backtrack for FailureDirectedSearch {
+    fn save_state(&mut self) -> DecLvl {
+        self.removed.save_state()
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.removed.num_saved()
+    }
+
+    fn restore_last(&mut self) {
+        // backtracking without a conflict on the pending decision (e.g. on restart), no information on its outcome
+        self.pending = None;
+        self.removed.restore_last_with(|var| self.heap.enqueue(var))
+    }
+}
+
+impl<L> SearchControl<L> for FailureDirectedSearch {
+    fn next_decision(&mut self, _stats: &Stats, model: &Model<L>) -> Option<Decision> {
+        let log_size = self.log_search_space(model);
+        if let Some((choice, log_size_before)) = self.pending.take() {
+            // the last decision was successfully propagated, rate it by the reduction of the search space
+            let reduction = (log_size - log_size_before).exp().min(1.0);
+            self.update_rating(choice, 1.0 + reduction);
+        }
+
+        let var = self.select_variable(model)?;
+        let lower = dichotomic_split(var.leq(model.state.lb(var)), model);
+        let upper = !lower;
+        // explore first the choice that is the most likely to fail
+        let choice = if self.rating(lower) <= self.rating(upper) {
+            lower
+        } else {
+            upper
+        };
+        self.pending = Some((choice, log_size));
+        Some(Decision::SetLiteral(choice))
+    }
+
+    fn conflict(
+        &mut self,
+        _clause: &Conflict,
+        _model: &Model<L>,
+        _explainer: &mut dyn Explainer,
+        _backtrack_level: DecLvl,
+    ) {
+        if let Some((choice, _)) = self.pending.take() {
+            // the propagation of the last decision failed
+            self.update_rating(choice, 0.0);
+        }
+    }
+
+    fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::extensions::AssignmentExt;
+    use crate::model::lang::expr::{leq, neq};
+    use crate::model::lang::IVar;
+    use crate::solver::search::combinators::CombinatorExt;
+    use crate::solver::search::lexical::Lexical;
+    use crate::solver::search::Brancher;
+    use crate::solver::Solver;
+    use itertools::Itertools;
+
+    type Model = crate::model::Model<String>;
+
+    /// Pigeon-hole problem with `n` pigeons and `n - 1` holes, where pigeons are integer variables (unsatisfiable).
+    fn pigeons(n: usize) -> (Model, Vec<IVar>) {
+        let mut model = Model::new();
+        let vars = (0..n)
+            .map(|i| model.new_ivar(0, n as i32 - 2, format!("p{i}")))
+            .collect_vec();
+        for (i, &a) in vars.iter().enumerate() {
+            for &b in &vars[i + 1..] {
+                model.enforce(neq(a, b), []);
+            }
+        }
+        (model, vars)
+    }
+
+    /// Failure-directed search on the given variables, followed by a lexical search on the remaining ones.
+    fn fds(vars: &[IVar]) -> Brancher<String> {
+        let brancher: Brancher<String> = Box::new(FailureDirectedSearch::new(vars.iter().map(|&v| v.into())));
+        brancher.and_then(Box::new(Lexical::with_min()))
+    }
+
+    #[test]
+    fn test_fds_proves_infeasibility() {
+        let (model, vars) = pigeons(5);
+        let mut solver = Solver::new(model);
+        solver.set_brancher_boxed(fds(&vars));
+        assert!(solver.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fds_optimization() {
+        // minimize the largest of n pairwise distinct variables
+        let n = 5;
+        let mut model = Model::new();
+        let vars = (0..n).map(|i| model.new_ivar(0, 20, format!("x{i}"))).collect_vec();
+        let obj = model.new_ivar(0, 20, "obj");
+        for (i, &a) in vars.iter().enumerate() {
+            model.enforce(leq(a, obj), []);
+            for &b in &vars[i + 1..] {
+                model.enforce(neq(a, b), []);
+            }
+        }
+        let mut solver = Solver::new(model);
+        solver.set_brancher_boxed(fds(&[vars.as_slice(), &[obj]].concat()));
+        let (cost, _) = solver.minimize(obj).unwrap().unwrap();
+        assert_eq!(cost, n - 1);
+    }
+
+    #[test]
+    fn test_decision_variables() {
+        let mut model = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 10, "y");
+        model.enforce(leq(x, y), []);
+        let mut brancher = FailureDirectedSearch::new([x.into()]);
+        let stats = Stats::default();
+        let Some(Decision::SetLiteral(decision)) = brancher.next_decision(&stats, &model) else {
+            panic!("No decision on x")
+        };
+        assert_eq!(decision.variable(), VarRef::from(x));
+
+        // once `x` is bound, the remaining variables are left to the next brancher
+        let mut solver = Solver::new(model);
+        solver.set_brancher_boxed(fds(&[x]));
+        let solution = solver.solve().unwrap().unwrap();
+        assert!(solution.var_domain(x).lb <= solution.var_domain(y).lb);
+    }
+
+    #[test]
+    fn test_domain_splitting() {
+        let mut model = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 100, "y");
+        model.enforce(leq(x + 3, y), []);
+        let mut solver = Solver::new(model);
+        let brancher: Brancher<String> = Box::new(Lexical::with_max());
+        solver.set_brancher_boxed(brancher.with_domain_splitting());
+        let solution = solver.solve().unwrap().unwrap();
+        assert_eq!(solution.var_domain(x).lb, 10);
+        assert_eq!(solution.var_domain(y).lb, 100);
+        // the lexical brancher would need a single decision per variable, domain splitting needs a logarithmic number of them
+        assert!(solver.stats.num_decisions > 2);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut model = Model::new();
+        let x = model.new_ivar(-3, 10, "x");
+        assert_eq!(dichotomic_split(x.leq(-3), &model), x.leq(3));
+        assert_eq!(dichotomic_split(x.leq(8), &model), x.leq(3));
+        assert_eq!(dichotomic_split(x.geq(10), &model), x.geq(4));
+        let b = model.new_bvar("b");
+        assert_eq!(dichotomic_split(b.false_lit(), &model), b.false_lit());
+        assert_eq!(dichotomic_split(b.true_lit(), &model), b.true_lit());
+    }
+}
@@ -4,14 +4,18 @@ use crate::core::literals::Disjunction;
 use crate::core::state::*;
 use crate::core::*;
 use crate::model::extensions::{AssignmentExt, DisjunctionExt, SavedAssignment, Shaped};
+use crate::model::lang::expr::or;
 use crate::model::lang::IAtom;
+use crate::model::symmetry;
 use crate::model::{Constraint, Label, Model, ModelShape};
 use crate::reasoners::cp::max::{AtLeastOneGeq, MaxElem};
 use crate::reasoners::{Contradiction, ReasonerId, Reasoners};
 use crate::reif::{DifferenceExpression, ReifExpr, Reifiable};
+use crate::solver::learnt::LearntClauses;
 use crate::solver::parallel::signals::{InputSignal, InputStream, SolverOutput, Synchro};
 use crate::solver::search::{default_brancher, Decision, SearchControl};
-use crate::solver::stats::Stats;
+use crate::solver::stats::{Stats, StatsReport};
+use crate::solver::trace::{self, TraceEvent, TraceSink};
 use crate::utils::cpu_time::StartCycleCount;
 use crossbeam_channel::Sender;
 use env_param::EnvParam;
@@ -27,6 +31,10 @@ static LOG_DECISIONS: EnvParam<bool> = EnvParam::new("ARIES_LOG_DECISIONS", "fal
 /// If true: each time a solution is found, the solver's stats will be printed (in optimization)
 static STATS_AT_SOLUTION: EnvParam<bool> = EnvParam::new("ARIES_STATS_AT_SOLUTION", "false");
 
+/// If true, all linear and difference constraints are additionally posted to the simplex reasoner,
+/// that checks their rational relaxation, tightens bounds from it, and bounds the objective when optimizing.
+static USE_SIMPLEX: EnvParam<bool> = EnvParam::new("ARIES_USE_SIMPLEX", "false");
+
 /// Macro that uses the the same syntax as `println!()` but:
 ///  - only evaluate arguments and print if `LOG_DECISIONS` is true.
 ///  - prepends the thread id to the line.
@@ -51,13 +59,45 @@ enum SearchResult {
 }
 pub type UnsatCore = Explanation;
 
+/// A solution on the Pareto front of a multi-objective problem.
+#[derive(Clone)]
+pub struct ParetoPoint {
+    /// Value of each objective in the solution
+    pub objectives: Vec<IntCst>,
+    pub solution: Arc<SavedAssignment>,
+}
+
+impl ParetoPoint {
+    pub fn new(objectives: &[IAtom], solution: Arc<SavedAssignment>) -> Self {
+        ParetoPoint {
+            objectives: objectives.iter().map(|&obj| solution.var_domain(obj).lb).collect(),
+            solution,
+        }
+    }
+
+    /// Returns true if this point is at least as good as the other one on all (minimized) objectives.
+    pub fn weakly_dominates(&self, other: &ParetoPoint) -> bool {
+        self.objectives.iter().zip(&other.objectives).all(|(a, b)| a <= b)
+    }
+}
+
 #[derive(Debug)]
 pub enum Exit {
     Interrupted,
+    /// Symmetries were broken for a previous query and may move this variable of the current query.
+    /// The lex-leader constraints could then remove solutions that are relevant to the query.
+    MovedBySymmetries(VarRef),
 }
 impl std::fmt::Display for Exit {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Solver interrupted.")
+        match self {
+            Exit::Interrupted => write!(f, "Solver interrupted."),
+            Exit::MovedBySymmetries(var) => write!(
+                f,
+                "Symmetries were broken for a previous query and may move variable {var:?}. \
+                 The solver should be cloned before its first query to be reused on other variables."
+            ),
+        }
     }
 }
 impl std::error::Error for Exit {}
@@ -80,6 +120,23 @@ pub struct Solver<Lbl> {
     /// A data structure with the various communication channels
     /// needed to receive/send updates and commands.
     sync: Synchro,
+    /// If set, all search events will be recorded in this sink.
+    trace: Option<Box<dyn TraceSink>>,
+    /// Values suggested by the user for some variables (see [`Solver::add_hint`]).
+    hints: Vec<(VarRef, IntCst)>,
+    /// If set, the solver backtracks chronologically (i.e. undoes a single decision level) after a conflict whose
+    /// backjump would undo more than this number of decision levels.
+    /// This preserves the work of the reasoners at the cost of leaving out-of-order literals on the trail.
+    /// (Nadel and Ryvchin, Chronological Backtracking, SAT 2018)
+    pub chronological_backtracking: Option<u32>,
+    /// If true, the symmetries of the model are detected before the search and broken with lex-leader constraints
+    /// (see [`crate::model::symmetry`]). This is only done when searching for a single, an optimal or a Pareto-optimal
+    /// solution, as it removes solutions from the search space.
+    pub symmetry_breaking: bool,
+    /// If the symmetry breaking constraints were added to the model, the `fixed` variables of the query for which
+    /// they were added: only symmetries leaving these variables unchanged were broken. The constraints are never
+    /// removed, so any later query must only depend on these variables.
+    symmetries_broken: Option<Vec<VarRef>>,
 }
 impl<Lbl: Label> Solver<Lbl> {
     pub fn new(model: Model<Lbl>) -> Solver<Lbl> {
@@ -92,21 +149,126 @@ impl<Lbl: Label> Solver<Lbl> {
             last_assumption_level: DecLvl::ROOT,
             stats: Default::default(),
             sync: Synchro::new(),
+            trace: trace::sink_from_env(),
+            hints: Vec::new(),
+            chronological_backtracking: None,
+            symmetry_breaking: false,
+            symmetries_broken: None,
         }
     }
 
     pub fn set_brancher(&mut self, brancher: impl SearchControl<Lbl> + 'static + Send) {
-        self.brancher = Box::new(brancher)
+        self.set_brancher_boxed(Box::new(brancher))
     }
 
     pub fn set_brancher_boxed(&mut self, brancher: Box<dyn SearchControl<Lbl> + 'static + Send>) {
-        self.brancher = brancher
+        self.brancher = brancher;
+        for &(var, value) in &self.hints {
+            self.brancher.add_hint(var, value);
+        }
+    }
+
+    /// Suggests a value for a variable, to be preferred by the brancher when making decisions on it.
+    ///
+    /// When optimizing, hints that assign all labeled variables of the problem (directly or through propagation)
+    /// are used as the initial incumbent, if they can be extended to a solution.
+    pub fn add_hint(&mut self, var: impl Into<VarRef>, value: IntCst) {
+        let var = var.into();
+        self.hints.push((var, value));
+        self.brancher.add_hint(var, value);
+    }
+
+    /// Suggests a (possibly partial) assignment as a starting point of the search, replacing any previous hints.
+    /// A previous solution can be reused with `solver.set_hint(solution.bound_variables())`.
+    ///
+    /// Note that the preferred values that the brancher derived from previous hints are not forgotten.
+    pub fn set_hint(&mut self, assignment: impl IntoIterator<Item = (VarRef, IntCst)>) {
+        self.hints.clear();
+        for (var, value) in assignment {
+            self.add_hint(var, value);
+        }
+    }
+
+    /// Returns the solution obtained by setting all hinted values and propagating,
+    /// or `None` if this results in a conflict or leaves some labeled variables unassigned.
+    /// Auxiliary (unlabeled) variables that remain unassigned are set to their lower bound, with propagation.
+    ///
+    /// The solver is left at the last assumption level.
+    fn hinted_solution(&mut self) -> Option<Arc<SavedAssignment>> {
+        if self.hints.is_empty() || self.propagate_and_backtrack_to_consistent().is_err() {
+            return None;
+        }
+        let mut consistent = true;
+        let literals = self
+            .hints
+            .iter()
+            .flat_map(|&(var, value)| [Lit::geq(var, value), Lit::leq(var, value)]);
+        for lit in literals.collect_vec() {
+            if self.model.entails(!lit) {
+                consistent = false;
+            } else if !self.model.entails(lit) {
+                self.decide(lit);
+                consistent = self.propagate().is_ok();
+            }
+            if !consistent {
+                break;
+            }
+        }
+        let state = &self.model.state;
+        let unassigned = |v: VarRef| !state.is_bound(v) && state.present(v) != Some(false);
+        let mut complete = consistent
+            && !state
+                .variables()
+                .any(|v| unassigned(v) && self.model.shape.labels.get(v).is_some());
+        let auxiliaries = state.variables().filter(|&v| unassigned(v)).collect_vec();
+        for v in auxiliaries {
+            let state = &self.model.state;
+            if !complete {
+                break;
+            } else if !state.is_bound(v) && state.present(v) != Some(false) {
+                self.decide(Lit::leq(v, state.lb(v)));
+                complete = self.propagate().is_ok();
+            }
+        }
+        let solution = complete.then(|| Arc::new(self.model.state.clone()));
+        self.reset_search();
+        solution
+    }
+
+    /// Records all subsequent search events in the given sink, replacing any previous one.
+    /// Clones of this solver will record their events in a clone of the sink.
+    pub fn set_trace_sink(&mut self, sink: impl TraceSink + 'static) {
+        self.trace = Some(Box::new(sink))
+    }
+
+    /// Records a search event, if a trace sink is attached to the solver.
+    /// The event is only built if it is going to be recorded.
+    fn trace(&mut self, event: impl FnOnce(&Model<Lbl>) -> TraceEvent) {
+        if let Some(sink) = &mut self.trace {
+            sink.record(event(&self.model))
+        }
     }
 
     pub fn input_stream(&self) -> InputStream {
         self.sync.input_stream()
     }
 
+    /// Discards the interruption requests that were sent to the solver but not processed yet.
+    ///
+    /// This is useful when an interruption may have been requested right after the end of a search,
+    /// which would otherwise abort the next one. Other pending signals are preserved.
+    pub fn clear_interruptions(&mut self) {
+        let pending = self
+            .sync
+            .signals
+            .try_iter()
+            .filter(|signal| !matches!(signal, InputSignal::Interrupt))
+            .collect_vec();
+        for signal in pending {
+            let _ = self.sync.sender.send(signal);
+        }
+    }
+
     pub fn set_solver_output(&mut self, output: Sender<SolverOutput>) {
         self.sync.set_output(output);
     }
@@ -160,10 +322,15 @@ impl<Lbl: Label> Solver<Lbl> {
                 self.reasoners
                     .diff
                     .add_reified_edge(value, rhs, lhs, rhs_add, &self.model.state);
+                if rhs_add == 0 {
+                    self.reasoners.euf.add_leq(lhs, rhs, value);
+                }
+                self.add_simplex_constraint([(lhs, 1), (rhs, -1)], rhs_add, value);
                 Ok(())
             }
             ReifExpr::Eq(a, b) => {
                 let lit = self.reasoners.eq.add_edge(*a, *b, &mut self.model);
+                self.reasoners.euf.add_equality(*a, *b, lit);
                 if lit != value {
                     self.add_clause([!value, lit], scope)?; // value => lit
                     self.add_clause([!lit, value], scope)?; // lit => value
@@ -172,6 +339,7 @@ impl<Lbl: Label> Solver<Lbl> {
             }
             ReifExpr::Neq(a, b) => {
                 let lit = !self.reasoners.eq.add_edge(*a, *b, &mut self.model);
+                self.reasoners.euf.add_equality(*a, *b, !lit);
                 if lit != value {
                     self.add_clause([!value, lit], scope)?; // value => lit
                     self.add_clause([!lit, value], scope)?; // lit => value
@@ -179,8 +347,7 @@ impl<Lbl: Label> Solver<Lbl> {
                 Ok(())
             }
             ReifExpr::EqVal(a, b) => {
-                let (lb, ub) = self.model.state.bounds(*a);
-                let lit = if (lb..=ub).contains(b) {
+                let lit = if self.model.state.contains(*a, *b) {
                     self.reasoners.eq.add_val_edge(*a, *b, &mut self.model)
                 } else {
                     Lit::FALSE
@@ -286,6 +453,7 @@ impl<Lbl: Label> Solver<Lbl> {
                     assert!(self.model.entails(value), "Unsupported reified linear constraints."); // FIXME: Support reified linear constraints
                     let scope = self.model.state.presence(value);
                     self.reasoners.cp.add_opt_linear_constraint(&lin, scope);
+                    self.add_simplex_constraint(lin.sum.iter().map(|e| (e.var, e.factor)), lin.upper_bound, value);
 
                     // if the linear sum is on three variables, try adding a redundant dynamic variable to the STN
                     if lin.upper_bound == 0 && lin.sum.len() == 3 {
@@ -326,24 +494,23 @@ impl<Lbl: Label> Solver<Lbl> {
             }
             ReifExpr::Alternative(a) => {
                 let prez = |v: VarRef| self.model.state.presence_literal(v);
-                assert!(
-                    self.model.entails(value),
-                    "Unsupported reified alternative constraints."
-                );
                 assert_eq!(prez(a.main), prez(value.variable()));
+                // if the constraint is not enforced, it is active when `value` holds and its propagators must be aware of it
+                let enforced = self.model.entails(value);
+                let active = if enforced { scope } else { value };
 
-                let scope = prez(a.main);
                 let presences = a.alternatives.iter().map(|alt| prez(alt.var)).collect_vec();
                 // at least one alternative must be present
-                self.add_clause(&presences, scope)?;
+                self.add_clause(presences.iter().copied().chain([!value]).collect_vec(), scope)?;
 
                 // at most one must be present
                 for (i, p1) in presences.iter().copied().enumerate() {
                     for &p2 in &presences[i + 1..] {
-                        self.add_clause([!p1, !p2], scope)?;
+                        self.add_clause([!value, !p1, !p2], scope)?;
                     }
                 }
 
+                let mut equalities = Vec::with_capacity(a.alternatives.len());
                 for alt in &a.alternatives {
                     let alt_scope = self.model.state.presence_literal(alt.var);
                     debug_assert!(self.model.state.implies(alt_scope, scope));
@@ -351,22 +518,35 @@ impl<Lbl: Label> Solver<Lbl> {
                     // a.main - alt.var = alt.shift
                     // alt.cst <= a.main - alt.var <= alt.cst
                     // -alt.cst >= alt.var - a.main   &&   a.main - alt.var <= alt.cst
-                    let alt_value = self.model.get_tautology_of_scope(alt_scope);
-                    self.post_constraint(&Constraint::Reified(
-                        ReifExpr::MaxDiff(DifferenceExpression::new(alt.var, a.main, -alt.cst)),
-                        alt_value,
-                    ))?;
-                    self.post_constraint(&Constraint::Reified(
-                        ReifExpr::MaxDiff(DifferenceExpression::new(a.main, alt.var, alt.cst)),
-                        alt_value,
-                    ))?;
+                    let geq = ReifExpr::MaxDiff(DifferenceExpression::new(alt.var, a.main, -alt.cst));
+                    let leq = ReifExpr::MaxDiff(DifferenceExpression::new(a.main, alt.var, alt.cst));
+                    if enforced {
+                        let alt_value = self.model.get_tautology_of_scope(alt_scope);
+                        self.post_constraint(&Constraint::Reified(geq, alt_value))?;
+                        self.post_constraint(&Constraint::Reified(leq, alt_value))?;
+                    } else {
+                        // value & prez(alt)  =>  a.main = alt.var + alt.cst
+                        let geq = self.model.reify(geq);
+                        let leq = self.model.reify(leq);
+                        self.add_clause([!value, !alt_scope, geq], scope)?;
+                        self.add_clause([!value, !alt_scope, leq], scope)?;
+                        equalities.push([geq, leq]);
+                    }
+                }
+
+                // the relation holds if a single alternative is present and equal to main
+                //  prez(alt_i) & (a.main = alt_i.var + alt_i.cst) & AND_j!=i !prez(alt_j)  =>  value
+                for (i, [geq, leq]) in equalities.into_iter().enumerate() {
+                    let mut clause = vec![value, !presences[i], !geq, !leq];
+                    clause.extend(presences.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, &p)| p));
+                    self.add_clause(clause, scope)?;
                 }
 
                 let prez = |v: VarRef| self.model.state.presence_literal(v);
 
                 // ub(main) <- max_i { ub(var_i) + cst_i  | prez_i }
                 self.reasoners.cp.add_propagator(AtLeastOneGeq {
-                    scope,
+                    scope: active,
                     lhs: SignedVar::plus(a.main),
                     elements: a
                         .alternatives
@@ -380,25 +560,30 @@ impl<Lbl: Label> Solver<Lbl> {
                 // -ub(-main) <- - max_i {  ub(-var_i) + cst_i | prez_i }
                 //  ub(-main) <-   max_i {  ub(-var_i) + cst_i | prez_i }
                 self.reasoners.cp.add_propagator(AtLeastOneGeq {
-                    scope,
+                    scope: active,
                     lhs: SignedVar::minus(a.main),
                     elements: a
                         .alternatives
                         .iter()
-                        .map(|alt| MaxElem::new(SignedVar::minus(alt.var), alt.cst, prez(alt.var)))
+                        .map(|alt| MaxElem::new(SignedVar::minus(alt.var), -alt.cst, prez(alt.var)))
                         .collect_vec(),
                 });
                 Ok(())
             }
             ReifExpr::EqMax(a) => {
                 let prez = |v: SignedVar| self.model.state.presence(v);
-                assert!(self.model.entails(value), "Unsupported reified eqmax constraints.");
                 assert_eq!(prez(a.lhs), prez(value.variable().into()));
+                // if the constraint is not enforced, it is active when `value` holds and its propagators must be aware of it
+                let enforced = self.model.entails(value);
+                let active = if enforced { scope } else { value };
 
-                let scope = prez(a.lhs);
                 let presences = a.rhs.iter().map(|alt| prez(alt.var)).collect_vec();
                 // at least one alternative must be present
-                self.add_clause(&presences, scope)?;
+                self.add_clause(presences.iter().copied().chain([!value]).collect_vec(), scope)?;
+
+                // literals `lhs >= rhs[i]` (resp. `lhs <= rhs[i]`), only needed if the constraint is not enforced
+                let mut dominated = Vec::with_capacity(a.rhs.len());
+                let mut dominating = Vec::with_capacity(a.rhs.len());
 
                 // POST  forall i    lhs >= rhs[i]   (scope: prez(rhs[i]))
                 for item in &a.rhs {
@@ -407,36 +592,37 @@ impl<Lbl: Label> Solver<Lbl> {
                     // a.lhs >= item.var + item.cst
                     // a.lhs - item.var >= item.cst
                     // item.var - a.lhs <= -item.cst
-                    let alt_value = self.model.get_tautology_of_scope(item_scope);
-                    if item.var.is_plus() {
-                        assert!(a.lhs.is_plus());
-                        self.post_constraint(&Constraint::Reified(
-                            ReifExpr::MaxDiff(DifferenceExpression::new(
-                                item.var.variable(),
-                                a.lhs.variable(),
-                                -item.cst,
-                            )),
-                            alt_value,
-                        ))?;
+                    let geq = ReifExpr::MaxDiff(DifferenceExpression::signed(item.var, a.lhs, -item.cst));
+                    if enforced {
+                        let alt_value = self.model.get_tautology_of_scope(item_scope);
+                        self.post_constraint(&Constraint::Reified(geq, alt_value))?;
                     } else {
-                        assert!(a.lhs.is_minus());
-                        // item.var - a.lhs <= -item.cst
-                        let x = item.var.variable();
-                        let y = a.lhs.variable();
-                        // (-x) - (-y) <= -item.cst
-                        // y - x <= -item.cst
-                        self.post_constraint(&Constraint::Reified(
-                            ReifExpr::MaxDiff(DifferenceExpression::new(y, x, -item.cst)),
-                            alt_value,
-                        ))?;
+                        // value & prez(rhs[i])  =>  lhs >= rhs[i]
+                        let geq = self.model.reify(geq);
+                        self.add_clause([!value, !item_scope, geq], scope)?;
+                        // a.lhs <= item.var + item.cst
+                        let leq = ReifExpr::MaxDiff(DifferenceExpression::signed(a.lhs, item.var, item.cst));
+                        let leq = self.model.reify(leq);
+                        // literal that holds if the item is absent or dominated by the lhs
+                        let dominated_item = self.model.reify(or([!item_scope, geq]));
+                        dominated.push(dominated_item);
+                        dominating.push(leq);
                     }
                 }
 
+                // the relation holds if all items are dominated by the lhs and a present one is equal to it
+                //  prez(rhs[i]) & (lhs <= rhs[i]) & AND_j (!prez(rhs[j]) | lhs >= rhs[j])  =>  value
+                for (i, leq) in dominating.into_iter().enumerate() {
+                    let mut clause = vec![value, !presences[i], !leq];
+                    clause.extend(dominated.iter().map(|&l| !l));
+                    self.add_clause(clause, scope)?;
+                }
+
                 let prez = |v: SignedVar| self.model.state.presence(v);
 
                 // POST  OR_i  (prez(rhs[i])  &&  rhs[i] >= lhs)    [scope: prez(lhs)]
                 self.reasoners.cp.add_propagator(AtLeastOneGeq {
-                    scope,
+                    scope: active,
                     lhs: a.lhs,
                     elements: a
                         .rhs
@@ -447,10 +633,51 @@ impl<Lbl: Label> Solver<Lbl> {
 
                 Ok(())
             }
+            ReifExpr::Circuit(circuit) => {
+                assert!(self.model.entails(value), "Unsupported reified circuit constraints.");
+                let n = circuit.arcs.len();
+                for i in 0..n {
+                    if !circuit.optional {
+                        self.add_clause([!circuit.arcs[i][i]], scope)?;
+                    }
+                    // exactly one successor and exactly one predecessor
+                    let out_arcs = circuit.arcs[i].clone();
+                    let in_arcs = circuit.arcs.iter().map(|arcs| arcs[i]).collect_vec();
+                    for arcs in [out_arcs, in_arcs] {
+                        self.add_clause(&arcs, scope)?;
+                        for (k, &a1) in arcs.iter().enumerate() {
+                            for &a2 in &arcs[k + 1..] {
+                                self.add_clause([!a1, !a2], scope)?;
+                            }
+                        }
+                    }
+                }
+                self.reasoners.cp.add_circuit_constraint(circuit);
+                Ok(())
+            }
+            ReifExpr::Regular(regular) => {
+                // if the constraint is not enforced, it is active when `value` holds and its propagator must be aware of it
+                let enforced = self.model.entails(value);
+                let active = if enforced { scope } else { value };
+                // each variable takes one of the values of the automaton
+                for values in &regular.values {
+                    let lits = values.iter().map(|&(_, l)| l).chain([!active]).collect_vec();
+                    self.add_clause(&lits, scope)?;
+                }
+                self.reasoners.cp.add_regular_constraint(regular, active);
+                Ok(())
+            }
             ReifExpr::EqVarMulLit(mul) => {
                 self.reasoners.cp.add_eq_var_mul_lit_constraint(mul);
                 Ok(())
             }
+            ReifExpr::Apply(app) => {
+                assert!(self.model.entails(value), "Unsupported reified function application.");
+                self.reasoners
+                    .euf
+                    .add_application(app.fun, &app.args, app.result, scope);
+                Ok(())
+            }
         }
     }
 
@@ -520,9 +747,83 @@ impl<Lbl: Label> Solver<Lbl> {
         Ok(())
     }
 
+    /// If enabled, adds the constraint `value => sum <= ub` to the simplex reasoner.
+    ///
+    /// As the simplex only supports constraints that are activated by a single literal, the constraint is ignored
+    /// if `value` is optional and not entailed.
+    fn add_simplex_constraint(&mut self, sum: impl IntoIterator<Item = (VarRef, IntCst)>, ub: IntCst, value: Lit) {
+        if !USE_SIMPLEX.get() {
+            return;
+        }
+        let presence = self.model.state.presence(value);
+        let active = if self.model.entails(value) {
+            presence
+        } else if presence == Lit::TRUE && !self.model.entails(!value) {
+            value
+        } else {
+            return;
+        };
+        self.reasoners.simplex.add_constraint(sum, ub, active);
+    }
+
+    /// If symmetry breaking is enabled and was not done yet, adds lex-leader constraints to the model for all
+    /// detected symmetries that leave the `fixed` variables unchanged.
+    ///
+    /// Fails if symmetries were already broken for a previous query and may move some of the `fixed` variables.
+    fn break_symmetries(&mut self, fixed: &[VarRef]) -> Result<(), Exit> {
+        if self.symmetries_broken.is_some() {
+            return self.check_unchanged_by_symmetries(fixed.iter().copied());
+        }
+        if !self.symmetry_breaking {
+            return Ok(());
+        }
+        assert_eq!(self.decision_level, DecLvl::ROOT);
+        self.symmetries_broken = Some(fixed.to_vec());
+        symmetry::break_symmetries(&mut self.model, fixed);
+        Ok(())
+    }
+
+    /// Fails if the symmetries broken for a previous query may move one of the variables.
+    /// The lex-leader constraints would then remove solutions that are relevant to a query on these variables.
+    fn check_unchanged_by_symmetries(&self, vars: impl IntoIterator<Item = VarRef>) -> Result<(), Exit> {
+        if let Some(unchanged) = &self.symmetries_broken {
+            if let Some(var) = vars.into_iter().find(|&v| v != VarRef::ZERO && !unchanged.contains(&v)) {
+                return Err(Exit::MovedBySymmetries(var));
+            }
+        }
+        Ok(())
+    }
+
+    /// Exports the learnt clauses of the solver, where variables are identified by their labels.
+    ///
+    /// Clauses that mention a variable without a unique label (e.g. an internal reification variable) are dropped.
+    /// No clause is exported if symmetries were broken, as the learnt clauses may then depend on the lex-leader
+    /// constraints and remove solutions of the original model.
+    pub fn export_learnt_clauses(&self) -> LearntClauses<Lbl> {
+        if self.symmetries_broken.is_some() {
+            return LearntClauses::from_clauses([], &self.model);
+        }
+        LearntClauses::from_clauses(self.reasoners.sat.learnt_clauses(), &self.model)
+    }
+
+    /// Imports clauses learnt by another solver on a model with the same labels (see [`crate::solver::learnt`]).
+    /// Clauses that mention an unknown label are dropped, the other ones are added as forgettable clauses.
+    ///
+    /// Returns the number of imported clauses.
+    pub fn import_learnt_clauses(&mut self, clauses: &LearntClauses<Lbl>) -> usize {
+        assert_eq!(self.decision_level, DecLvl::ROOT);
+        let clauses = clauses.to_clauses(&self.model).collect_vec();
+        let num_imported = clauses.len();
+        for clause in clauses {
+            self.reasoners.sat.add_forgettable_clause(clause);
+        }
+        num_imported
+    }
+
     /// Searches for the first satisfying assignment, returning none if the search
     /// space was exhausted without encountering a solution.
     pub fn solve(&mut self) -> Result<Option<Arc<SavedAssignment>>, Exit> {
+        self.break_symmetries(&[])?;
         if self.post_constraints().is_err() {
             return Ok(None);
         }
@@ -542,6 +843,7 @@ impl<Lbl: Label> Solver<Lbl> {
     ///  needed for something else.
     pub fn enumerate(&mut self, variables: &[VarRef]) -> Result<Vec<Vec<IntCst>>, Exit> {
         assert_eq!(self.decision_level, DecLvl::ROOT);
+        self.check_unchanged_by_symmetries(variables.iter().copied())?;
         debug_assert!(
             {
                 variables
@@ -568,6 +870,10 @@ impl<Lbl: Label> Solver<Lbl> {
                     for v in variables {
                         let (val, _) = self.model.state.bounds(*v);
                         assignment.push(val);
+                        // the domain of an absent variable is irrelevant (and may not be fixed)
+                        if !self.model.state.present(*v).unwrap_or(true) {
+                            continue;
+                        }
                         clause.push(Lit::lt(*v, val));
                         clause.push(Lit::gt(*v, val));
                     }
@@ -593,6 +899,8 @@ impl<Lbl: Label> Solver<Lbl> {
         self.brancher.import_vars(&self.model);
 
         assert_eq!(self.decision_level, DecLvl::ROOT);
+        let assumption_lits = assumption_lits.into_iter().collect_vec();
+        self.check_unchanged_by_symmetries(assumption_lits.iter().map(|l| l.variable()))?;
 
         match self.propagate_and_backtrack_to_consistent() {
             Ok(()) => (),
@@ -688,11 +996,23 @@ impl<Lbl: Label> Solver<Lbl> {
                     self.decide(lit);
                 }
                 Some(Decision::Restart) => {
+                    let level = self.decision_level.to_int();
+                    self.trace(|_| TraceEvent::Restart { level });
                     self.reset_search();
                     self.stats.add_restart();
                 }
                 None => {
                     log_dec!("=> SOLUTION");
+                    let level = self.decision_level.to_int();
+                    self.trace(|m| TraceEvent::Solution {
+                        level,
+                        assignment: m
+                            .state
+                            .variables()
+                            .filter(|&var| m.state.present(var) == Some(true))
+                            .filter_map(|var| Some((m.shape.labels.get(var)?.to_string(), m.state.lb(var))))
+                            .collect(),
+                    });
                     // SAT: consistent + no choices left
                     self.stats.solve_time += start_time.elapsed();
                     self.stats.solve_cycles += start_cycles.elapsed();
@@ -741,28 +1061,42 @@ impl<Lbl: Label> Solver<Lbl> {
         // best solution found so far
         let mut best = None;
 
+        self.break_symmetries(&[objective.var.into()])?;
         if self.post_constraints().is_err() {
             // trivially UNSAT
             return Ok(None);
         }
+        if USE_SIMPLEX.get() {
+            self.reasoners.simplex.set_objective(objective.var.into(), minimize);
+        }
+
+        // if the hints form a solution, it is used as the first incumbent
+        let mut hinted = self.hinted_solution();
+        if let Some(sol) = &hinted {
+            self.sync.notify_solution_found(sol.clone());
+            on_new_solution(sol.var_domain(objective).lb, sol);
+        }
 
         loop {
-            let sol = match self.search()? {
-                SearchResult::AtSolution => {
-                    // solver stopped at a solution, this is necessarily an improvement on the best solution found so far
-                    // notify other solvers that we have found a new solution
-                    let sol = Arc::new(self.model.state.clone());
-                    self.sync.notify_solution_found(sol.clone());
-                    let objective_value = sol.var_domain(objective).lb;
-                    on_new_solution(objective_value, &sol);
-                    if STATS_AT_SOLUTION.get() {
-                        println!("*********  New sol: {objective_value} *********");
-                        self.print_stats();
+            let sol = match hinted.take() {
+                Some(sol) => sol,
+                None => match self.search()? {
+                    SearchResult::AtSolution => {
+                        // solver stopped at a solution, this is necessarily an improvement on the best solution found so far
+                        // notify other solvers that we have found a new solution
+                        let sol = Arc::new(self.model.state.clone());
+                        self.sync.notify_solution_found(sol.clone());
+                        let objective_value = sol.var_domain(objective).lb;
+                        on_new_solution(objective_value, &sol);
+                        if STATS_AT_SOLUTION.get() {
+                            println!("*********  New sol: {objective_value} *********");
+                            self.print_stats();
+                        }
+                        sol
                     }
-                    sol
-                }
-                SearchResult::ExternalSolution(sol) => sol, // a solution was handed to us by another solver
-                SearchResult::Unsat(_conflict) => return Ok(best), // exhausted search space under the current wuality assumptions
+                    SearchResult::ExternalSolution(sol) => sol, // a solution was handed to us by another solver
+                    SearchResult::Unsat(_conflict) => return Ok(best), // exhausted search space under the current wuality assumptions
+                },
             };
 
             // determine whether the solution found is an improvement on the previous one (might not be the case if sent by another solver)
@@ -784,6 +1118,9 @@ impl<Lbl: Label> Solver<Lbl> {
                 // as the preferred ones.
                 self.brancher.new_assignment_found(objective_value, sol.clone());
                 self.stats.add_solution(objective_value); // TODO: might consider external solutions
+                self.trace(|_| TraceEvent::Incumbent {
+                    objective: objective_value,
+                });
 
                 // save the best solution
                 best = Some((objective_value, sol));
@@ -803,6 +1140,100 @@ impl<Lbl: Label> Solver<Lbl> {
         }
     }
 
+    /// Enumerates the Pareto front of the problem for the given objectives, that are all minimized.
+    ///
+    /// Each point of the front is a solution that is not dominated by any other solution, i.e., no other solution
+    /// is at least as good on all objectives and strictly better on one. Only one solution is returned for each
+    /// combination of objective values.
+    ///
+    /// Each time a solution is found, it is passed to `on_point` with a `false` flag.
+    /// Solutions are then improved until they are proven to be non-dominated, at which point they are passed
+    /// again to `on_point` with a `true` flag. All proven points are returned once the front is complete.
+    ///
+    /// IMPORTANT: as for [`Solver::enumerate`], the clauses blocking dominated solutions are not removable.
+    pub fn pareto_front(
+        &mut self,
+        objectives: &[IAtom],
+        mut on_point: impl FnMut(&ParetoPoint, bool),
+    ) -> Result<Vec<ParetoPoint>, Exit> {
+        assert_eq!(self.decision_level, DecLvl::ROOT);
+        assert_eq!(self.last_assumption_level, DecLvl::ROOT);
+        // points proven to be non-dominated
+        let mut front: Vec<ParetoPoint> = Vec::new();
+        // points found but not proven yet, the last one being the one we try to improve on
+        let mut candidates: Vec<ParetoPoint> = Vec::new();
+
+        let objective_vars = objectives.iter().map(|o| VarRef::from(o.var)).collect_vec();
+        self.break_symmetries(&objective_vars)?;
+        if self.post_constraints().is_err() {
+            // trivially UNSAT
+            return Ok(front);
+        }
+
+        loop {
+            self.reset();
+            // force the search to look for solutions dominating the current candidate
+            if let Some(current) = candidates.last() {
+                let assumptions = objectives
+                    .iter()
+                    .zip(&current.objectives)
+                    .map(|(obj, &value)| obj.le_lit(value))
+                    .collect_vec();
+                if assumptions
+                    .into_iter()
+                    .any(|lit| self.assume_and_propagate(lit).is_err())
+                {
+                    // no solution dominates the current candidate
+                    let current = candidates.pop().unwrap();
+                    on_point(&current, true);
+                    front.push(current);
+                    continue;
+                }
+            }
+            let sol = match self.search()? {
+                SearchResult::AtSolution => {
+                    let sol = Arc::new(self.model.state.clone());
+                    self.sync.notify_solution_found(sol.clone());
+                    sol
+                }
+                SearchResult::ExternalSolution(sol) => sol, // a solution was handed to us by another solver
+                SearchResult::Unsat(conflict) if conflict.is_empty() => {
+                    // UNSAT at the root: all remaining candidates are non-dominated
+                    for current in candidates.drain(..).rev() {
+                        on_point(&current, true);
+                        front.push(current);
+                    }
+                    return Ok(front);
+                }
+                SearchResult::Unsat(_) => match candidates.pop() {
+                    // exhausted the search space dominating the current candidate
+                    Some(current) => {
+                        on_point(&current, true);
+                        front.push(current);
+                        continue;
+                    }
+                    None => return Ok(front),
+                },
+            };
+            let point = ParetoPoint::new(objectives, sol);
+            if front.iter().chain(&candidates).any(|p| p.weakly_dominates(&point)) {
+                // may only happen for a solution from another solver
+                continue;
+            }
+            self.reset();
+            // forbid all solutions that are not strictly better than this one on at least one objective
+            let clause = objectives
+                .iter()
+                .zip(&point.objectives)
+                .map(|(obj, &value)| obj.lt_lit(value))
+                .collect_vec();
+            self.reasoners.sat.add_clause(clause);
+            candidates.retain(|c| !point.weakly_dominates(c));
+            on_point(&point, false);
+            candidates.push(point);
+        }
+    }
+
     pub fn decide(&mut self, decision: Lit) {
         assert!(self.all_constraints_posted());
         self.save_state();
@@ -814,6 +1245,11 @@ impl<Lbl: Label> Solver<Lbl> {
         );
         let res = self.model.state.decide(decision);
         assert_eq!(res, Ok(true), "Decision did not result in a valid modification.");
+        let level = self.decision_level.to_int();
+        self.trace(|m| TraceEvent::Decision {
+            level,
+            lit: m.fmt(decision).to_string(),
+        });
         self.stats.add_decision(decision)
     }
 
@@ -911,6 +1347,31 @@ impl<Lbl: Label> Solver<Lbl> {
         }
     }
 
+    /// Returns the decision level to backtrack to after learning the `clause`, given the level at which it is asserting.
+    ///
+    /// This is the asserting level unless chronological backtracking is enabled and the backjump would undo too many levels,
+    /// in which case we only undo the latest level of the clause. The asserted literal will then be propagated at a higher level
+    /// than its asserting level (i.e. out of order), which is handled by the SAT reasoner on backtracking.
+    fn backtrack_level_after_conflict(&self, clause: &[Lit], asserting_level: DecLvl) -> DecLvl {
+        let Some(threshold) = self.chronological_backtracking else {
+            return asserting_level;
+        };
+        if clause.len() < 2 {
+            // unit clauses are posted as tautologies and require backtracking to the root
+            return asserting_level;
+        }
+        let conflict_level = clause
+            .iter()
+            .map(|&l| self.model.state.entailing_level(!l))
+            .max()
+            .unwrap();
+        if conflict_level.to_int() > asserting_level.to_int() + threshold {
+            conflict_level - 1
+        } else {
+            asserting_level
+        }
+    }
+
     /// Integrates a conflicting clause (typically learnt through conflict analysis)
     /// and backtracks to the appropriate level.
     /// As a side effect, the activity of the variables in the clause will be increased.
@@ -940,9 +1401,12 @@ impl<Lbl: Label> Solver<Lbl> {
         //     // println!("]");
         // }
         // println!();
-        if let Some(dl) = self.backtrack_level_for_clause(expl.literals()) {
+        if let Some(asserting_level) = self.backtrack_level_for_clause(expl.literals()) {
+            let dl = self.backtrack_level_after_conflict(expl.literals(), asserting_level);
             // inform the brancher that we are in a conflict state
             self.brancher.conflict(expl, &self.model, &mut self.reasoners, dl);
+            let from = self.decision_level.to_int();
+            self.trace(|_| TraceEvent::Backjump { from, to: dl.to_int() });
             // backtrack
             self.restore(dl);
             // println!("conflict:");
@@ -958,7 +1422,7 @@ impl<Lbl: Label> Solver<Lbl> {
                 self.reasoners.tautologies.add_tautology(expl.clause.literals()[0])
             } else {
                 // add clause to sat solver, making sure the asserted literal is set to true
-                self.reasoners.sat.add_learnt_clause(&expl.clause);
+                self.reasoners.sat.add_learnt_clause(&expl.clause, asserting_level);
             }
 
             true
@@ -1048,10 +1512,22 @@ impl<Lbl: Label> Solver<Lbl> {
             for &i in self.reasoners.writers() {
                 let theory_propagation_start = StartCycleCount::now();
                 self.stats[i].propagation_loops += 1;
+                let num_events_before = self.model.state.num_events();
                 let th = self.reasoners.reasoner_mut(i);
 
                 match th.propagate(&mut self.model.state) {
-                    Ok(()) => (),
+                    Ok(()) => {
+                        if let Some(sink) = &mut self.trace {
+                            let inferences = self.model.state.num_events() - num_events_before;
+                            if inferences > 0 {
+                                sink.record(TraceEvent::Propagation {
+                                    level: self.decision_level.to_int(),
+                                    reasoner: i.to_string(),
+                                    inferences: inferences as u64,
+                                });
+                            }
+                        }
+                    }
                     Err(contradiction) => {
                         self.brancher.pre_conflict_analysis(&self.model);
                         // contradiction, learn clause and exit
@@ -1067,6 +1543,13 @@ impl<Lbl: Label> Solver<Lbl> {
                         let lbd = self.lbd(&clause, &self.model.state);
                         self.stats
                             .add_conflict(self.current_decision_level(), clause.len(), lbd);
+                        let level = self.decision_level.to_int();
+                        self.trace(|m| TraceEvent::Conflict {
+                            level,
+                            reasoner: i.to_string(),
+                            clause: clause.literals().iter().map(|&l| m.fmt(l).to_string()).collect(),
+                            lbd,
+                        });
                         self.stats[i].conflicts += 1;
                         self.stats.propagation_time += global_start.elapsed();
                         self.stats[i].propagation_time += theory_propagation_start.elapsed();
@@ -1093,6 +1576,15 @@ impl<Lbl: Label> Solver<Lbl> {
         }
     }
 
+    /// Returns a machine-readable snapshot of the statistics of the solver and of all its reasoners.
+    pub fn stats_report(&self) -> StatsReport {
+        let mut report = self.stats.report();
+        for (i, th) in self.reasoners.theories() {
+            report.reasoners.entry(i.to_string()).or_default().counters = th.stats();
+        }
+        report
+    }
+
     /// Undo any decision that was made.
     /// This results in backtracking to the last assumption level (or to the ROOT if no assumption was made).
     pub fn reset_search(&mut self) {
@@ -1159,6 +1651,11 @@ impl<Lbl: Label> Clone for Solver<Lbl> {
             last_assumption_level: self.last_assumption_level,
             stats: self.stats.clone(),
             sync: self.sync.clone(),
+            trace: self.trace.as_ref().map(|t| t.clone_box()),
+            hints: self.hints.clone(),
+            chronological_backtracking: self.chronological_backtracking,
+            symmetry_breaking: self.symmetry_breaking,
+            symmetries_broken: self.symmetries_broken.clone(),
         }
     }
 }
@@ -1220,4 +1717,286 @@ mod test {
         // check(s, T, [!px, !py, xy1], [xy1]);
         // check(s, T, [!px, !py], [!px, !py]); // !pxy, would be correct as well
     }
+
+    #[test]
+    fn test_clear_interruptions() {
+        use crate::backtrack::Backtrack;
+        use crate::solver::parallel::signals::InputSignal;
+
+        let mut m = Model::new();
+        let x = m.new_bvar("x").true_lit();
+        let mut s = Solver::new(m);
+        s.enforce(x, []);
+
+        // an interruption sent outside of any search aborts the next one
+        s.input_stream().sender.send(InputSignal::Interrupt).unwrap();
+        assert!(s.solve().is_err());
+        s.reset();
+
+        s.input_stream().sender.send(InputSignal::Interrupt).unwrap();
+        s.clear_interruptions();
+        assert!(s.solve().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_hints() {
+        use crate::model::extensions::{AssignmentExt, SavedAssignment};
+        use crate::model::lang::expr::leq;
+        use crate::solver::search::conflicts::ConflictBasedBrancher;
+
+        let mut m = Model::new();
+        let x = m.new_ivar(0, 10, "x");
+        let y = m.new_ivar(0, 10, "y");
+        m.enforce(leq(y, x), []);
+
+        // partial hint: values are followed by the default and conflict-based branchers
+        let mut s = Solver::new(m.clone());
+        s.add_hint(x, 7);
+        let sol = s.solve().unwrap().unwrap();
+        assert_eq!(sol.var_domain(x).lb, 7);
+        let mut s = Solver::new(m.clone());
+        s.set_brancher(ConflictBasedBrancher::new(vec![x.geq(1), y.geq(1)]));
+        s.set_hint([(x.into(), 5), (y.into(), 4)]);
+        let sol = s.solve().unwrap().unwrap();
+        assert_eq!((sol.var_domain(x).lb, sol.var_domain(y).lb), (5, 4));
+
+        // full hint: used as the initial incumbent when optimizing
+        let mut s = Solver::new(m.clone());
+        s.set_hint([(x.into(), 8), (y.into(), 2)]);
+        let mut incumbents = Vec::new();
+        let (best, _) = s.minimize_with(x, |cost, _| incumbents.push(cost)).unwrap().unwrap();
+        assert_eq!(best, 0);
+        assert_eq!(incumbents.first(), Some(&8));
+
+        // hint on the labeled variables only: the unconstrained auxiliary variable is completed
+        let mut with_auxiliary = m.clone();
+        with_auxiliary.state.new_var(0, 10);
+        let mut s = Solver::new(with_auxiliary);
+        s.set_hint([(x.into(), 8), (y.into(), 2)]);
+        let hinted = s.hinted_solution().unwrap();
+        assert_eq!(hinted.lb(x), 8);
+
+        // invalid hint: not an incumbent
+        let mut s = Solver::new(m);
+        s.set_hint([(x.into(), 2), (y.into(), 5)]);
+        let mut incumbents = Vec::new();
+        let on_solution = |_, sol: &SavedAssignment| incumbents.push((sol.var_domain(x).lb, sol.var_domain(y).lb));
+        let (best, _) = s.maximize_with(y, on_solution).unwrap().unwrap();
+        assert_eq!(best, 10);
+        assert!(incumbents.iter().all(|(x, y)| y <= x));
+    }
+
+    #[test]
+    fn test_pareto_front() {
+        use crate::model::lang::linear::LinearSum;
+        use itertools::Itertools;
+
+        // x + y >= 8, with x and y in [0, 6]: the front is (2, 6), (3, 5), ..., (6, 2)
+        let mut m = Model::new();
+        let x = m.new_ivar(0, 6, "x");
+        let y = m.new_ivar(0, 6, "y");
+        m.enforce((LinearSum::from(x) + y).geq(8), []);
+        let mut s = Solver::new(m);
+        let mut found = Vec::new();
+        let mut proven = Vec::new();
+        let front = s
+            .pareto_front(&[x.into(), y.into()], |p, is_proven| {
+                let values = (p.objectives[0], p.objectives[1]);
+                if is_proven {
+                    proven.push(values)
+                } else {
+                    found.push(values)
+                }
+            })
+            .unwrap();
+        let mut points = front.iter().map(|p| (p.objectives[0], p.objectives[1])).collect_vec();
+        points.sort();
+        assert_eq!(points, (2..=6).map(|i| (i, 8 - i)).collect_vec());
+        proven.sort();
+        assert_eq!(proven, points);
+        assert!(points.iter().all(|p| found.contains(p)));
+    }
+
+    #[test]
+    fn test_reified_max() {
+        use crate::core::IntCst;
+        use crate::model::lang::max::{EqMax, EqMin};
+        use crate::model::lang::IAtom;
+        use itertools::Itertools;
+
+        for use_max in [true, false] {
+            let mut m = Model::new();
+            let x = m.new_ivar(0, 3, "x");
+            let y = m.new_ivar(0, 3, "y");
+            let z = m.new_ivar(0, 3, "z");
+            let value = if use_max {
+                m.reify(EqMax::new(z, [IAtom::from(x), y + 1]))
+            } else {
+                m.reify(EqMin::new(z, [IAtom::from(x), y + 1]))
+            };
+            let mut s = Solver::new(m);
+            let mut solutions = s.enumerate(&[x.into(), y.into(), z.into(), value.variable()]).unwrap();
+            solutions.sort();
+
+            let expected = (0..=3)
+                .cartesian_product(0..=3)
+                .cartesian_product(0..=3)
+                .map(|((x, y), z): ((IntCst, IntCst), IntCst)| {
+                    let expected = if use_max { x.max(y + 1) } else { x.min(y + 1) };
+                    vec![x, y, z, (z == expected) as IntCst]
+                })
+                .sorted()
+                .collect_vec();
+            assert_eq!(solutions, expected);
+        }
+    }
+
+    #[test]
+    fn test_reified_alternative() {
+        use crate::core::IntCst;
+        use crate::model::lang::expr::alternative;
+        use crate::model::lang::IAtom;
+        use itertools::Itertools;
+
+        let mut m = Model::new();
+        let px = m.new_presence_variable(Lit::TRUE, "px").true_lit();
+        let py = m.new_presence_variable(Lit::TRUE, "py").true_lit();
+        let x = m.new_optional_ivar(0, 2, px, "x");
+        let y = m.new_optional_ivar(0, 2, py, "y");
+        let z = m.new_ivar(0, 3, "z");
+        let value = m.reify(alternative(z, [IAtom::from(x), y + 1]));
+        let vars = [
+            px.variable(),
+            x.into(),
+            py.variable(),
+            y.into(),
+            z.into(),
+            value.variable(),
+        ];
+        let mut s = Solver::new(m);
+        let mut solutions = s
+            .enumerate(&vars)
+            .unwrap()
+            .into_iter()
+            .map(|mut sol| {
+                // ignore the value of absent variables
+                for i in [0, 2] {
+                    if sol[i] == 0 {
+                        sol[i + 1] = 0;
+                    }
+                }
+                sol
+            })
+            .collect_vec();
+        solutions.sort();
+        solutions.dedup();
+
+        let options = |shift: IntCst| [None, Some(shift), Some(1 + shift), Some(2 + shift)];
+        let mut expected = Vec::new();
+        for (x, y) in options(0).into_iter().cartesian_product(options(1)) {
+            for z in 0..=3 {
+                let holds = match (x, y) {
+                    (Some(v), None) | (None, Some(v)) => v == z,
+                    _ => false,
+                };
+                expected.push(vec![
+                    x.is_some() as IntCst,
+                    x.unwrap_or(0),
+                    y.is_some() as IntCst,
+                    y.map_or(0, |y| y - 1),
+                    z,
+                    holds as IntCst,
+                ]);
+            }
+        }
+        expected.sort();
+        assert_eq!(solutions, expected);
+    }
+
+    #[test]
+    fn test_enumerate_absent() {
+        let mut m = Model::new();
+        let p = m.new_presence_variable(Lit::TRUE, "p").true_lit();
+        let x = m.new_optional_ivar(0, 5, p, "x");
+        let y = m.new_ivar(0, 1, "y");
+        m.enforce(!p, []);
+        let mut s = Solver::new(m);
+        let solutions = s.enumerate(&[p.variable(), x.into(), y.into()]).unwrap();
+        // the value of the absent variable does not distinguish solutions
+        assert!(solutions.iter().all(|sol| sol[0] == 0));
+        let mut ys = solutions.iter().map(|sol| sol[2]).collect::<Vec<_>>();
+        ys.sort();
+        assert_eq!(ys, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_alternative_bounds() {
+        use crate::model::lang::expr::alternative;
+        use crate::model::lang::IAtom;
+
+        let mut m = Model::new();
+        let px = m.new_presence_variable(Lit::TRUE, "px").true_lit();
+        let py = m.new_presence_variable(Lit::TRUE, "py").true_lit();
+        let x = m.new_optional_ivar(5, 8, px, "x");
+        let y = m.new_optional_ivar(3, 6, py, "y");
+        let z = m.new_ivar(0, 20, "z");
+        m.enforce(alternative(z, [IAtom::from(x), y + 2]), []);
+        let mut s = Solver::new(m);
+        s.propagate().unwrap();
+        // both alternatives are in [5, 8], whichever is present
+        assert_eq!(s.model.state.value(px), None);
+        assert_eq!(s.model.state.value(py), None);
+        assert_eq!(s.model.state.bounds(z.into()), (5, 8));
+    }
+    #[test]
+    fn test_chronological_backtracking() {
+        use crate::model::lang::expr::{leq, neq};
+        use crate::model::lang::IVar;
+        use itertools::Itertools;
+
+        /// N-queens problem, with one variable per column giving the row of its queen.
+        fn queens(n: i32) -> (Model, Vec<IVar>) {
+            let mut m = Model::new();
+            let qs = (0..n).map(|_| m.new_ivar(0, n - 1, "q")).collect_vec();
+            for (i, &a) in qs.iter().enumerate() {
+                for (j, &b) in qs.iter().enumerate().skip(i + 1) {
+                    let d = (j - i) as i32;
+                    m.enforce(neq(a, b), []);
+                    m.enforce(neq(a + d, b), []);
+                    m.enforce(neq(a - d, b), []);
+                }
+            }
+            (m, qs)
+        }
+
+        for threshold in [None, Some(0), Some(2)] {
+            let (m, qs) = queens(8);
+            let vars = qs.iter().map(|&q| q.into()).collect_vec();
+            let mut s = Solver::new(m.clone());
+            s.chronological_backtracking = threshold;
+            assert_eq!(s.enumerate(&vars).unwrap().len(), 92);
+
+            // lowest row of the first queen when it is at least two rows above the second one
+            let mut m = m;
+            m.enforce(leq(qs[1] + 2, qs[0]), []);
+            let mut s = Solver::new(m);
+            s.chronological_backtracking = threshold;
+            let (cost, _) = s.minimize(qs[0]).unwrap().unwrap();
+            assert_eq!(cost, 2);
+
+            // pigeon-hole problem with 7 pigeons and 6 holes
+            let mut m = Model::new();
+            let pigeons = (0..7).map(|_| m.new_ivar(0, 5, "p")).collect_vec();
+            for (a, b) in pigeons.iter().tuple_combinations() {
+                m.enforce(neq(*a, *b), []);
+            }
+            let mut s = Solver::new(m);
+            s.chronological_backtracking = threshold;
+            assert!(s.solve().unwrap().is_none());
+            if threshold == Some(0) {
+                // some out-of-order literals were undone and propagated again
+                assert!(s.reasoners.sat.stats()["reassertions"] > 0);
+            }
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::backtrack::DecLvl;
 use crate::core::{IntCst, Lit};
-use crate::reasoners::ReasonerId;
 use crate::reasoners::REASONERS;
+use crate::reasoners::{ReasonerId, TheoryStats, SIZE_COUNTERS};
 use crate::utils::cpu_time::*;
 use env_param::EnvParam;
 use std::collections::BTreeMap;
@@ -105,6 +105,135 @@ impl Stats {
     pub fn num_conflicts(&self) -> u64 {
         self.num_conflicts
     }
+
+    /// Returns a serializable snapshot of the statistics.
+    /// The counters of each reasoner (`ReasonerStats::counters`) are left empty and should be filled
+    /// by the caller that has access to the reasoners (see `Solver::stats_report`).
+    pub fn report(&self) -> StatsReport {
+        StatsReport {
+            solutions: self.num_solutions,
+            restarts: self.num_restarts,
+            decisions: self.num_decisions,
+            conflicts: self.num_conflicts,
+            init_time: self.init_time.as_secs_f64(),
+            solve_time: self.solve_time.as_secs_f64(),
+            propagation_cycles: self.propagation_time.count(),
+            best_cost: self.best_cost,
+            reasoners: self
+                .per_module_stat
+                .iter()
+                .map(|(id, ms)| {
+                    let stats = ReasonerStats {
+                        propagation_loops: ms.propagation_loops,
+                        conflicts: ms.conflicts,
+                        propagation_cycles: ms.propagation_time.count(),
+                        counters: TheoryStats::new(),
+                    };
+                    (id.to_string(), stats)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Machine-readable statistics of a solver, meant to be serialized (e.g. to JSON) for benchmarking purposes.
+/// All times are in seconds.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsReport {
+    pub solutions: u64,
+    pub restarts: u64,
+    pub decisions: u64,
+    pub conflicts: u64,
+    pub init_time: f64,
+    pub solve_time: f64,
+    /// CPU cycles spent in propagation, only available on platforms supporting CPU timing.
+    pub propagation_cycles: Option<u64>,
+    /// Cost of the best solution found, if any.
+    pub best_cost: Option<IntCst>,
+    /// Statistics of each reasoner, indexed by its name (as displayed by `ReasonerId`).
+    pub reasoners: BTreeMap<String, ReasonerStats>,
+}
+
+/// Machine-readable statistics of a single reasoner.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReasonerStats {
+    pub propagation_loops: u64,
+    pub conflicts: u64,
+    pub propagation_cycles: Option<u64>,
+    /// Reasoner specific counters (e.g. clause database size for SAT, propagations for STN and CP).
+    pub counters: TheoryStats,
+}
+
+impl StatsReport {
+    /// Flattens the report into a map of `key -> value` where all values are formatted as strings.
+    /// Keys of reasoner statistics are of the form `<reasoner>.<stat>` (e.g. `SAT.db_size`).
+    ///
+    /// This is intended to fill string-based metric maps, such as the one of the unified-planning protocol.
+    pub fn flatten(&self) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::new();
+        map.insert("solutions".to_string(), self.solutions.to_string());
+        map.insert("restarts".to_string(), self.restarts.to_string());
+        map.insert("decisions".to_string(), self.decisions.to_string());
+        map.insert("conflicts".to_string(), self.conflicts.to_string());
+        map.insert("init_time".to_string(), format!("{:.6}", self.init_time));
+        map.insert("solve_time".to_string(), format!("{:.6}", self.solve_time));
+        if let Some(cycles) = self.propagation_cycles {
+            map.insert("propagation_cycles".to_string(), cycles.to_string());
+        }
+        if let Some(cost) = self.best_cost {
+            map.insert("best_cost".to_string(), cost.to_string());
+        }
+        for (name, rs) in &self.reasoners {
+            map.insert(format!("{name}.propagation_loops"), rs.propagation_loops.to_string());
+            map.insert(format!("{name}.conflicts"), rs.conflicts.to_string());
+            if let Some(cycles) = rs.propagation_cycles {
+                map.insert(format!("{name}.propagation_cycles"), cycles.to_string());
+            }
+            for (counter, value) in &rs.counters {
+                map.insert(format!("{name}.{counter}"), value.to_string());
+            }
+        }
+        map
+    }
+}
+
+impl std::ops::AddAssign<&StatsReport> for StatsReport {
+    /// Accumulates the counters of another report into this one (e.g. to sum the statistics of several solvers).
+    /// The best cost is not accumulated.
+    ///
+    /// Reasoner specific counters are summed, except for the sizes of data structures (see [`SIZE_COUNTERS`])
+    /// for which the largest value is kept.
+    fn add_assign(&mut self, rhs: &StatsReport) {
+        fn add_opt(a: &mut Option<u64>, b: Option<u64>) {
+            *a = match (*a, b) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            }
+        }
+        self.solutions += rhs.solutions;
+        self.restarts += rhs.restarts;
+        self.decisions += rhs.decisions;
+        self.conflicts += rhs.conflicts;
+        self.init_time += rhs.init_time;
+        self.solve_time += rhs.solve_time;
+        add_opt(&mut self.propagation_cycles, rhs.propagation_cycles);
+        for (name, rs) in &rhs.reasoners {
+            let acc = self.reasoners.entry(name.clone()).or_default();
+            acc.propagation_loops += rs.propagation_loops;
+            acc.conflicts += rs.conflicts;
+            add_opt(&mut acc.propagation_cycles, rs.propagation_cycles);
+            for (counter, value) in &rs.counters {
+                let acc_value = acc.counters.entry(counter.clone()).or_default();
+                if SIZE_COUNTERS.contains(&counter.as_str()) {
+                    *acc_value = (*acc_value).max(*value);
+                } else {
+                    *acc_value += *value;
+                }
+            }
+        }
+    }
 }
 
 impl Default for Stats {
@@ -232,3 +361,104 @@ impl IndexMut<ReasonerId> for Stats {
         self.per_module_stat.get_mut(&index).unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::model::lang::expr::or;
+    use crate::reasoners::SIZE_COUNTERS;
+    use crate::solver::stats::StatsReport;
+
+    type Model = crate::model::Model<&'static str>;
+    type Solver = crate::solver::Solver<&'static str>;
+
+    #[test]
+    fn test_stats_report() {
+        let mut model = Model::new();
+        let a = model.new_bvar("a").true_lit();
+        let b = model.new_bvar("b").true_lit();
+        model.enforce(or([a, b]), []);
+        model.enforce(or([!a, b]), []);
+        let mut solver = Solver::new(model);
+        assert!(solver.solve().unwrap().is_some());
+
+        let report = solver.stats_report();
+        assert_eq!(report.conflicts, solver.stats.num_conflicts);
+        assert_eq!(report.reasoners["SAT"].counters["db_size"], 2);
+        assert!(report.reasoners["DiffLog"].counters.contains_key("propagations"));
+        assert!(report.reasoners["CP"].counters.contains_key("constraints"));
+
+        // round trip through JSON
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&report).unwrap();
+            let parsed: StatsReport = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, report);
+        }
+
+        let flat = report.flatten();
+        assert_eq!(flat["SAT.db_size"], "2");
+
+        let mut total = report.clone();
+        total += &report;
+        assert_eq!(total.decisions, 2 * report.decisions);
+        // reasoner counters may be gauges and are not summed
+        assert_eq!(total.reasoners["SAT"].counters["db_size"], 2);
+    }
+
+    #[test]
+    fn test_merge_stats_reports() {
+        use crate::model::lang::expr::leq;
+
+        let mut small = Model::new();
+        let a = small.new_bvar("a").true_lit();
+        small.enforce(or([a]), []);
+        let mut small = Solver::new(small);
+        assert!(small.solve().unwrap().is_some());
+        let small = small.stats_report();
+
+        let mut large = Model::new();
+        let x = large.new_ivar(0, 10, "x");
+        let y = large.new_ivar(0, 10, "y");
+        let b = large.new_bvar("b").true_lit();
+        let c = large.new_bvar("c").true_lit();
+        large.enforce(leq(x + 1, y), []);
+        large.enforce(or([b, c]), []);
+        large.enforce(or([!b, c]), []);
+        let mut large = Solver::new(large);
+        assert!(large.solve().unwrap().is_some());
+        let large = large.stats_report();
+
+        let mut total = small.clone();
+        total += &large;
+        assert_eq!(total.decisions, small.decisions + large.decisions);
+        for (name, rs) in &total.reasoners {
+            for (counter, &value) in &rs.counters {
+                let get = |report: &StatsReport| {
+                    report
+                        .reasoners
+                        .get(name)
+                        .and_then(|r| r.counters.get(counter))
+                        .copied()
+                        .unwrap_or(0)
+                };
+                let (s, l) = (get(&small), get(&large));
+                if SIZE_COUNTERS.contains(&counter.as_str()) {
+                    assert_eq!(value, s.max(l), "{name}.{counter}");
+                } else {
+                    assert_eq!(value, s + l, "{name}.{counter}");
+                }
+            }
+        }
+        // the sizes of the clause databases are not summed, but their unit propagations are
+        let sat = |r: &StatsReport, counter: &str| r.reasoners["SAT"].counters[counter];
+        assert_eq!(
+            sat(&total, "db_size"),
+            sat(&small, "db_size").max(sat(&large, "db_size"))
+        );
+        assert!(sat(&small, "unit_propagations") > 0 && sat(&large, "unit_propagations") > 0);
+        assert_eq!(
+            sat(&total, "unit_propagations"),
+            sat(&small, "unit_propagations") + sat(&large, "unit_propagations")
+        );
+    }
+}
@@ -0,0 +1,630 @@
+//! Structured trace of the search, intended for offline analysis and visualization.
+//!
+//! A `TraceSink` can be attached to a solver with `Solver::set_trace_sink`, after which it will be notified
+//! of every decision, propagation, conflict, backjump, restart and solution of the search.
+//! The [`JsonlTrace`] sink writes one JSON object per line, which can be read back with [`read_jsonl`] and
+//! exported to a standalone HTML view of the search tree with [`SearchTree::write_html`].
+//!
+//! Setting the `ARIES_TRACE_FILE` environment variable automatically attaches a `JsonlTrace` to every
+//! newly created solver, all of them writing to the same file.
+//!
+//! The JSONL format requires the `serde` feature. Without it, only in-memory traces are available.
+
+use crate::core::IntCst;
+use env_param::EnvParam;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+#[cfg(feature = "serde")]
+use std::fs::File;
+use std::io::Write;
+#[cfg(feature = "serde")]
+use std::io::{BufRead, BufWriter};
+#[cfg(feature = "serde")]
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "serde")]
+use std::sync::OnceLock;
+use std::sync::{Arc, Mutex};
+
+/// If set to a non-empty path, the search of all solvers will be traced to this file in the JSONL format.
+pub static TRACE_FILE: EnvParam<String> = EnvParam::new("ARIES_TRACE_FILE", "");
+
+/// An event of the search.
+///
+/// Literals are represented by their human readable form, which contains the labels of the variables, if any.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "kebab-case"))]
+pub enum TraceEvent {
+    /// A decision that opens the decision level `level`.
+    Decision { level: u32, lit: String },
+    /// A propagation loop of a reasoner that resulted in `inferences` domain updates.
+    Propagation {
+        level: u32,
+        reasoner: String,
+        inferences: u64,
+    },
+    /// A conflict detected by `reasoner` at `level`, from which the given clause was learnt.
+    Conflict {
+        level: u32,
+        reasoner: String,
+        clause: Vec<String>,
+        lbd: u32,
+    },
+    /// Backtracking from decision level `from` to decision level `to`, after a conflict.
+    Backjump { from: u32, to: u32 },
+    /// The search was restarted from decision level `level`.
+    Restart { level: u32 },
+    /// A solution was found by search, with the value of each present labeled variable.
+    Solution {
+        level: u32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        assignment: BTreeMap<String, IntCst>,
+    },
+    /// A new best solution with the given objective value was found (optimization only).
+    Incumbent { objective: IntCst },
+}
+
+/// A line of a trace: an event together with the identifier of the solver that emitted it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceRecord {
+    pub solver: u32,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub event: TraceEvent,
+}
+
+/// A recipient for the events of the search.
+pub trait TraceSink: Send {
+    fn record(&mut self, event: TraceEvent);
+
+    /// Creates a sink for a clone of the solver.
+    /// Implementations typically share their output with the original sink, but give it a distinct solver identifier.
+    fn clone_box(&self) -> Box<dyn TraceSink>;
+}
+
+/// Source of solver identifiers, shared by all sinks of the process.
+static NEXT_SOLVER_ID: AtomicU32 = AtomicU32::new(0);
+
+fn next_solver_id() -> u32 {
+    NEXT_SOLVER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A sink that writes each event as a JSON object on its own line.
+///
+/// Clones of the sink (e.g. created for the workers of a parallel solver) write to the same output.
+#[cfg(feature = "serde")]
+pub struct JsonlTrace {
+    solver: u32,
+    output: SharedOutput,
+}
+
+#[cfg(feature = "serde")]
+type SharedOutput = Arc<Mutex<Box<dyn Write + Send>>>;
+
+#[cfg(feature = "serde")]
+impl JsonlTrace {
+    pub fn new(output: impl Write + Send + 'static) -> Self {
+        JsonlTrace {
+            solver: next_solver_id(),
+            output: Arc::new(Mutex::new(Box::new(output))),
+        }
+    }
+
+    /// Creates a sink that writes to a new file at the given path.
+    pub fn to_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Creates a sink from the `ARIES_TRACE_FILE` environment variable, if it is set.
+    ///
+    /// The file is created once per process: all sinks created by this method write to the same file,
+    /// each with its own solver identifier. If the file cannot be created, the error is reported once
+    /// and no sink is returned.
+    pub fn from_env() -> Option<Self> {
+        static OUTPUT: OnceLock<Option<SharedOutput>> = OnceLock::new();
+        let output = OUTPUT.get_or_init(|| {
+            let path = TRACE_FILE.get_ref();
+            if path.is_empty() {
+                return None;
+            }
+            match File::create(path) {
+                Ok(file) => Some(Arc::new(Mutex::new(Box::new(BufWriter::new(file))))),
+                Err(e) => {
+                    tracing::error!("Could not create trace file {path}: {e}");
+                    None
+                }
+            }
+        });
+        output.as_ref().map(|output| JsonlTrace {
+            solver: next_solver_id(),
+            output: output.clone(),
+        })
+    }
+
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.output.lock().unwrap().flush()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TraceSink for JsonlTrace {
+    fn record(&mut self, event: TraceEvent) {
+        let record = TraceRecord {
+            solver: self.solver,
+            event,
+        };
+        let mut line = serde_json::to_string(&record).expect("Could not serialize trace event");
+        line.push('\n');
+        // an IO error should not interrupt the search
+        let _ = self.output.lock().unwrap().write_all(line.as_bytes());
+    }
+
+    fn clone_box(&self) -> Box<dyn TraceSink> {
+        Box::new(JsonlTrace {
+            solver: next_solver_id(),
+            output: self.output.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Drop for JsonlTrace {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// A sink that keeps all records in memory. Clones share the same buffer.
+#[derive(Clone)]
+pub struct MemoryTrace {
+    solver: u32,
+    pub records: Arc<Mutex<Vec<TraceRecord>>>,
+}
+
+impl MemoryTrace {
+    pub fn new() -> Self {
+        MemoryTrace {
+            solver: next_solver_id(),
+            records: Default::default(),
+        }
+    }
+
+    /// Returns a copy of all records so far.
+    pub fn records(&self) -> Vec<TraceRecord> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+impl Default for MemoryTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceSink for MemoryTrace {
+    fn record(&mut self, event: TraceEvent) {
+        let record = TraceRecord {
+            solver: self.solver,
+            event,
+        };
+        self.records.lock().unwrap().push(record)
+    }
+
+    fn clone_box(&self) -> Box<dyn TraceSink> {
+        Box::new(MemoryTrace {
+            solver: next_solver_id(),
+            records: self.records.clone(),
+        })
+    }
+}
+
+/// Sink of a newly created solver, as requested by the `ARIES_TRACE_FILE` environment variable.
+/// Without the `serde` feature, the variable is ignored with a warning.
+pub(crate) fn sink_from_env() -> Option<Box<dyn TraceSink>> {
+    #[cfg(feature = "serde")]
+    return JsonlTrace::from_env().map(|t| Box::new(t) as Box<dyn TraceSink>);
+    #[cfg(not(feature = "serde"))]
+    {
+        static WARNING: std::sync::Once = std::sync::Once::new();
+        if !TRACE_FILE.get_ref().is_empty() {
+            WARNING
+                .call_once(|| tracing::warn!("ARIES_TRACE_FILE is ignored: aries was built without the serde feature"));
+        }
+        None
+    }
+}
+
+/// Reads all records of trace in the JSONL format.
+#[cfg(feature = "serde")]
+pub fn read_jsonl(input: impl BufRead) -> anyhow::Result<Vec<TraceRecord>> {
+    let mut records = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line).map_err(|e| anyhow::anyhow!("Line {}: {e}", i + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Status of a node of the search tree.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NodeStatus {
+    /// Node with at least one child or whose exploration was interrupted.
+    Branch,
+    /// A conflict was detected in this node.
+    Failure,
+    /// A solution was found in this node.
+    Solution,
+}
+
+/// A node of the search tree, whose children are the decisions taken from it.
+#[derive(Clone, Debug)]
+pub struct Node {
+    /// Decision that led to this node (None for the root)
+    pub decision: Option<String>,
+    pub status: NodeStatus,
+    /// Number of inferences of each reasoner while in this node.
+    pub inferences: BTreeMap<String, u64>,
+    /// Clauses learnt from the conflicts in this node.
+    pub learnt: Vec<Vec<String>>,
+    /// Assignment of the labeled variables in the solution found in this node, if any.
+    pub solution: Option<BTreeMap<String, IntCst>>,
+    pub children: Vec<usize>,
+}
+
+impl Node {
+    fn new(decision: Option<String>) -> Self {
+        Node {
+            decision,
+            status: NodeStatus::Branch,
+            inferences: Default::default(),
+            learnt: Vec::new(),
+            solution: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Search tree reconstructed from the trace of a single solver.
+///
+/// Each restart starts a new subtree of the root.
+pub struct SearchTree {
+    pub solver: u32,
+    /// All nodes of the tree, the first one being the root.
+    pub nodes: Vec<Node>,
+    pub num_restarts: u32,
+    pub num_conflicts: u32,
+    pub num_solutions: u32,
+}
+
+impl SearchTree {
+    /// Builds a search tree for each solver appearing in the trace.
+    pub fn build_all(records: &[TraceRecord]) -> Vec<SearchTree> {
+        let mut solvers = records.iter().map(|r| r.solver).collect::<Vec<_>>();
+        solvers.sort();
+        solvers.dedup();
+        solvers
+            .into_iter()
+            .map(|s| Self::build(s, records.iter().filter(|r| r.solver == s).map(|r| &r.event)))
+            .collect()
+    }
+
+    /// Builds the search tree of a solver from its events.
+    pub fn build<'a>(solver: u32, events: impl IntoIterator<Item = &'a TraceEvent>) -> SearchTree {
+        let mut tree = SearchTree {
+            solver,
+            nodes: vec![Node::new(None)],
+            num_restarts: 0,
+            num_conflicts: 0,
+            num_solutions: 0,
+        };
+        // stack of nodes, where the node at index `i` is the one at decision level `i`.
+        // Note that levels opened by assumptions (no decision event) are associated to their parent node.
+        let mut stack: Vec<usize> = vec![0];
+        let current = |stack: &Vec<usize>, level: u32| stack[(level as usize).min(stack.len() - 1)];
+
+        for event in events {
+            match event {
+                TraceEvent::Decision { level, lit } => {
+                    let parent = current(&stack, level.saturating_sub(1));
+                    stack.truncate(*level as usize);
+                    while stack.len() < *level as usize {
+                        stack.push(parent);
+                    }
+                    let id = tree.nodes.len();
+                    tree.nodes.push(Node::new(Some(lit.clone())));
+                    tree.nodes[parent].children.push(id);
+                    stack.push(id);
+                }
+                TraceEvent::Propagation {
+                    level,
+                    reasoner,
+                    inferences,
+                } => {
+                    let node = &mut tree.nodes[current(&stack, *level)];
+                    *node.inferences.entry(reasoner.clone()).or_default() += inferences;
+                }
+                TraceEvent::Conflict { level, clause, .. } => {
+                    tree.num_conflicts += 1;
+                    let node = &mut tree.nodes[current(&stack, *level)];
+                    node.status = NodeStatus::Failure;
+                    node.learnt.push(clause.clone());
+                }
+                TraceEvent::Backjump { to, .. } => stack.truncate(*to as usize + 1),
+                TraceEvent::Restart { .. } => {
+                    tree.num_restarts += 1;
+                    stack.truncate(1);
+                }
+                TraceEvent::Solution { level, assignment } => {
+                    tree.num_solutions += 1;
+                    let node = &mut tree.nodes[current(&stack, *level)];
+                    node.status = NodeStatus::Solution;
+                    node.solution = Some(assignment.clone());
+                }
+                TraceEvent::Incumbent { .. } => {}
+            }
+        }
+        tree
+    }
+
+    /// Writes a standalone HTML page with a collapsible view of all given search trees.
+    pub fn write_html(trees: &[SearchTree], out: &mut impl Write) -> std::io::Result<()> {
+        let mut html = String::new();
+        html.push_str(HTML_HEADER);
+        for tree in trees {
+            let _ = writeln!(
+                html,
+                "<h2>Solver {}</h2><p>{} nodes, {} conflicts, {} restarts, {} solutions</p>",
+                tree.solver,
+                tree.nodes.len(),
+                tree.num_conflicts,
+                tree.num_restarts,
+                tree.num_solutions
+            );
+            html.push_str("<ul class=\"tree\">");
+            tree.write_nodes(&mut html);
+            html.push_str("</ul>");
+        }
+        html.push_str("</body></html>\n");
+        out.write_all(html.as_bytes())
+    }
+
+    /// Writes all nodes of the tree, in depth-first order.
+    /// An explicit stack is used because the depth of the tree is only bounded by the number of decisions.
+    fn write_nodes(&self, html: &mut String) {
+        enum Step {
+            Open(usize),
+            Close { has_children: bool },
+        }
+        let mut stack = vec![Step::Open(0)];
+        while let Some(step) = stack.pop() {
+            let id = match step {
+                Step::Open(id) => id,
+                Step::Close { has_children } => {
+                    if has_children {
+                        html.push_str("</ul>");
+                    }
+                    html.push_str("</details></li>");
+                    continue;
+                }
+            };
+            let node = &self.nodes[id];
+            let class = match node.status {
+                NodeStatus::Branch => "branch",
+                NodeStatus::Failure => "failure",
+                NodeStatus::Solution => "solution",
+            };
+            let label = node.decision.as_deref().unwrap_or("ROOT");
+            let inferences = node
+                .inferences
+                .iter()
+                .map(|(reasoner, n)| format!("{reasoner}: {n}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            html.push_str("<li><details open><summary>");
+            let _ = write!(
+                html,
+                "<span class=\"{class}\">{}</span> <span class=\"info\">{}</span>",
+                escape(label),
+                escape(&inferences)
+            );
+            html.push_str("</summary>");
+            for clause in &node.learnt {
+                let _ = write!(
+                    html,
+                    "<div class=\"learnt\">learnt: {}</div>",
+                    escape(&clause.join(" | "))
+                );
+            }
+            if let Some(solution) = &node.solution {
+                let values = solution
+                    .iter()
+                    .map(|(var, value)| format!("{var} = {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = write!(html, "<div class=\"info\">solution: {}</div>", escape(&values));
+            }
+            let has_children = !node.children.is_empty();
+            if has_children {
+                html.push_str("<ul>");
+            }
+            stack.push(Step::Close { has_children });
+            stack.extend(node.children.iter().rev().map(|&child| Step::Open(child)));
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>Aries search tree</title>
+<style>
+body { font-family: monospace; }
+ul.tree, ul.tree ul { list-style: none; padding-left: 1.5em; border-left: 1px dotted #aaa; }
+.failure { color: #c00; }
+.solution { color: #080; font-weight: bold; }
+.info { color: #888; font-size: 0.8em; }
+.learnt { color: #a60; font-size: 0.8em; padding-left: 1.5em; }
+</style></head><body>
+<h1>Search tree</h1>
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_tree() {
+        let events = vec![
+            TraceEvent::Decision {
+                level: 1,
+                lit: "a".to_string(),
+            },
+            TraceEvent::Propagation {
+                level: 1,
+                reasoner: "SAT".to_string(),
+                inferences: 3,
+            },
+            TraceEvent::Decision {
+                level: 2,
+                lit: "b".to_string(),
+            },
+            TraceEvent::Conflict {
+                level: 2,
+                reasoner: "SAT".to_string(),
+                clause: vec!["!b".to_string()],
+                lbd: 1,
+            },
+            TraceEvent::Backjump { from: 2, to: 0 },
+            TraceEvent::Decision {
+                level: 1,
+                lit: "c".to_string(),
+            },
+            TraceEvent::Solution {
+                level: 1,
+                assignment: [("x".to_string(), 3)].into(),
+            },
+        ];
+        let tree = SearchTree::build(0, &events);
+        assert_eq!(tree.nodes.len(), 4);
+        assert_eq!(tree.nodes[0].children, vec![1, 3]);
+        assert_eq!(tree.nodes[1].children, vec![2]);
+        assert_eq!(tree.nodes[1].inferences["SAT"], 3);
+        assert_eq!(tree.nodes[2].status, NodeStatus::Failure);
+        assert_eq!(tree.nodes[3].status, NodeStatus::Solution);
+
+        // round trip through the JSONL format
+        let records = events
+            .iter()
+            .map(|e| TraceRecord {
+                solver: 7,
+                event: e.clone(),
+            })
+            .collect::<Vec<_>>();
+        #[cfg(feature = "serde")]
+        {
+            let jsonl = records
+                .iter()
+                .map(|r| serde_json::to_string(r).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let parsed = read_jsonl(jsonl.as_bytes()).unwrap();
+            assert_eq!(parsed, records);
+        }
+
+        let mut html = Vec::new();
+        SearchTree::write_html(&SearchTree::build_all(&records), &mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.contains("Solver 7"));
+        assert!(html.contains("learnt: !b"));
+        assert!(html.contains("solution: x = 3"));
+    }
+
+    #[test]
+    fn test_deep_search_tree() {
+        // a single branch, deeper than what a recursive traversal would support
+        let depth = 100_000;
+        let events = (1..=depth)
+            .map(|level| TraceEvent::Decision {
+                level,
+                lit: format!("d{level}"),
+            })
+            .collect::<Vec<_>>();
+        let tree = SearchTree::build(0, &events);
+        assert_eq!(tree.nodes.len(), depth as usize + 1);
+        let mut html = Vec::new();
+        SearchTree::write_html(&[tree], &mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert_eq!(html.matches("<li>").count(), depth as usize + 1);
+        assert!(html.contains(&format!("d{depth}")));
+    }
+
+    #[test]
+    fn test_solver_trace() {
+        use crate::model::lang::expr::or;
+        use crate::solver::search::lexical::Lexical;
+        type Model = crate::model::Model<String>;
+        type Solver = crate::solver::Solver<String>;
+
+        // pigeon hole problem with 3 pigeons and 2 holes
+        let mut model = Model::new();
+        let x = (0..3)
+            .map(|p| {
+                (0..2)
+                    .map(|h| model.new_bvar(format!("p{p}h{h}")).true_lit())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for p in &x {
+            model.enforce(or(p.as_slice()), []);
+        }
+        for (i, p1) in x.iter().enumerate() {
+            for p2 in &x[i + 1..] {
+                for h in 0..2 {
+                    model.enforce(or([!p1[h], !p2[h]]), []);
+                }
+            }
+        }
+        let trace = MemoryTrace::new();
+        let mut solver = Solver::new(model);
+        solver.set_brancher(Lexical::with_max());
+        solver.set_trace_sink(trace.clone());
+        assert!(solver.solve().unwrap().is_none());
+
+        let records = trace.records();
+        assert!(records.iter().all(|r| r.solver == records[0].solver));
+        let events = records.iter().map(|r| &r.event).collect::<Vec<_>>();
+        assert!(matches!(events[0], TraceEvent::Decision { level: 1, lit } if lit == "p0h0"));
+        assert!(events.iter().any(|e| matches!(e, TraceEvent::Propagation { .. })));
+        assert!(events.iter().any(|e| matches!(e, TraceEvent::Conflict { .. })));
+        assert!(events.iter().any(|e| matches!(e, TraceEvent::Backjump { .. })));
+        assert!(!events.iter().any(|e| matches!(e, TraceEvent::Solution { .. })));
+
+        let tree = SearchTree::build(records[0].solver, events);
+        assert!(tree.num_conflicts > 0);
+        assert!(tree.nodes.iter().any(|n| n.status == NodeStatus::Failure));
+
+        // the solution of a satisfiable problem is recorded with the value of its labeled variables
+        let mut model = Model::new();
+        let a = model.new_bvar("a".to_string()).true_lit();
+        model.new_ivar(2, 5, "x".to_string());
+        model.enforce(a, []);
+        let trace = MemoryTrace::new();
+        let mut solver = Solver::new(model);
+        solver.set_brancher(Lexical::with_min());
+        solver.set_trace_sink(trace.clone());
+        assert!(solver.solve().unwrap().is_some());
+        let solution = trace.records().into_iter().find_map(|r| match r.event {
+            TraceEvent::Solution { assignment, .. } => Some(assignment),
+            _ => None,
+        });
+        let expected = [("a".to_string(), 1), ("x".to_string(), 2)];
+        assert_eq!(solution, Some(expected.into()));
+    }
+}